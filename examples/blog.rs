@@ -0,0 +1,58 @@
+//! Writes a blog sitemap with the top page, the archive pages, and the posts.
+//!
+//! ```console
+//! $ cargo run --example blog
+//! ```
+use sitemap_xml_writer::{Changefreq, SitemapWriter, Url};
+use std::{fs::File, io::BufWriter, path::PathBuf};
+
+const BASE_URL: &str = "http://www.example.com";
+
+fn main() -> anyhow::Result<()> {
+    let dir = output_dir("blog")?;
+    let posts = [
+        ("2005-01-01", "happy-new-year"),
+        ("2005-01-15", "a-post-with-%3Cspecial%3E-&-'characters'"),
+        ("2005-02-03", "another-post"),
+    ];
+
+    let path = dir.join("sitemap.xml");
+    let mut writer = SitemapWriter::start_with_indent(BufWriter::new(File::create(&path)?))?;
+    writer.write(
+        Url::loc(format!("{}/", BASE_URL).as_str())?
+            .lastmod(posts[posts.len() - 1].0)?
+            .changefreq(Changefreq::Daily)?
+            .priority(1.0)?,
+    )?;
+    for month in ["2005-01", "2005-02"] {
+        writer.write(
+            Url::loc(format!("{}/archives/{}/", BASE_URL, month).as_str())?
+                .changefreq(Changefreq::Monthly)?,
+        )?;
+    }
+    for (date, slug) in posts {
+        writer.write(
+            Url::loc(format!("{}/{}/{}", BASE_URL, date.replace('-', "/"), slug).as_str())?
+                .lastmod(date)?
+                .changefreq(Changefreq::Never)?,
+        )?;
+    }
+    let (inner, summary) = writer.finish()?;
+    // `BufWriter::into_inner` flushes the buffer and reports a write error.
+    inner.into_inner()?;
+
+    println!(
+        "wrote {} urls to {}",
+        summary.number_of_entries(),
+        path.display()
+    );
+    Ok(())
+}
+
+fn output_dir(name: &str) -> anyhow::Result<PathBuf> {
+    let dir = std::env::temp_dir()
+        .join("sitemap-xml-writer-examples")
+        .join(name);
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
@@ -0,0 +1,58 @@
+//! Writes a product catalog split into multiple sitemap files and a sitemap
+//! index referencing them.
+//!
+//! ```console
+//! $ cargo run --example ecommerce
+//! ```
+use sitemap_xml_writer::{Changefreq, Sitemap, SitemapIndexWriter, SitemapWriter, Url};
+use std::{fs::File, io::BufWriter, path::PathBuf};
+
+const BASE_URL: &str = "http://www.example.com";
+const NUMBER_OF_PRODUCTS: usize = 120_000;
+const URLS_PER_SITEMAP: usize = 50_000;
+
+fn main() -> anyhow::Result<()> {
+    let dir = output_dir("ecommerce")?;
+
+    let mut index_writer =
+        SitemapIndexWriter::start(BufWriter::new(File::create(dir.join("sitemap.xml"))?))?;
+    let mut product_ids = 0..NUMBER_OF_PRODUCTS;
+    let mut number_of_sitemaps = 0;
+    while !product_ids.is_empty() {
+        number_of_sitemaps += 1;
+        let file_name = format!("sitemap-products-{}.xml", number_of_sitemaps);
+        let mut writer = SitemapWriter::start(BufWriter::new(File::create(dir.join(&file_name))?))?;
+        for id in product_ids.by_ref().take(URLS_PER_SITEMAP) {
+            let loc = format!("{}/products/{}?ref=sitemap&lang=en", BASE_URL, id);
+            writer.write(
+                Url::loc(loc.as_str())?
+                    .lastmod("2005-01-01")?
+                    .changefreq(Changefreq::Weekly)?
+                    .priority(0.5)?,
+            )?;
+        }
+        let (inner, _) = writer.finish()?;
+        // `BufWriter::into_inner` flushes the buffer and reports a write error.
+        inner.into_inner()?;
+
+        let loc = format!("{}/{}", BASE_URL, file_name);
+        index_writer.write(Sitemap::loc(loc.as_str())?.lastmod("2005-01-01")?)?;
+    }
+    let (inner, _) = index_writer.finish()?;
+    inner.into_inner()?;
+
+    println!(
+        "wrote {} sitemaps and a sitemap index to {}",
+        number_of_sitemaps,
+        dir.display()
+    );
+    Ok(())
+}
+
+fn output_dir(name: &str) -> anyhow::Result<PathBuf> {
+    let dir = std::env::temp_dir()
+        .join("sitemap-xml-writer-examples")
+        .join(name);
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
@@ -0,0 +1,335 @@
+use std::borrow::Cow;
+
+use crate::{
+    loc::Loc,
+    sitemap_writer::Error,
+    url_extension::{ExtensionWriter, UrlExtension},
+};
+
+type Result<T, E = crate::Error> = std::result::Result<T, E>;
+
+/// An `xhtml:link` entry with `rel="alternate"`, pointing to a localized
+/// version of the page with the `hreflang` code.
+///
+/// It is added to a `url` entry by [`crate::Url::alternate`]. The namespace
+/// must be declared by [`crate::SitemapWriterBuilder::namespace`] with
+/// [`Alternate::PREFIX`] and [`Alternate::NAMESPACE_URI`].
+///
+/// See [Tell Google about localized versions of your page](https://developers.google.com/search/docs/specialty/international/localized-versions#sitemap).
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{Alternate, SitemapWriterBuilder, Url};
+/// use std::io::Cursor;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut writer = SitemapWriterBuilder::new()
+///     .namespace(Alternate::PREFIX, Alternate::NAMESPACE_URI)
+///     .start(Cursor::new(Vec::new()))?;
+/// writer.write(
+///     Url::loc("http://www.example.com/english/page.html")?
+///         .alternate(Alternate::new("de", "http://www.example.com/deutsch/page.html")?)
+///         .alternate(Alternate::new("en", "http://www.example.com/english/page.html")?),
+/// )?;
+/// let (inner, _) = writer.finish()?;
+/// assert_eq!(
+///     String::from_utf8(inner.into_inner())?,
+///     concat!(
+///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+///         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:xhtml="http://www.w3.org/1999/xhtml">"#,
+///         r#"<url>"#,
+///         r#"<loc>http://www.example.com/english/page.html</loc>"#,
+///         r#"<xhtml:link rel="alternate" hreflang="de" href="http://www.example.com/deutsch/page.html"/>"#,
+///         r#"<xhtml:link rel="alternate" hreflang="en" href="http://www.example.com/english/page.html"/>"#,
+///         r#"</url>"#,
+///         r#"</urlset>"#
+///     )
+/// );
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Alternate<'a> {
+    pub(crate) hreflang: Cow<'a, str>,
+    pub(crate) href: Cow<'a, str>,
+}
+
+impl<'a> Alternate<'a> {
+    /// The namespace prefix of the `xhtml:link` entries.
+    pub const PREFIX: &'static str = "xhtml";
+
+    /// The namespace URI of the `xhtml:link` entries.
+    pub const NAMESPACE_URI: &'static str = "http://www.w3.org/1999/xhtml";
+
+    /// Builds an `xhtml:link` entry with the specified `hreflang` code (e.g.
+    /// `"en"`, `"de-ch"` or `"x-default"`) and the URL of the localized page.
+    ///
+    /// The `hreflang` code must be `x-default` or a well-formed BCP 47
+    /// language tag ([RFC 5646](https://www.rfc-editor.org/rfc/rfc5646)). It
+    /// returns an error of [`crate::ErrorKind::InvalidHreflang`] otherwise.
+    /// The subtags are not checked against the IANA Language Subtag Registry.
+    pub fn new<L, S>(hreflang: L, href: S) -> Result<Self>
+    where
+        L: Into<Cow<'a, str>>,
+        S: TryInto<Loc<'a>>,
+    {
+        let hreflang = hreflang.into();
+        if !(hreflang.eq_ignore_ascii_case("x-default") || is_language_tag(&hreflang)) {
+            return Err(Error::InvalidHreflang.into());
+        }
+        let href = href.try_into().map_err(|_| Error::InvalidAlternate)?;
+        Ok(Self {
+            hreflang,
+            href: href.into_inner(),
+        })
+    }
+
+    /// Returns the `hreflang` code.
+    pub fn hreflang(&self) -> &str {
+        &self.hreflang
+    }
+
+    /// Returns the URL of the localized page.
+    pub fn href(&self) -> &str {
+        &self.href
+    }
+
+    /// Converts this `Alternate` into an owned `Alternate<'static>`.
+    pub fn into_owned(self) -> Alternate<'static> {
+        Alternate {
+            hreflang: Cow::Owned(self.hreflang.into_owned()),
+            href: Cow::Owned(self.href.into_owned()),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for Alternate<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut s = serializer.serialize_struct("Alternate", 2)?;
+        s.serialize_field("hreflang", &self.hreflang)?;
+        s.serialize_field("href", &self.href)?;
+        s.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for Alternate<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Alternate")]
+        struct Repr {
+            hreflang: String,
+            href: String,
+        }
+
+        let repr = Repr::deserialize(deserializer)?;
+        Alternate::new(repr.hreflang, repr.href.as_str())
+            .map(Alternate::into_owned)
+            .map_err(|_| serde::de::Error::custom("invalid alternate"))
+    }
+}
+
+// irregular grandfathered tags (RFC 5646 section 2.1)
+const IRREGULAR: &[&str] = &[
+    "en-GB-oed",
+    "i-ami",
+    "i-bnn",
+    "i-default",
+    "i-enochian",
+    "i-hak",
+    "i-klingon",
+    "i-lux",
+    "i-mingo",
+    "i-navajo",
+    "i-pwn",
+    "i-tao",
+    "i-tay",
+    "i-tsu",
+    "sgn-BE-FR",
+    "sgn-BE-NL",
+    "sgn-CH-DE",
+];
+
+/// Returns `true` if `s` is a well-formed BCP 47 language tag.
+fn is_language_tag(s: &str) -> bool {
+    if IRREGULAR.iter().any(|tag| tag.eq_ignore_ascii_case(s)) {
+        return true;
+    }
+    let subtags = s.split('-').collect::<Vec<&str>>();
+    if subtags[0].eq_ignore_ascii_case("x") {
+        return is_private_use(&subtags[1..]);
+    }
+
+    // language
+    let language = subtags[0];
+    if !is_alpha(language, 2..=8) {
+        return false;
+    }
+    let mut i = 1;
+    if language.len() <= 3 {
+        // extlang
+        let end = (i + 3).min(subtags.len());
+        while i < end && is_alpha(subtags[i], 3..=3) {
+            i += 1;
+        }
+    }
+    // script
+    if i < subtags.len() && is_alpha(subtags[i], 4..=4) {
+        i += 1;
+    }
+    // region
+    if i < subtags.len()
+        && (is_alpha(subtags[i], 2..=2)
+            || (subtags[i].len() == 3 && subtags[i].bytes().all(|b| b.is_ascii_digit())))
+    {
+        i += 1;
+    }
+    // variant
+    while i < subtags.len()
+        && (is_alphanumeric(subtags[i], 5..=8)
+            || (is_alphanumeric(subtags[i], 4..=4) && subtags[i].as_bytes()[0].is_ascii_digit()))
+    {
+        i += 1;
+    }
+    // extension
+    let mut singletons = Vec::new();
+    while i < subtags.len()
+        && is_alphanumeric(subtags[i], 1..=1)
+        && !subtags[i].eq_ignore_ascii_case("x")
+    {
+        let singleton = subtags[i].to_ascii_lowercase();
+        if singletons.contains(&singleton) {
+            return false;
+        }
+        singletons.push(singleton);
+        i += 1;
+        let start = i;
+        while i < subtags.len() && is_alphanumeric(subtags[i], 2..=8) {
+            i += 1;
+        }
+        if i == start {
+            return false;
+        }
+    }
+    // privateuse
+    if i < subtags.len() && subtags[i].eq_ignore_ascii_case("x") {
+        return is_private_use(&subtags[i + 1..]);
+    }
+    i == subtags.len()
+}
+
+fn is_private_use(subtags: &[&str]) -> bool {
+    !subtags.is_empty() && subtags.iter().all(|s| is_alphanumeric(s, 1..=8))
+}
+
+fn is_alpha(s: &str, len: std::ops::RangeInclusive<usize>) -> bool {
+    len.contains(&s.len()) && s.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+fn is_alphanumeric(s: &str, len: std::ops::RangeInclusive<usize>) -> bool {
+    len.contains(&s.len()) && s.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+impl<'a> UrlExtension for Alternate<'a> {
+    fn namespace(&self) -> (&str, &str) {
+        (Self::PREFIX, Self::NAMESPACE_URI)
+    }
+
+    fn write(&self, writer: &mut ExtensionWriter<'_>) -> Result<()> {
+        writer.empty_element(
+            "link",
+            &[
+                ("rel", "alternate"),
+                ("hreflang", &self.hreflang),
+                ("href", &self.href),
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+    use crate::sitemap_xml_writer::SitemapXmlWriter;
+
+    #[test]
+    fn test_new() -> anyhow::Result<()> {
+        let alternate = Alternate::new("de-ch", "http://www.example.com/?a&b")?;
+        assert_eq!(alternate.hreflang(), "de-ch");
+        assert_eq!(alternate.href(), "http://www.example.com/?a&b");
+        let mut xml_writer = SitemapXmlWriter::new(Vec::new(), false, false);
+        let mut writer = ExtensionWriter::new(&mut xml_writer, Alternate::PREFIX);
+        alternate.into_owned().write(&mut writer)?;
+        assert_eq!(
+            String::from_utf8(xml_writer.into_inner())?,
+            r#"<xhtml:link rel="alternate" hreflang="de-ch" href="http://www.example.com/?a&amp;b"/>"#
+        );
+
+        assert!(Alternate::new("X-Default", "http://www.example.com/").is_ok());
+        assert!(matches!(
+            Alternate::new("", "http://www.example.com/").map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidHreflang)
+        ));
+        assert!(matches!(
+            Alternate::new("en", "a".repeat(2048).as_str()).map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidAlternate)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_language_tag() {
+        for tag in [
+            "en",
+            "EN-us",
+            "zh-Hant-TW",
+            "zh-yue-HK",
+            "zh-min-nan",
+            "sr-Latn-RS",
+            "es-419",
+            "de-CH-1901",
+            "sl-rozaj-biske",
+            "de-DE-u-co-phonebk",
+            "en-a-bbb-x-a-ccc",
+            "x-whatever",
+            "qaa-Qaaa-QM-x-southern",
+            "i-klingon",
+            "en-GB-oed",
+            "cel-gaulish",
+        ] {
+            assert!(is_language_tag(tag), "{}", tag);
+        }
+        for tag in [
+            "",
+            "e",
+            "en-",
+            "-en",
+            "en--us",
+            "en us",
+            "en_US",
+            "toolonglang",
+            "1a",
+            "en-a",
+            "en-a-b",
+            "de-a-xx-a-yy",
+            "en-x",
+            "en-x-toolongpart",
+            "en-US-US",
+            "zh-Hant-Hans",
+            "en-ü",
+        ] {
+            assert!(!is_language_tag(tag), "{}", tag);
+        }
+    }
+}
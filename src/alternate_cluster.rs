@@ -0,0 +1,267 @@
+use std::{collections::HashMap, fmt};
+
+use crate::url::Url;
+
+/// A validator of the `hreflang` alternates (see [`crate::Alternate`]) of a
+/// set of `url` entries.
+///
+/// Google ignores the alternates that are not reciprocal, so the entries of
+/// a cluster of localized pages should be validated before writing them. An
+/// entry with alternates must list itself, and every alternate must be an
+/// entry of the cluster that lists the entry in return. Entries without
+/// alternates are not part of any cluster.
+///
+/// The URLs are compared as they are (without normalization).
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{Alternate, AlternateCluster, AlternateViolation, Url};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let en = "http://www.example.com/english/page.html";
+/// let de = "http://www.example.com/deutsch/page.html";
+/// let urls = [
+///     Url::loc(en)?
+///         .alternate(Alternate::new("en", en)?)
+///         .alternate(Alternate::new("de", de)?),
+///     Url::loc(de)?.alternate(Alternate::new("de", de)?),
+/// ];
+/// let mut cluster = AlternateCluster::new();
+/// for url in urls.iter() {
+///     cluster.add(url);
+/// }
+/// assert_eq!(
+///     cluster.validate(),
+///     [AlternateViolation::MissingReturnLink {
+///         loc: de.to_string(),
+///         href: en.to_string(),
+///     }]
+/// );
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AlternateCluster {
+    entries: Vec<(String, Vec<(String, String)>)>,
+    indexes: HashMap<String, usize>,
+}
+
+/// A violation reported by [`AlternateCluster::validate`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AlternateViolation {
+    /// The entry `loc` lists the `hreflang` code for different URLs.
+    ConflictingHreflang { loc: String, hreflang: String },
+    /// The alternate `href` (with the `hreflang` code) of the entry `loc` is
+    /// not an entry of the cluster.
+    MissingEntry {
+        loc: String,
+        hreflang: String,
+        href: String,
+    },
+    /// The entry `loc` does not list `href` as an alternate, although the
+    /// entry `href` lists `loc`.
+    MissingReturnLink { loc: String, href: String },
+    /// The entry `loc` has alternates but does not list itself.
+    MissingSelfLink { loc: String },
+}
+
+impl fmt::Display for AlternateViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlternateViolation::ConflictingHreflang { loc, hreflang } => {
+                write!(f, "{} lists hreflang {} for different URLs", loc, hreflang)
+            }
+            AlternateViolation::MissingEntry {
+                loc,
+                hreflang,
+                href,
+            } => write!(
+                f,
+                "{} lists {} ({}) that is not an entry",
+                loc, href, hreflang
+            ),
+            AlternateViolation::MissingReturnLink { loc, href } => {
+                write!(f, "{} does not link back to {}", loc, href)
+            }
+            AlternateViolation::MissingSelfLink { loc } => {
+                write!(f, "{} does not list itself", loc)
+            }
+        }
+    }
+}
+
+impl AlternateCluster {
+    /// Creates an empty cluster.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the `loc` and the alternates of the entry. The alternates of the
+    /// entries with the same `loc` are merged.
+    pub fn add(&mut self, url: &Url<'_>) -> &mut Self {
+        let alternates = url
+            .alternates
+            .iter()
+            .map(|alternate| (alternate.hreflang.to_string(), alternate.href.to_string()));
+        match self.indexes.get(url.loc.as_ref()) {
+            Some(&index) => self.entries[index].1.extend(alternates),
+            None => {
+                self.indexes.insert(url.loc.to_string(), self.entries.len());
+                self.entries
+                    .push((url.loc.to_string(), alternates.collect()));
+            }
+        }
+        self
+    }
+
+    /// Returns the violations in the order the entries are added. It returns
+    /// an empty `Vec` if all the clusters are complete and reciprocal.
+    pub fn validate(&self) -> Vec<AlternateViolation> {
+        let mut violations = Vec::new();
+        for (loc, alternates) in self.entries.iter() {
+            if alternates.is_empty() {
+                continue;
+            }
+
+            let mut hreflangs = HashMap::<&str, &str>::new();
+            let mut conflicting = Vec::new();
+            for (hreflang, href) in alternates.iter() {
+                match hreflangs.insert(hreflang, href) {
+                    Some(other) if other != href && !conflicting.contains(&hreflang) => {
+                        conflicting.push(hreflang);
+                        violations.push(AlternateViolation::ConflictingHreflang {
+                            loc: loc.clone(),
+                            hreflang: hreflang.clone(),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+
+            if !alternates.iter().any(|(_, href)| href == loc) {
+                violations.push(AlternateViolation::MissingSelfLink { loc: loc.clone() });
+            }
+
+            let mut checked = Vec::new();
+            for (hreflang, href) in alternates.iter() {
+                if href == loc || checked.contains(&href) {
+                    continue;
+                }
+                checked.push(href);
+                match self.indexes.get(href) {
+                    None => violations.push(AlternateViolation::MissingEntry {
+                        loc: loc.clone(),
+                        hreflang: hreflang.clone(),
+                        href: href.clone(),
+                    }),
+                    Some(&index) => {
+                        if !self.entries[index].1.iter().any(|(_, h)| h == loc) {
+                            violations.push(AlternateViolation::MissingReturnLink {
+                                loc: href.clone(),
+                                href: loc.clone(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alternate::Alternate;
+
+    const EN: &str = "http://www.example.com/en/";
+    const DE: &str = "http://www.example.com/de/";
+    const FR: &str = "http://www.example.com/fr/";
+
+    fn url(loc: &'static str, alternates: &[(&'static str, &'static str)]) -> Url<'static> {
+        alternates
+            .iter()
+            .fold(Url::loc(loc).unwrap(), |url, (hreflang, href)| {
+                url.alternate(Alternate::new(*hreflang, *href).unwrap())
+            })
+    }
+
+    fn validate(urls: &[Url<'_>]) -> Vec<AlternateViolation> {
+        let mut cluster = AlternateCluster::new();
+        for url in urls {
+            cluster.add(url);
+        }
+        cluster.validate()
+    }
+
+    #[test]
+    fn test_reciprocal() {
+        let alternates = [("en", EN), ("de", DE), ("x-default", EN)];
+        assert_eq!(
+            validate(&[
+                url(EN, &alternates),
+                url(DE, &alternates),
+                url("http://www.example.com/", &[]),
+            ]),
+            []
+        );
+        assert_eq!(validate(&[]), []);
+    }
+
+    #[test]
+    fn test_violations() {
+        assert_eq!(
+            validate(&[
+                url(EN, &[("en", EN), ("de", DE), ("fr", FR)]),
+                url(DE, &[("en", FR)]),
+                url(FR, &[("fr", FR)]),
+                url(
+                    "http://www.example.com/es/",
+                    &[("es", "http://www.example.com/es/")]
+                ),
+                url(FR, &[("en", EN)]),
+            ]),
+            [
+                AlternateViolation::MissingReturnLink {
+                    loc: DE.to_string(),
+                    href: EN.to_string()
+                },
+                AlternateViolation::MissingSelfLink {
+                    loc: DE.to_string()
+                },
+                AlternateViolation::MissingReturnLink {
+                    loc: FR.to_string(),
+                    href: DE.to_string()
+                },
+            ]
+        );
+
+        assert_eq!(
+            validate(&[url(EN, &[("en", EN), ("en", DE), ("en", FR)])]),
+            [
+                AlternateViolation::ConflictingHreflang {
+                    loc: EN.to_string(),
+                    hreflang: "en".to_string()
+                },
+                AlternateViolation::MissingEntry {
+                    loc: EN.to_string(),
+                    hreflang: "en".to_string(),
+                    href: DE.to_string()
+                },
+                AlternateViolation::MissingEntry {
+                    loc: EN.to_string(),
+                    hreflang: "en".to_string(),
+                    href: FR.to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            AlternateViolation::MissingSelfLink {
+                loc: EN.to_string()
+            }
+            .to_string(),
+            "http://www.example.com/en/ does not list itself"
+        );
+    }
+}
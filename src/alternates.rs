@@ -0,0 +1,224 @@
+use std::borrow::Cow;
+
+use crate::{alternate::Alternate, loc::Loc, sitemap_writer::Error, url::Url};
+
+type Result<T, E = crate::Error> = std::result::Result<T, E>;
+
+/// A set of localized versions of a page, including the `x-default`
+/// alternate.
+///
+/// It generates the reciprocal set of `url` entries: one entry per page,
+/// each listing all the alternates (see [`crate::AlternateCluster`]).
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{Alternate, Alternates, SitemapWriterBuilder};
+/// use std::io::Cursor;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let alternates = Alternates::new(
+///     "en",
+///     [
+///         ("en", "http://www.example.com/en/"),
+///         ("de", "http://www.example.com/de/"),
+///     ],
+/// )?;
+/// let mut writer = SitemapWriterBuilder::new()
+///     .namespace(Alternate::PREFIX, Alternate::NAMESPACE_URI)
+///     .start(Cursor::new(Vec::new()))?;
+/// writer.extend(alternates.urls());
+/// let (inner, _) = writer.finish()?;
+/// assert_eq!(
+///     String::from_utf8(inner.into_inner())?,
+///     concat!(
+///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+///         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:xhtml="http://www.w3.org/1999/xhtml">"#,
+///         r#"<url>"#,
+///         r#"<loc>http://www.example.com/en/</loc>"#,
+///         r#"<xhtml:link rel="alternate" hreflang="en" href="http://www.example.com/en/"/>"#,
+///         r#"<xhtml:link rel="alternate" hreflang="de" href="http://www.example.com/de/"/>"#,
+///         r#"<xhtml:link rel="alternate" hreflang="x-default" href="http://www.example.com/en/"/>"#,
+///         r#"</url>"#,
+///         r#"<url>"#,
+///         r#"<loc>http://www.example.com/de/</loc>"#,
+///         r#"<xhtml:link rel="alternate" hreflang="en" href="http://www.example.com/en/"/>"#,
+///         r#"<xhtml:link rel="alternate" hreflang="de" href="http://www.example.com/de/"/>"#,
+///         r#"<xhtml:link rel="alternate" hreflang="x-default" href="http://www.example.com/en/"/>"#,
+///         r#"</url>"#,
+///         r#"</urlset>"#
+///     )
+/// );
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Alternates<'a> {
+    alternates: Vec<Alternate<'a>>,
+}
+
+impl<'a> Alternates<'a> {
+    /// The `hreflang` code of the alternate for unmatched languages.
+    pub const X_DEFAULT: &'static str = "x-default";
+
+    /// Builds a set from the pairs of the `hreflang` code and the URL of the
+    /// localized page, in their order (e.g. use a `BTreeMap` for a sorted
+    /// order). The `x-default` alternate is added with the URL of
+    /// `default_hreflang`.
+    ///
+    /// It returns an error if there are no pairs, if an `hreflang` code is
+    /// duplicated (case-insensitively) or is `x-default`, or if
+    /// `default_hreflang` is not one of the `hreflang` codes.
+    pub fn new<I, L, S>(default_hreflang: &str, alternates: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (L, S)>,
+        L: Into<Cow<'a, str>>,
+        S: TryInto<Loc<'a>>,
+    {
+        let mut list = Vec::<Alternate<'a>>::new();
+        for (hreflang, href) in alternates {
+            let alternate = Alternate::new(hreflang, href)?;
+            if alternate.hreflang().eq_ignore_ascii_case(Self::X_DEFAULT)
+                || list
+                    .iter()
+                    .any(|a| a.hreflang().eq_ignore_ascii_case(alternate.hreflang()))
+            {
+                return Err(Error::InvalidAlternate.into());
+            }
+            list.push(alternate);
+        }
+        let x_default = list
+            .iter()
+            .find(|a| a.hreflang().eq_ignore_ascii_case(default_hreflang))
+            .ok_or(Error::InvalidAlternate)?
+            .href
+            .clone();
+        list.push(Alternate {
+            hreflang: Cow::Borrowed(Self::X_DEFAULT),
+            href: x_default,
+        });
+        Ok(Self { alternates: list })
+    }
+
+    /// Changes the URL of the `x-default` alternate (e.g. a language
+    /// selector page).
+    pub fn x_default<S>(mut self, href: S) -> Result<Self>
+    where
+        S: TryInto<Loc<'a>>,
+    {
+        let alternate = Alternate::new(Self::X_DEFAULT, href)?;
+        if let Some(last) = self.alternates.last_mut() {
+            *last = alternate;
+        }
+        Ok(self)
+    }
+
+    /// Returns the alternates. The `x-default` alternate is the last one.
+    pub fn alternates(&self) -> &[Alternate<'a>] {
+        &self.alternates
+    }
+
+    /// Returns the reciprocal set of `url` entries: one entry per distinct
+    /// URL (including the `x-default` one) in order, each listing all the
+    /// alternates.
+    pub fn urls(&self) -> Vec<Url<'a>> {
+        let mut locs = Vec::<&Cow<'a, str>>::new();
+        for alternate in self.alternates.iter() {
+            if !locs.contains(&&alternate.href) {
+                locs.push(&alternate.href);
+            }
+        }
+        locs.into_iter()
+            .map(|loc| {
+                self.alternates.iter().fold(
+                    Url::new(Loc::new_unchecked(loc.clone())),
+                    |url, alternate| url.alternate(alternate.clone()),
+                )
+            })
+            .collect()
+    }
+
+    /// Converts this `Alternates` into an owned `Alternates<'static>`.
+    pub fn into_owned(self) -> Alternates<'static> {
+        Alternates {
+            alternates: self
+                .alternates
+                .into_iter()
+                .map(Alternate::into_owned)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alternate_cluster::AlternateCluster;
+    use crate::error::ErrorKind;
+
+    const EN: &str = "http://www.example.com/en/";
+    const DE: &str = "http://www.example.com/de/";
+
+    fn hreflangs(alternates: &Alternates<'_>) -> Vec<(String, String)> {
+        alternates
+            .alternates()
+            .iter()
+            .map(|a| (a.hreflang().to_string(), a.href().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_new() -> anyhow::Result<()> {
+        let alternates = Alternates::new("DE", [("en", EN), ("de", DE)])?;
+        assert_eq!(
+            hreflangs(&alternates),
+            [
+                ("en".to_string(), EN.to_string()),
+                ("de".to_string(), DE.to_string()),
+                ("x-default".to_string(), DE.to_string()),
+            ]
+        );
+
+        assert!(matches!(
+            Alternates::new("en", Vec::<(&str, &str)>::new()).map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidAlternate)
+        ));
+        assert!(Alternates::new("fr", [("en", EN), ("de", DE)]).is_err());
+        assert!(Alternates::new("en", [("en", EN), ("EN", DE)]).is_err());
+        assert!(Alternates::new("en", [("en", EN), ("x-default", DE)]).is_err());
+        assert!(Alternates::new("en", [("en", "a".repeat(2048).as_str())]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_urls() -> anyhow::Result<()> {
+        let alternates = Alternates::new("en", [("en", EN), ("de", DE)])?
+            .x_default("http://www.example.com/")?
+            .into_owned();
+        assert_eq!(
+            hreflangs(&alternates).last(),
+            Some(&(
+                "x-default".to_string(),
+                "http://www.example.com/".to_string()
+            ))
+        );
+
+        let urls = alternates.urls();
+        assert_eq!(
+            urls.iter()
+                .map(|url| url.loc.as_ref())
+                .collect::<Vec<&str>>(),
+            [EN, DE, "http://www.example.com/"]
+        );
+        let mut cluster = AlternateCluster::new();
+        for url in urls.iter() {
+            assert_eq!(url.alternates, alternates.alternates());
+            cluster.add(url);
+        }
+        assert_eq!(cluster.validate(), []);
+
+        let urls = Alternates::new("en", [("en", EN), ("de", DE)])?.urls();
+        assert_eq!(urls.len(), 2);
+        Ok(())
+    }
+}
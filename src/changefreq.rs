@@ -1,6 +1,17 @@
 /// A `changefreq` child entry.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, strum::AsRefStr, strum::EnumString)]
-#[strum(serialize_all = "lowercase")]
+///
+/// The canonical form used by [`std::fmt::Display`] and
+/// [`std::str::FromStr`] is the lowercase name (e.g. `"monthly"`).
+///
+/// With the `serde` feature, it is serialized as the lowercase name and
+/// deserialized case-insensitively (e.g. `"Monthly"`). Use
+/// `Changefreq::deserialize_strict` to accept only the lowercase name.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum Changefreq {
     /// `always`
     Always,
@@ -18,6 +29,149 @@ pub enum Changefreq {
     Never,
 }
 
+impl Changefreq {
+    /// All values in the order of their definition.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::Changefreq;
+    ///
+    /// assert_eq!(Changefreq::ALL.len(), 7);
+    /// assert_eq!(Changefreq::ALL[0], Changefreq::Always);
+    /// ```
+    pub const ALL: [Changefreq; 7] = [
+        Changefreq::Always,
+        Changefreq::Hourly,
+        Changefreq::Daily,
+        Changefreq::Weekly,
+        Changefreq::Monthly,
+        Changefreq::Yearly,
+        Changefreq::Never,
+    ];
+
+    /// The names of all values in the order of their definition.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::Changefreq;
+    ///
+    /// assert_eq!(Changefreq::VARIANTS[0], "always");
+    /// ```
+    pub const VARIANTS: [&'static str; 7] = [
+        "always", "hourly", "daily", "weekly", "monthly", "yearly", "never",
+    ];
+
+    /// Returns an iterator over all values in the order of their definition.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::Changefreq;
+    ///
+    /// let names = Changefreq::iter().map(|c| c.to_string()).collect::<Vec<String>>();
+    /// assert_eq!(names, Changefreq::VARIANTS);
+    /// ```
+    pub fn iter() -> impl Iterator<Item = Changefreq> {
+        Self::ALL.into_iter()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Error;
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("error")
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl AsRef<str> for Changefreq {
+    fn as_ref(&self) -> &str {
+        match self {
+            Changefreq::Always => "always",
+            Changefreq::Hourly => "hourly",
+            Changefreq::Daily => "daily",
+            Changefreq::Weekly => "weekly",
+            Changefreq::Monthly => "monthly",
+            Changefreq::Yearly => "yearly",
+            Changefreq::Never => "never",
+        }
+    }
+}
+
+impl std::fmt::Display for Changefreq {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+impl std::str::FromStr for Changefreq {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Changefreq::iter().find(|v| v.as_ref() == s).ok_or(Error)
+    }
+}
+
+impl TryFrom<&str> for Changefreq {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Changefreq {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Changefreq::iter()
+            .find(|v| v.as_ref().eq_ignore_ascii_case(&s))
+            .ok_or_else(|| serde::de::Error::unknown_variant(&s, &Changefreq::VARIANTS))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Changefreq {
+    /// Deserializes a `Changefreq` from the lowercase name only.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::Changefreq;
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct Config {
+    ///     #[serde(deserialize_with = "Changefreq::deserialize_strict")]
+    ///     changefreq: Changefreq,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let config: Config = serde_json::from_str(r#"{"changefreq":"daily"}"#)?;
+    /// assert_eq!(config.changefreq, Changefreq::Daily);
+    /// assert!(serde_json::from_str::<Config>(r#"{"changefreq":"Daily"}"#).is_err());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn deserialize_strict<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::Deserialize;
+        let s = String::deserialize(deserializer)?;
+        Changefreq::iter()
+            .find(|v| v.as_ref() == s)
+            .ok_or_else(|| serde::de::Error::unknown_variant(&s, &Changefreq::VARIANTS))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -35,8 +189,39 @@ mod tests {
             (Never, "never"),
         ] {
             assert_eq!(Changefreq::try_from(s)?, v);
+            assert_eq!(s.parse::<Changefreq>()?, v);
             assert_eq!(v.as_ref(), s);
+            assert_eq!(v.to_string(), s);
         }
+        assert!(Changefreq::try_from("Daily").is_err());
+        assert!("sometimes".parse::<Changefreq>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_all() {
+        assert_eq!(Changefreq::iter().collect::<Vec<_>>(), Changefreq::ALL);
+        for (v, s) in Changefreq::ALL.iter().zip(Changefreq::VARIANTS) {
+            assert_eq!(v.as_ref(), s);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() -> anyhow::Result<()> {
+        for v in Changefreq::iter() {
+            let json = serde_json::to_string(&v)?;
+            assert_eq!(json, format!("\"{}\"", v));
+            assert_eq!(serde_json::from_str::<Changefreq>(&json)?, v);
+            let upper = json.to_uppercase();
+            assert_eq!(serde_json::from_str::<Changefreq>(&upper)?, v);
+        }
+        assert!(serde_json::from_str::<Changefreq>(r#""sometimes""#).is_err());
+
+        let mut de = serde_json::Deserializer::from_str(r#""daily""#);
+        assert_eq!(Changefreq::deserialize_strict(&mut de)?, Changefreq::Daily);
+        let mut de = serde_json::Deserializer::from_str(r#""Daily""#);
+        assert!(Changefreq::deserialize_strict(&mut de).is_err());
         Ok(())
     }
 }
@@ -0,0 +1,111 @@
+use std::{collections::VecDeque, io::Write};
+
+/// A writer that splits the written bytes into fixed-size chunks.
+///
+/// It is intended to be used as the underlying writer of [`crate::SitemapWriter`]
+/// or [`crate::SitemapIndexWriter`] to upload the output incrementally (e.g.
+/// S3 multipart upload) without keeping the whole output in memory.
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{ChunkedWriter, SitemapWriter};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut uploaded = Vec::new();
+/// let mut writer = SitemapWriter::start(ChunkedWriter::new(64))?;
+/// for i in 0..10 {
+///     writer.write(format!("http://www.example.com/{}", i).as_str())?;
+///     // Every chunk except the last one has exactly 64 bytes.
+///     while let Some(chunk) = writer.get_mut().pop_chunk() {
+///         assert_eq!(chunk.len(), 64);
+///         uploaded.push(chunk);
+///     }
+/// }
+/// let (inner, _) = writer.finish()?;
+/// uploaded.extend(inner.finish());
+///
+/// assert!(String::from_utf8(uploaded.concat())?.ends_with("</urlset>"));
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ChunkedWriter {
+    chunk_size: usize,
+    current: Vec<u8>,
+    chunks: VecDeque<Vec<u8>>,
+}
+
+impl ChunkedWriter {
+    /// Creates a new `ChunkedWriter` that produces chunks of `chunk_size` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    pub fn new(chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk_size must be greater than 0");
+        Self {
+            chunk_size,
+            current: Vec::with_capacity(chunk_size),
+            chunks: VecDeque::new(),
+        }
+    }
+
+    /// Removes and returns the oldest completed chunk.
+    pub fn pop_chunk(&mut self) -> Option<Vec<u8>> {
+        self.chunks.pop_front()
+    }
+
+    /// Removes and returns all completed chunks.
+    pub fn drain_chunks(&mut self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        self.chunks.drain(..)
+    }
+
+    /// Consumes this `ChunkedWriter`, returning the remaining chunks including
+    /// the last (possibly shorter) chunk.
+    pub fn finish(mut self) -> Vec<Vec<u8>> {
+        if !self.current.is_empty() {
+            self.chunks.push_back(self.current);
+        }
+        self.chunks.into()
+    }
+}
+
+impl Write for ChunkedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let l = (self.chunk_size - self.current.len()).min(buf.len());
+        self.current.extend_from_slice(&buf[..l]);
+        if self.current.len() == self.chunk_size {
+            let chunk = std::mem::replace(&mut self.current, Vec::with_capacity(self.chunk_size));
+            self.chunks.push_back(chunk);
+        }
+        Ok(l)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> anyhow::Result<()> {
+        let mut writer = ChunkedWriter::new(3);
+        writer.write_all(b"ab")?;
+        assert_eq!(writer.pop_chunk(), None);
+        writer.write_all(b"cdefgh")?;
+        assert_eq!(
+            writer.drain_chunks().collect::<Vec<Vec<u8>>>(),
+            vec![b"abc".to_vec(), b"def".to_vec()]
+        );
+        writer.write_all(b"ijk")?;
+        assert_eq!(writer.finish(), vec![b"ghi".to_vec(), b"jk".to_vec()]);
+
+        let writer = ChunkedWriter::new(3);
+        assert!(writer.finish().is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,420 @@
+//! A parser for the XML Schema `date` and `dateTime` formats.
+//!
+//! - <https://www.w3.org/TR/xmlschema11-2/#date>
+//! - <https://www.w3.org/TR/xmlschema11-2/#dateTime>
+
+/// The components of a W3C Datetime (XML Schema `date` or `dateTime`) string.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sitemap_xml_writer::Lastmod;
+/// # fn main() -> anyhow::Result<()> {
+/// let lastmod = Lastmod::try_from("2004-12-23T18:00:15.5+09:00")?;
+/// let date_time = lastmod.date_time().expect("valid lastmod");
+/// assert_eq!(
+///     (date_time.year(), date_time.month(), date_time.day()),
+///     (2004, 12, 23)
+/// );
+/// let time = date_time.time().expect("dateTime");
+/// assert_eq!(
+///     (time.hour(), time.minute(), time.second(), time.fraction()),
+///     (18, 0, 15, "5")
+/// );
+/// assert_eq!(date_time.offset_minutes(), Some(540));
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct W3cDateTime<'a> {
+    year: i128,
+    month: u8,
+    day: u8,
+    time: Option<W3cTime<'a>>,
+    offset: Option<i16>,
+}
+
+/// The time components of a W3C Datetime (XML Schema `dateTime`) string.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct W3cTime<'a> {
+    hour: u8,
+    minute: u8,
+    second: u8,
+    fraction: &'a str,
+}
+
+impl<'a> W3cDateTime<'a> {
+    /// Returns the year. It may be negative or have more than 4 digits.
+    pub fn year(&self) -> i128 {
+        self.year
+    }
+
+    /// Returns the month (1-12).
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// Returns the day of the month (1-31).
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// Returns the time components, or `None` for a `date`.
+    pub fn time(&self) -> Option<W3cTime<'a>> {
+        self.time
+    }
+
+    /// Returns the timezone offset in minutes, or `None` if the timezone is
+    /// not specified. `Z` is `Some(0)`.
+    pub fn offset_minutes(&self) -> Option<i16> {
+        self.offset
+    }
+
+    /// Parses a `date` or `dateTime` string.
+    pub fn parse(s: &'a str) -> Option<Self> {
+        let mut p = Parser(s);
+        let negative = p.eat(b'-');
+        let year = p.digits(4, usize::MAX)?;
+        if (year.len() > 4 && year.starts_with('0')) || year.len() > 30 {
+            return None;
+        }
+        let year = year.parse::<i128>().ok()?;
+        let year = if negative { -year } else { year };
+        p.expect(b'-')?;
+        let month = p.number(2, 1, 12)?;
+        p.expect(b'-')?;
+        let day = p.number(2, 1, days_in_month(year, month))?;
+        let time = if p.eat(b'T') {
+            let hour = p.number(2, 0, 24)?;
+            p.expect(b':')?;
+            let minute = p.number(2, 0, 59)?;
+            p.expect(b':')?;
+            let second = p.number(2, 0, 59)?;
+            let fraction = if p.eat(b'.') {
+                p.digits(1, usize::MAX)?
+            } else {
+                ""
+            };
+            if hour == 24 && (minute != 0 || second != 0 || fraction.bytes().any(|b| b != b'0')) {
+                return None;
+            }
+            Some(W3cTime {
+                hour,
+                minute,
+                second,
+                fraction,
+            })
+        } else {
+            None
+        };
+        let offset = if p.eat(b'Z') {
+            Some(0)
+        } else if let Some(sign) = p.sign() {
+            let hour = p.number(2, 0, 14)?;
+            p.expect(b':')?;
+            let minute = p.number(2, 0, 59)?;
+            if hour == 14 && minute != 0 {
+                return None;
+            }
+            Some(sign * (i16::from(hour) * 60 + i16::from(minute)))
+        } else {
+            None
+        };
+        if !p.0.is_empty() {
+            return None;
+        }
+        Some(Self {
+            year,
+            month,
+            day,
+            time,
+            offset,
+        })
+    }
+
+    /// Returns a key to order the instants. A missing time is treated as
+    /// `00:00:00` and a missing timezone is treated as UTC.
+    pub(crate) fn order_key(&self) -> (i128, &'a str) {
+        let days = days_from_civil(self.year, self.month, self.day);
+        let (seconds, fraction) = match self.time {
+            Some(W3cTime {
+                hour,
+                minute,
+                second,
+                fraction,
+            }) => (
+                i128::from(hour) * 3_600 + i128::from(minute) * 60 + i128::from(second),
+                fraction.trim_end_matches('0'),
+            ),
+            None => (0, ""),
+        };
+        let offset = i128::from(self.offset.unwrap_or(0)) * 60;
+        (days * 86_400 + seconds - offset, fraction)
+    }
+}
+
+impl<'a> W3cTime<'a> {
+    /// Returns the hour (0-24). `24` is only used for `24:00:00`.
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    /// Returns the minute (0-59).
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    /// Returns the second (0-59).
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// Returns the digits after the decimal point of the seconds. It is empty
+    /// if the seconds have no fractional part.
+    pub fn fraction(&self) -> &'a str {
+        self.fraction
+    }
+}
+
+pub(crate) fn days_in_month(year: i128, month: u8) -> u8 {
+    match month {
+        2 if year.rem_euclid(4) == 0
+            && (year.rem_euclid(100) != 0 || year.rem_euclid(400) == 0) =>
+        {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Returns the number of days since 1970-01-01 in the proleptic Gregorian
+/// calendar. <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>
+pub(crate) fn days_from_civil(year: i128, month: u8, day: u8) -> i128 {
+    let (month, day) = (i128::from(month), i128::from(day));
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+pub(crate) fn civil_from_days(days: i128) -> (i128, u8, u8) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u8;
+    let year = yoe + era * 400 + i128::from(month <= 2);
+    (year, month, day)
+}
+
+struct Parser<'a>(&'a str);
+
+impl<'a> Parser<'a> {
+    fn eat(&mut self, b: u8) -> bool {
+        if self.0.as_bytes().first() == Some(&b) {
+            self.0 = &self.0[1..];
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sign(&mut self) -> Option<i16> {
+        if self.eat(b'+') {
+            Some(1)
+        } else if self.eat(b'-') {
+            Some(-1)
+        } else {
+            None
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> Option<()> {
+        self.eat(b).then_some(())
+    }
+
+    fn digits(&mut self, min: usize, max: usize) -> Option<&'a str> {
+        let l = self.0.bytes().take_while(u8::is_ascii_digit).count();
+        if l < min || l > max {
+            return None;
+        }
+        let (digits, rest) = self.0.split_at(l);
+        self.0 = rest;
+        Some(digits)
+    }
+
+    fn number(&mut self, width: usize, min: u8, max: u8) -> Option<u8> {
+        let n = self.digits(width, width)?.parse::<u8>().ok()?;
+        (min..=max).contains(&n).then_some(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            W3cDateTime::parse("2005-01-01"),
+            Some(W3cDateTime {
+                year: 2005,
+                month: 1,
+                day: 1,
+                time: None,
+                offset: None
+            })
+        );
+        assert_eq!(
+            W3cDateTime::parse("-12345-12-31T24:00:00.000-14:00"),
+            Some(W3cDateTime {
+                year: -12345,
+                month: 12,
+                day: 31,
+                time: Some(W3cTime {
+                    hour: 24,
+                    minute: 0,
+                    second: 0,
+                    fraction: "000"
+                }),
+                offset: Some(-840)
+            })
+        );
+        assert_eq!(
+            W3cDateTime::parse("2004-12-23T18:00:15.5+09:30").map(|d| (d.time, d.offset)),
+            Some((
+                Some(W3cTime {
+                    hour: 18,
+                    minute: 0,
+                    second: 15,
+                    fraction: "5"
+                }),
+                Some(570)
+            ))
+        );
+        for s in [
+            "",
+            "2005",
+            "05-01-01",
+            "02005-01-01",
+            "2005-1-01",
+            "2005-00-01",
+            "2005-13-01",
+            "2005-01-00",
+            "2005-01-32",
+            "2005-02-29",
+            "1900-02-29",
+            "2005-04-31",
+            "2005-01-01T",
+            "2005-01-01T25:00:00",
+            "2005-01-01T24:00:01",
+            "2005-01-01T24:00:00.1",
+            "2005-01-01T00:60:00",
+            "2005-01-01T00:00:60",
+            "2005-01-01T00:00:00.",
+            "2005-01-01T00:00",
+            "2005-01-01+14:01",
+            "2005-01-01+15:00",
+            "2005-01-01+0900",
+            "2005-01-01Z ",
+            "+2005-01-01",
+        ] {
+            assert_eq!(W3cDateTime::parse(s), None, "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_parse_days_in_month() {
+        assert!(W3cDateTime::parse("2004-02-29").is_some());
+        assert!(W3cDateTime::parse("2000-02-29").is_some());
+        assert!(W3cDateTime::parse("0000-02-29").is_some());
+        assert!(W3cDateTime::parse("-0004-02-29").is_some());
+        assert!(W3cDateTime::parse("2005-12-31").is_some());
+    }
+
+    #[test]
+    fn test_order_key() {
+        let key = |s| W3cDateTime::parse(s).unwrap().order_key();
+        assert_eq!(key("1970-01-01"), (0, ""));
+        assert_eq!(key("1970-01-02T00:00:00Z"), (86_400, ""));
+        assert_eq!(key("1970-01-01T24:00:00"), key("1970-01-02"));
+        assert_eq!(
+            key("2005-01-01T09:00:00+09:00"),
+            key("2005-01-01T00:00:00Z")
+        );
+        assert_eq!(key("2005-01-01T00:00:00.50"), key("2005-01-01T00:00:00.5"));
+        assert!(key("2005-01-01T00:00:00.25") < key("2005-01-01T00:00:00.5"));
+        assert!(key("-0001-12-31") < key("0000-01-01"));
+    }
+
+    #[test]
+    fn test_days_from_civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+    }
+
+    #[test]
+    fn test_parse_agrees_with_regex() {
+        // The regular expressions previously used to validate `lastmod`.
+        // They do not check the number of days in the month.
+        let date_re = regex::Regex::new(r#"\A-?([1-9][0-9]{3,}|0[0-9]{3})-(0[1-9]|1[0-2])-(0[1-9]|[12][0-9]|3[01])(Z|(\+|-)((0[0-9]|1[0-3]):[0-5][0-9]|14:00))?\z"#).unwrap();
+        let date_time_re = regex::Regex::new(r#"\A-?([1-9][0-9]{3,}|0[0-9]{3})-(0[1-9]|1[0-2])-(0[1-9]|[12][0-9]|3[01])T(([01][0-9]|2[0-3]):[0-5][0-9]:[0-5][0-9](\.[0-9]+)?|(24:00:00(\.0+)?))(Z|(\+|-)((0[0-9]|1[0-3]):[0-5][0-9]|14:00))?\z"#).unwrap();
+        let years = ["2005", "-2005", "0005", "12345", "012345", "205", ""];
+        let dates = [
+            "-01-01", "-12-28", "-00-01", "-13-01", "-01-00", "-1-01", "01-01",
+        ];
+        let times = [
+            "",
+            "T00:00:00",
+            "T23:59:59.999",
+            "T24:00:00",
+            "T24:00:00.00",
+            "T24:00:00.01",
+            "T24:01:00",
+            "T12:60:00",
+            "T12:00",
+            "T12:00:00.",
+            "T",
+        ];
+        let offsets = [
+            "", "Z", "+09:00", "-14:00", "+14:01", "+13:59", "+0900", "z",
+        ];
+        for year in years {
+            for date in dates {
+                for time in times {
+                    for offset in offsets {
+                        let s = format!("{}{}{}{}", year, date, time, offset);
+                        assert_eq!(
+                            W3cDateTime::parse(&s).is_some(),
+                            date_re.is_match(&s) || date_time_re.is_match(&s),
+                            "{}",
+                            s
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_civil_from_days() {
+        for (y, m, d) in [
+            (1970, 1, 1),
+            (1969, 12, 31),
+            (2000, 2, 29),
+            (2004, 12, 23),
+            (-1, 3, 1),
+            (10000, 1, 1),
+        ] {
+            assert_eq!(civil_from_days(days_from_civil(y, m, d)), (y, m, d));
+        }
+    }
+}
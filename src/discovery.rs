@@ -0,0 +1,61 @@
+//! Helpers for announcing the location of a sitemap (or sitemap index) file.
+//!
+//! Use the same URL for all of them to keep the discovery surfaces
+//! consistent with the generated file.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use sitemap_xml_writer::discovery;
+//!
+//! let loc = "https://www.example.com/sitemap_index.xml";
+//! assert_eq!(
+//!     discovery::html_link_tag(loc),
+//!     r#"<link rel="sitemap" type="application/xml" href="https://www.example.com/sitemap_index.xml">"#
+//! );
+//! assert_eq!(
+//!     discovery::link_header_value(loc),
+//!     r#"<https://www.example.com/sitemap_index.xml>; rel="sitemap""#
+//! );
+//! assert_eq!(
+//!     discovery::robots_txt_line(loc),
+//!     "Sitemap: https://www.example.com/sitemap_index.xml"
+//! );
+//! ```
+
+use crate::sitemap_xml_writer::entity_escape;
+
+/// The value of the `X-Robots-Tag` HTTP header for responses serving sitemap
+/// files. It keeps the files themselves out of search results.
+pub const X_ROBOTS_TAG_VALUE: &str = "noindex";
+
+/// Returns a `<link rel="sitemap">` tag for an HTML `<head>`.
+pub fn html_link_tag(loc: &str) -> String {
+    format!(
+        r#"<link rel="sitemap" type="application/xml" href="{}">"#,
+        entity_escape(loc)
+    )
+}
+
+/// Returns a value of the `Link` HTTP header.
+pub fn link_header_value(loc: &str) -> String {
+    format!(r#"<{}>; rel="sitemap""#, loc)
+}
+
+/// Returns a `Sitemap:` line for robots.txt.
+pub fn robots_txt_line(loc: &str) -> String {
+    format!("Sitemap: {}", loc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_link_tag_escape() {
+        assert_eq!(
+            html_link_tag("https://www.example.com/sitemap.xml?a=1&b=\"2\""),
+            r#"<link rel="sitemap" type="application/xml" href="https://www.example.com/sitemap.xml?a=1&amp;b=&quot;2&quot;">"#
+        );
+    }
+}
@@ -0,0 +1,344 @@
+/// The kind of an error returned by the writers.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The `lastmod` is too far in the future.
+    FutureLastmod,
+    /// The host of the `loc` is not allowed.
+    HostNotAllowed,
+    /// An alternate (see [`crate::Alternate`]) is invalid.
+    InvalidAlternate,
+    /// The `changefreq` is invalid.
+    InvalidChangefreq,
+    /// The child entries are written in an invalid order, or an entry is
+    /// written after the closing tag.
+    InvalidElementOrder,
+    /// An extension (e.g. its namespace or its elements) is invalid.
+    InvalidExtension,
+    /// The format of the sitemap file is invalid.
+    InvalidFormat,
+    /// The `hreflang` of an alternate (see [`crate::Alternate`]) is not a
+    /// well-formed BCP 47 language tag.
+    InvalidHreflang,
+    /// An image (see [`crate::Image`]) is invalid.
+    InvalidImage,
+    /// The `lastmod` is invalid.
+    InvalidLastmod,
+    /// The `loc` is invalid.
+    InvalidLoc,
+    /// A news entry (see [`crate::News`]) is invalid.
+    InvalidNews,
+    /// The `news:language` of a news entry is not an ISO 639 language code.
+    InvalidNewsLanguage,
+    /// The `news:publication_date` of a news entry is invalid.
+    InvalidNewsPublicationDate,
+    /// The `priority` is invalid.
+    InvalidPriority,
+    /// A video (see [`crate::Video`]) is invalid.
+    InvalidVideo,
+    /// The `video:duration` is out of range.
+    InvalidVideoDuration,
+    /// The `video:expiration_date` is invalid.
+    InvalidVideoExpirationDate,
+    /// The `video:platform` is invalid.
+    InvalidVideoPlatform,
+    /// The `video:price` is invalid.
+    InvalidVideoPrice,
+    /// The `video:publication_date` is invalid.
+    InvalidVideoPublicationDate,
+    /// The `video:rating` is out of range.
+    InvalidVideoRating,
+    /// The `video:restriction` is invalid.
+    InvalidVideoRestriction,
+    /// A `video:tag` is invalid.
+    InvalidVideoTag,
+    /// The `video:uploader` is invalid.
+    InvalidVideoUploader,
+    /// An I/O error.
+    Io,
+    /// The file exceeds 50 MiB (52,428,800 bytes). The room for the closing
+    /// tag is kept, so it is returned by the entry that does not fit.
+    MaxByteLength,
+    /// The `url` entry exceeds 1,000 images.
+    MaxNumberOfImages,
+    /// The `url` entry has more than one `news:news` entry.
+    MaxNumberOfNews,
+    /// The `news:stock_tickers` exceeds 5 stock tickers.
+    MaxNumberOfNewsStockTickers,
+    /// The news sitemap file exceeds 1,000 URLs (see
+    /// [`crate::GoogleNewsProfile`]).
+    MaxNumberOfNewsUrls,
+    /// The sitemap index file exceeds 50,000 sitemaps.
+    MaxNumberOfSitemaps,
+    /// The sitemap file exceeds 50,000 URLs.
+    MaxNumberOfUrls,
+    /// The `video:video` entry exceeds 32 tags.
+    MaxNumberOfVideoTags,
+    /// The scheme of the `loc` is not allowed.
+    SchemeNotAllowed,
+    /// The `loc` refers to the file being written.
+    SelfReferentialLoc,
+    /// The `news:publication_date` is too old for
+    /// [`crate::GoogleNewsProfile`].
+    StaleNewsPublicationDate,
+}
+
+/// The writer that returned an error.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ErrorWriter {
+    /// [`crate::SitemapWriter`]
+    Sitemap,
+    /// [`crate::SitemapIndexWriter`]
+    SitemapIndex,
+}
+
+/// The field of an entry that caused an error.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorField {
+    /// `changefreq`
+    Changefreq,
+    /// An extension (see [`crate::UrlExtension`])
+    Extension,
+    /// The format of the sitemap file (see [`crate::SitemapFormat`])
+    Format,
+    /// `lastmod`
+    Lastmod,
+    /// `loc`
+    Loc,
+    /// `priority`
+    Priority,
+}
+
+/// The context of an [`Error`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Context {
+    writer: ErrorWriter,
+    entry_index: Option<usize>,
+    field: Option<ErrorField>,
+}
+
+impl Context {
+    /// Returns the writer that returned the error.
+    pub fn writer(&self) -> ErrorWriter {
+        self.writer
+    }
+
+    /// Returns the index (0-based) of the entry that caused the error, if
+    /// any.
+    ///
+    /// The writers set it to the number of entries passed to them (by
+    /// `write`, `begin_url` or [`Extend::extend`]) before the entry since the
+    /// file was started, including the rejected and skipped entries. It can
+    /// be overridden by [`Error::with_entry_index`].
+    pub fn entry_index(&self) -> Option<usize> {
+        self.entry_index
+    }
+
+    /// Returns the field of the entry that caused the error, if any.
+    pub fn field(&self) -> Option<ErrorField> {
+        self.field
+    }
+}
+
+/// An error returned by [`crate::SitemapWriter`] or
+/// [`crate::SitemapIndexWriter`] (and the builders of their entries) with
+/// its [`Context`].
+///
+/// Applications driving both writers can use it in their `?` chains. It is
+/// the crate-level error type; the per-writer error types
+/// ([`crate::SitemapWriterError`] and [`crate::SitemapIndexWriterError`])
+/// are deprecated. Use [`Error::kind`] to tell the errors apart.
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{Error, ErrorField, ErrorKind, ErrorWriter, SitemapWriter};
+/// use std::io::Cursor;
+///
+/// fn write(locs: &[&str]) -> Result<(), Error> {
+///     let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+///     for loc in locs {
+///         writer.write(*loc)?;
+///     }
+///     writer.end()?;
+///     Ok(())
+/// }
+///
+/// let too_long = format!("http://www.example.com/{}", "a".repeat(2048));
+/// let e = write(&["http://www.example.com/", &too_long]).unwrap_err();
+/// assert_eq!(e.kind(), ErrorKind::InvalidLoc);
+/// assert_eq!(e.context().writer(), ErrorWriter::Sitemap);
+/// assert_eq!(e.context().entry_index(), Some(1));
+/// assert_eq!(e.context().field(), Some(ErrorField::Loc));
+/// assert_eq!(e.to_string(), "sitemap writer: entry 1: invalid loc");
+/// ```
+#[derive(Debug)]
+pub struct Error {
+    context: Context,
+    source: Source,
+}
+
+#[derive(Debug)]
+enum Source {
+    SitemapWriter(crate::sitemap_writer::Error),
+    SitemapIndexWriter(crate::sitemap_index_writer::Error),
+}
+
+impl Error {
+    /// Returns the kind of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match &self.source {
+            Source::SitemapWriter(e) => e.kind(),
+            Source::SitemapIndexWriter(e) => e.kind(),
+        }
+    }
+
+    /// Returns the context of this error.
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /// Sets the index (0-based) of the entry that caused this error.
+    pub fn with_entry_index(mut self, entry_index: usize) -> Self {
+        self.context.entry_index = Some(entry_index);
+        self
+    }
+
+    fn new(writer: ErrorWriter, source: Source) -> Self {
+        let mut error = Self {
+            context: Context {
+                writer,
+                entry_index: None,
+                field: None,
+            },
+            source,
+        };
+        error.context.field = match error.kind() {
+            ErrorKind::InvalidChangefreq => Some(ErrorField::Changefreq),
+            ErrorKind::InvalidAlternate
+            | ErrorKind::InvalidExtension
+            | ErrorKind::InvalidHreflang
+            | ErrorKind::InvalidImage
+            | ErrorKind::InvalidNews
+            | ErrorKind::InvalidNewsLanguage
+            | ErrorKind::InvalidNewsPublicationDate
+            | ErrorKind::InvalidVideo
+            | ErrorKind::InvalidVideoDuration
+            | ErrorKind::InvalidVideoExpirationDate
+            | ErrorKind::InvalidVideoPlatform
+            | ErrorKind::InvalidVideoPrice
+            | ErrorKind::InvalidVideoPublicationDate
+            | ErrorKind::InvalidVideoRating
+            | ErrorKind::InvalidVideoRestriction
+            | ErrorKind::InvalidVideoTag
+            | ErrorKind::InvalidVideoUploader
+            | ErrorKind::MaxNumberOfImages
+            | ErrorKind::MaxNumberOfNews
+            | ErrorKind::MaxNumberOfNewsStockTickers
+            | ErrorKind::MaxNumberOfVideoTags
+            | ErrorKind::StaleNewsPublicationDate => Some(ErrorField::Extension),
+            ErrorKind::InvalidFormat => Some(ErrorField::Format),
+            ErrorKind::FutureLastmod | ErrorKind::InvalidLastmod => Some(ErrorField::Lastmod),
+            ErrorKind::HostNotAllowed
+            | ErrorKind::InvalidLoc
+            | ErrorKind::SchemeNotAllowed
+            | ErrorKind::SelfReferentialLoc => Some(ErrorField::Loc),
+            ErrorKind::InvalidPriority => Some(ErrorField::Priority),
+            ErrorKind::InvalidElementOrder
+            | ErrorKind::Io
+            | ErrorKind::MaxByteLength
+            | ErrorKind::MaxNumberOfNewsUrls
+            | ErrorKind::MaxNumberOfSitemaps
+            | ErrorKind::MaxNumberOfUrls => None,
+        };
+        error
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.context.writer {
+            ErrorWriter::Sitemap => f.write_str("sitemap writer: ")?,
+            ErrorWriter::SitemapIndex => f.write_str("sitemap index writer: ")?,
+        }
+        if let Some(entry_index) = self.context.entry_index {
+            write!(f, "entry {}: ", entry_index)?;
+        }
+        match &self.source {
+            Source::SitemapWriter(e) => e.fmt(f),
+            Source::SitemapIndexWriter(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.source {
+            Source::SitemapWriter(e) => Some(e),
+            Source::SitemapIndexWriter(e) => Some(e),
+        }
+    }
+}
+
+impl From<crate::sitemap_writer::Error> for Error {
+    fn from(value: crate::sitemap_writer::Error) -> Self {
+        Self::new(ErrorWriter::Sitemap, Source::SitemapWriter(value))
+    }
+}
+
+impl From<crate::sitemap_index_writer::Error> for Error {
+    fn from(value: crate::sitemap_index_writer::Error) -> Self {
+        Self::new(ErrorWriter::SitemapIndex, Source::SitemapIndexWriter(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sitemap_index_writer, sitemap_writer};
+
+    #[test]
+    fn test_kind() {
+        let e = Error::from(sitemap_writer::Error::MaxNumberOfUrls);
+        assert_eq!(e.kind(), ErrorKind::MaxNumberOfUrls);
+        assert_eq!(
+            e.to_string(),
+            "sitemap writer: max number of urls is 50,000"
+        );
+
+        let e = Error::from(sitemap_index_writer::Error::InvalidFormat);
+        assert_eq!(e.kind(), ErrorKind::InvalidFormat);
+        assert_eq!(e.to_string(), "sitemap index writer: invalid format");
+
+        let e = Error::from(sitemap_writer::Error::from(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "x",
+        )));
+        assert_eq!(e.kind(), ErrorKind::Io);
+        assert_eq!(e.to_string(), "sitemap writer: io");
+        let source = std::error::Error::source(&e).unwrap();
+        assert_eq!(
+            std::error::Error::source(source).map(|e| e.to_string()),
+            Some("x".to_string())
+        );
+    }
+
+    #[test]
+    fn test_context() {
+        let e = Error::from(sitemap_writer::Error::FutureLastmod);
+        assert_eq!(e.context().writer(), ErrorWriter::Sitemap);
+        assert_eq!(e.context().entry_index(), None);
+        assert_eq!(e.context().field(), Some(ErrorField::Lastmod));
+
+        let e = Error::from(sitemap_index_writer::Error::MaxByteLength).with_entry_index(3);
+        assert_eq!(e.context().writer(), ErrorWriter::SitemapIndex);
+        assert_eq!(e.context().entry_index(), Some(3));
+        assert_eq!(e.context().field(), None);
+        assert_eq!(
+            e.to_string(),
+            "sitemap index writer: entry 3: max byte length is 50 MiB (52,428,800 bytes)"
+        );
+        assert!(std::error::Error::source(&e).is_some());
+    }
+}
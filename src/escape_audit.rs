@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+/// The characters escaped in a child entry, reported to the callback set by
+/// `escape_audit` of the writer builders.
+///
+/// It helps track down data sources that embed raw ampersands or quotes in
+/// URLs. It is reported only for child entries containing at least one
+/// escaped character.
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::SitemapWriterBuilder;
+/// use std::{
+///     io::Cursor,
+///     sync::{Arc, Mutex},
+/// };
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let reports = Arc::new(Mutex::new(Vec::new()));
+/// let mut writer = SitemapWriterBuilder::new()
+///     .escape_audit({
+///         let reports = Arc::clone(&reports);
+///         move |audit| {
+///             reports.lock().unwrap().push(format!(
+///                 "{} {} {:?}",
+///                 audit.loc(),
+///                 audit.element(),
+///                 audit.escaped().collect::<String>()
+///             ))
+///         }
+///     })
+///     .start(Cursor::new(Vec::new()))?;
+/// writer.write("http://www.example.com/")?;
+/// writer.write("http://www.example.com/?a=1&b=2")?;
+/// writer.end()?;
+/// assert_eq!(
+///     *reports.lock().unwrap(),
+///     ["http://www.example.com/?a=1&b=2 loc \"&\""]
+/// );
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EscapeAudit<'a> {
+    loc: &'a str,
+    element: &'a str,
+    content: &'a str,
+}
+
+impl<'a> EscapeAudit<'a> {
+    pub(crate) fn new(loc: &'a str, element: &'a str, content: &'a str) -> Self {
+        Self {
+            loc,
+            element,
+            content,
+        }
+    }
+
+    /// Returns the `loc` (unescaped) of the entry.
+    pub fn loc(&self) -> &'a str {
+        self.loc
+    }
+
+    /// Returns the name of the child entry (e.g. `"loc"`).
+    pub fn element(&self) -> &'a str {
+        self.element
+    }
+
+    /// Returns the content (unescaped) of the child entry.
+    pub fn content(&self) -> &'a str {
+        self.content
+    }
+
+    /// Returns an iterator over the escaped characters in the order of their
+    /// appearance.
+    pub fn escaped(&self) -> impl Iterator<Item = char> + 'a {
+        self.content.chars().filter(|c| is_escaped(*c))
+    }
+}
+
+pub(crate) fn is_escaped(c: char) -> bool {
+    matches!(c, '"' | '&' | '\'' | '<' | '>')
+}
+
+type Callback = dyn Fn(&EscapeAudit<'_>) + Send + Sync;
+
+#[derive(Clone)]
+pub(crate) struct EscapeAuditFn(Arc<Callback>);
+
+impl EscapeAuditFn {
+    pub(crate) fn new<F>(f: F) -> Self
+    where
+        F: Fn(&EscapeAudit<'_>) + Send + Sync + 'static,
+    {
+        Self(Arc::new(f))
+    }
+
+    pub(crate) fn call(&self, audit: &EscapeAudit<'_>) {
+        (self.0)(audit)
+    }
+}
+
+impl std::fmt::Debug for EscapeAuditFn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("EscapeAuditFn")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escaped() {
+        let audit = EscapeAudit::new("http://a/", "loc", "a&b'c\"d<e>f&");
+        assert_eq!(audit.escaped().collect::<String>(), "&'\"<>&");
+        let audit = EscapeAudit::new("http://a/", "loc", "http://a/");
+        assert_eq!(audit.escaped().count(), 0);
+    }
+}
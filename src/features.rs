@@ -0,0 +1,66 @@
+//! The Cargo features this crate was compiled with.
+//!
+//! It is useful for diagnostics (e.g. logging at startup) in long-lived
+//! services.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use sitemap_xml_writer::features;
+//!
+//! let enabled = features::enabled().collect::<Vec<&str>>();
+//! assert_eq!(enabled.contains(&"url"), features::URL);
+//! ```
+
+/// `true` if the `chrono` feature is enabled.
+pub const CHRONO: bool = cfg!(feature = "chrono");
+
+/// `true` if the `regex` feature is enabled.
+pub const REGEX: bool = cfg!(feature = "regex");
+
+/// `true` if the `serde` feature is enabled.
+pub const SERDE: bool = cfg!(feature = "serde");
+
+/// `true` if the `time` feature is enabled.
+pub const TIME: bool = cfg!(feature = "time");
+
+/// `true` if the `unicode-normalization` feature is enabled.
+pub const UNICODE_NORMALIZATION: bool = cfg!(feature = "unicode-normalization");
+
+/// `true` if the `url` feature is enabled.
+pub const URL: bool = cfg!(feature = "url");
+
+const ALL: &[(&str, bool)] = &[
+    ("chrono", CHRONO),
+    ("regex", REGEX),
+    ("serde", SERDE),
+    ("time", TIME),
+    ("unicode-normalization", UNICODE_NORMALIZATION),
+    ("url", URL),
+];
+
+/// Returns the names of the enabled features.
+pub fn enabled() -> impl Iterator<Item = &'static str> {
+    ALL.iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| *name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let enabled = enabled().collect::<Vec<&str>>();
+        assert_eq!(enabled.contains(&"chrono"), cfg!(feature = "chrono"));
+        assert_eq!(enabled.contains(&"regex"), cfg!(feature = "regex"));
+        assert_eq!(enabled.contains(&"serde"), cfg!(feature = "serde"));
+        assert_eq!(enabled.contains(&"time"), cfg!(feature = "time"));
+        assert_eq!(
+            enabled.contains(&"unicode-normalization"),
+            cfg!(feature = "unicode-normalization")
+        );
+        assert_eq!(enabled.contains(&"url"), cfg!(feature = "url"));
+    }
+}
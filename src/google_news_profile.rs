@@ -0,0 +1,92 @@
+use std::time::{Duration, SystemTime};
+
+use crate::{date_time::W3cDateTime, lastmod_skew_guard::unix_seconds};
+
+/// The constraints of Google News sitemaps.
+///
+/// Google News only accepts articles published in the last two days, and at
+/// most 1,000 URLs in a news sitemap file. With this profile,
+/// [`crate::SitemapWriter`] rejects a `news:news` entry whose
+/// `news:publication_date` is older than [`GoogleNewsProfile::MAX_AGE`], and
+/// the URLs after [`GoogleNewsProfile::MAX_NUMBER_OF_URLS`].
+///
+/// The publication date of a `news:news` entry added by
+/// [`crate::Url::extension`] or [`crate::UrlWriter::extension`] is not
+/// checked. Use [`crate::Url::news`] or [`crate::UrlWriter::news`].
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{GoogleNewsProfile, News, SitemapWriterBuilder, Url};
+/// use std::{
+///     io::Cursor,
+///     time::{Duration, UNIX_EPOCH},
+/// };
+///
+/// # fn main() -> anyhow::Result<()> {
+/// // 2008-12-24T00:00:00Z
+/// let now = UNIX_EPOCH + Duration::from_secs(1_230_076_800);
+/// let mut writer = SitemapWriterBuilder::new()
+///     .namespace(News::PREFIX, News::NAMESPACE_URI)
+///     .google_news_profile(GoogleNewsProfile::new().now(now))
+///     .start(Cursor::new(Vec::new()))?;
+/// let news = |date| News::new("The Example Times", "en", date, "Title");
+/// writer.write(Url::loc("http://www.example.com/1")?.news(news("2008-12-23")?)?)?;
+/// assert!(writer
+///     .write(Url::loc("http://www.example.com/2")?.news(news("2008-12-20")?)?)
+///     .is_err());
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct GoogleNewsProfile {
+    now: Option<SystemTime>,
+}
+
+impl GoogleNewsProfile {
+    /// The maximum age of the `news:publication_date` (2 days).
+    pub const MAX_AGE: Duration = Duration::from_secs(2 * 24 * 60 * 60);
+
+    /// The maximum number of URLs in a news sitemap file.
+    pub const MAX_NUMBER_OF_URLS: usize = 1_000;
+
+    /// Creates a new `GoogleNewsProfile`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fixes the current time instead of using [`SystemTime::now`].
+    pub fn now(mut self, now: SystemTime) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    /// Returns `true` if the `news:publication_date` is recent enough.
+    pub(crate) fn is_recent(&self, publication_date: &str) -> bool {
+        let now = unix_seconds(self.now.unwrap_or_else(SystemTime::now));
+        let min = now - i128::from(Self::MAX_AGE.as_secs());
+        match W3cDateTime::parse(publication_date) {
+            Some(date_time) => date_time.order_key().0 >= min,
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    #[test]
+    fn test_is_recent() {
+        // 2008-12-24T00:00:00Z
+        let now = UNIX_EPOCH + Duration::from_secs(1_230_076_800);
+        let profile = GoogleNewsProfile::new().now(now);
+        assert!(profile.is_recent("2008-12-24"));
+        assert!(profile.is_recent("2008-12-22"));
+        assert!(profile.is_recent("2008-12-22T09:00:00+09:00"));
+        assert!(!profile.is_recent("2008-12-22T08:59:59+09:00"));
+        assert!(!profile.is_recent("2008-12-21"));
+        assert!(!profile.is_recent("invalid"));
+    }
+}
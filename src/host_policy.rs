@@ -0,0 +1,302 @@
+use std::borrow::Cow;
+
+/// The action taken when a `loc` violates a [`HostPolicy`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum HostPolicyAction {
+    /// Returns an error.
+    #[default]
+    Reject,
+    /// Skips the entry without writing it.
+    Skip,
+}
+
+/// An allowlist/denylist of hosts (or URL patterns) for the `loc` of entries.
+///
+/// A `loc` is allowed if it matches none of the denied hosts and patterns,
+/// and, if any allowed hosts or patterns are registered, matches at least one
+/// of them.
+///
+/// A host entry matches the host of the `loc` case-insensitively, ignoring a
+/// trailing dot (e.g. `www.example.com.`). With the `url` feature, hosts are
+/// compared in their IDNA (punycode) form (e.g. `bücher.example` matches
+/// `xn--bcher-kva.example`). A host entry starting with `*.` matches any
+/// subdomain of the rest (e.g. `*.example.com` matches `www.example.com` but
+/// not `example.com`).
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{HostPolicy, HostPolicyAction, SitemapWriterBuilder};
+/// use std::io::Cursor;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let policy = HostPolicy::new()
+///     .allow_host("www.example.com")
+///     .deny_host("*.staging.example.com")
+///     .on_violation(HostPolicyAction::Skip);
+/// let mut writer = SitemapWriterBuilder::new()
+///     .host_policy(policy)
+///     .start(Cursor::new(Vec::new()))?;
+/// writer.write("http://www.example.com/")?;
+/// writer.write("http://www.staging.example.com/")?; // skipped
+/// let (_, summary) = writer.finish()?;
+/// assert_eq!(summary.number_of_entries(), 1);
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct HostPolicy {
+    allow: Vec<Pattern>,
+    deny: Vec<Pattern>,
+    action: HostPolicyAction,
+}
+
+#[derive(Clone, Debug)]
+enum Pattern {
+    Host(String),
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl Pattern {
+    fn host(host: String) -> Self {
+        match host.strip_prefix("*.") {
+            None => Pattern::Host(normalize_host(&host).into_owned()),
+            Some(domain) => Pattern::Host(format!("*.{}", normalize_host(domain))),
+        }
+    }
+
+    /// `host` must be normalized by [`normalize_host`].
+    #[cfg_attr(not(feature = "regex"), allow(unused_variables))]
+    fn is_match(&self, loc: &str, host: Option<&str>) -> bool {
+        match self {
+            Pattern::Host(pattern) => match host {
+                None => false,
+                Some(host) => match pattern.strip_prefix("*.") {
+                    None => host == pattern,
+                    Some(domain) => {
+                        host.len() > domain.len()
+                            && host.ends_with(domain)
+                            && host.as_bytes()[host.len() - domain.len() - 1] == b'.'
+                    }
+                },
+            },
+            #[cfg(feature = "regex")]
+            Pattern::Regex(regex) => regex.is_match(loc),
+        }
+    }
+}
+
+impl HostPolicy {
+    /// Creates a new `HostPolicy` that allows all hosts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a host to the allowlist.
+    pub fn allow_host<S: Into<String>>(mut self, host: S) -> Self {
+        self.allow.push(Pattern::host(host.into()));
+        self
+    }
+
+    /// Adds a host to the denylist.
+    pub fn deny_host<S: Into<String>>(mut self, host: S) -> Self {
+        self.deny.push(Pattern::host(host.into()));
+        self
+    }
+
+    /// Adds a regular expression matched against the whole `loc` to the
+    /// allowlist.
+    #[cfg(feature = "regex")]
+    pub fn allow_pattern(mut self, pattern: &str) -> Result<Self, regex::Error> {
+        self.allow.push(Pattern::Regex(regex::Regex::new(pattern)?));
+        Ok(self)
+    }
+
+    /// Adds a regular expression matched against the whole `loc` to the
+    /// denylist.
+    #[cfg(feature = "regex")]
+    pub fn deny_pattern(mut self, pattern: &str) -> Result<Self, regex::Error> {
+        self.deny.push(Pattern::Regex(regex::Regex::new(pattern)?));
+        Ok(self)
+    }
+
+    /// Sets the action taken when a `loc` violates this policy. The default
+    /// is [`HostPolicyAction::Reject`].
+    pub fn on_violation(mut self, action: HostPolicyAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Returns the action taken when a `loc` violates this policy.
+    pub fn action(&self) -> HostPolicyAction {
+        self.action
+    }
+
+    /// Returns `true` if the `loc` is allowed by this policy.
+    pub fn is_allowed(&self, loc: &str) -> bool {
+        let host = host(loc).map(normalize_host);
+        let host = host.as_deref();
+        if self.deny.iter().any(|p| p.is_match(loc, host)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|p| p.is_match(loc, host))
+    }
+}
+
+/// Returns the host in lowercase without a trailing dot (e.g.
+/// `"WWW.Example.COM."` to `"www.example.com"`). With the `url` feature,
+/// internationalized domain names are converted to their IDNA (punycode) form.
+pub(crate) fn normalize_host(host: &str) -> Cow<'_, str> {
+    let host = host.strip_suffix('.').unwrap_or(host);
+    #[cfg(feature = "url")]
+    if !host.is_ascii() {
+        if let Ok(host) = url::Host::parse(host) {
+            return Cow::Owned(host.to_string());
+        }
+    }
+    if host.bytes().any(|b| b.is_ascii_uppercase()) {
+        Cow::Owned(host.to_ascii_lowercase())
+    } else {
+        Cow::Borrowed(host)
+    }
+}
+
+/// Returns the `loc` with the scheme in lowercase and the host normalized by
+/// [`normalize_host`] (e.g. `"HTTP://WWW.Example.COM./a"` to
+/// `"http://www.example.com/a"`). The rest is not changed.
+pub(crate) fn normalize_loc(loc: &str) -> Cow<'_, str> {
+    let (scheme, host) = match (loc.split_once("://"), host(loc)) {
+        (Some((scheme, _)), Some(host)) => (scheme, host),
+        _ => return Cow::Borrowed(loc),
+    };
+    let start = host.as_ptr() as usize - loc.as_ptr() as usize;
+    let normalized_host = normalize_host(host);
+    if !scheme.bytes().any(|b| b.is_ascii_uppercase()) && normalized_host == host {
+        return Cow::Borrowed(loc);
+    }
+    Cow::Owned(format!(
+        "{}{}{}{}",
+        scheme.to_ascii_lowercase(),
+        &loc[scheme.len()..start],
+        normalized_host,
+        &loc[start + host.len()..]
+    ))
+}
+
+pub(crate) fn host(loc: &str) -> Option<&str> {
+    let (_, rest) = loc.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host_port = authority
+        .rsplit_once('@')
+        .map(|(_, s)| s)
+        .unwrap_or(authority);
+    let host = if host_port.starts_with('[') {
+        host_port
+            .find(']')
+            .map(|i| &host_port[..=i])
+            .unwrap_or(host_port)
+    } else {
+        host_port
+            .split_once(':')
+            .map(|(h, _)| h)
+            .unwrap_or(host_port)
+    };
+    Some(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host() {
+        assert_eq!(host("http://www.example.com/"), Some("www.example.com"));
+        assert_eq!(host("http://www.example.com"), Some("www.example.com"));
+        assert_eq!(host("http://www.example.com?q"), Some("www.example.com"));
+        assert_eq!(
+            host("http://u:p@www.example.com:8080/"),
+            Some("www.example.com")
+        );
+        assert_eq!(host("http://[::1]:8080/"), Some("[::1]"));
+        assert_eq!(host("www.example.com"), None);
+    }
+
+    #[test]
+    fn test_normalize_loc() {
+        assert_eq!(
+            normalize_loc("http://www.example.com/A"),
+            "http://www.example.com/A"
+        );
+        assert_eq!(
+            normalize_loc("HTTP://u:P@WWW.Example.COM.:8080/A?B#C"),
+            "http://u:P@www.example.com:8080/A?B#C"
+        );
+        assert_eq!(normalize_loc("urn:Example"), "urn:Example");
+    }
+
+    #[test]
+    fn test_normalize_host() {
+        assert_eq!(normalize_host("www.example.com"), "www.example.com");
+        assert_eq!(normalize_host("WWW.Example.COM."), "www.example.com");
+        assert_eq!(normalize_host("[::1]"), "[::1]");
+        #[cfg(feature = "url")]
+        assert_eq!(
+            normalize_host("B\u{fc}cher.example"),
+            "xn--bcher-kva.example"
+        );
+    }
+
+    #[test]
+    fn test_is_allowed() {
+        let policy = HostPolicy::new();
+        assert!(policy.is_allowed("http://www.example.com/"));
+
+        let policy = HostPolicy::new().allow_host("www.example.com");
+        assert!(policy.is_allowed("http://www.example.com/"));
+        assert!(policy.is_allowed("http://WWW.EXAMPLE.COM/"));
+        assert!(policy.is_allowed("http://www.example.com./"));
+        assert!(!policy.is_allowed("http://example.com/"));
+
+        let policy = HostPolicy::new().deny_host("*.Example.COM.");
+        assert!(policy.is_allowed("http://example.com/"));
+        assert!(policy.is_allowed("http://wwwexample.com/"));
+        assert!(!policy.is_allowed("http://WWW.example.com./"));
+
+        let policy = HostPolicy::new().deny_host("*.internal");
+        assert!(policy.is_allowed("http://internal/"));
+        assert!(!policy.is_allowed("http://app.internal/"));
+        assert!(!policy.is_allowed("http://a.b.internal/"));
+
+        let policy = HostPolicy::new()
+            .allow_host("*.example.com")
+            .deny_host("staging.example.com");
+        assert!(policy.is_allowed("http://www.example.com/"));
+        assert!(!policy.is_allowed("http://staging.example.com/"));
+        assert!(!policy.is_allowed("http://example.org/"));
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn test_is_allowed_idna() {
+        let policy = HostPolicy::new().allow_host("b\u{fc}cher.example");
+        assert!(policy.is_allowed("http://xn--bcher-kva.example/"));
+        assert!(policy.is_allowed("http://B\u{dc}CHER.example/"));
+
+        let policy = HostPolicy::new().deny_host("*.xn--bcher-kva.example");
+        assert!(!policy.is_allowed("http://www.b\u{fc}cher.example/"));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_is_allowed_pattern() -> anyhow::Result<()> {
+        let policy = HostPolicy::new()
+            .allow_pattern(r"^https://www\.example\.com/")?
+            .deny_pattern(r"/private/")?;
+        assert!(policy.is_allowed("https://www.example.com/"));
+        assert!(!policy.is_allowed("http://www.example.com/"));
+        assert!(!policy.is_allowed("https://www.example.com/private/"));
+        assert!(HostPolicy::new().allow_pattern("(").is_err());
+        Ok(())
+    }
+}
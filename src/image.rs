@@ -0,0 +1,300 @@
+use std::borrow::Cow;
+
+use crate::{
+    loc::Loc,
+    sitemap_writer::Error,
+    sitemap_xml_writer::is_text,
+    url_extension::{ExtensionWriter, UrlExtension},
+};
+
+type Result<T, E = crate::Error> = std::result::Result<T, E>;
+
+/// An `image:image` entry of the
+/// [image sitemap extension](https://developers.google.com/search/docs/crawling-indexing/sitemaps/image-sitemaps).
+///
+/// It is added to a `url` entry by [`crate::Url::image`]. The namespace must
+/// be declared by [`crate::SitemapWriterBuilder::namespace`] with
+/// [`Image::PREFIX`] and [`Image::NAMESPACE_URI`].
+///
+/// The text child entries (`image:caption`, `image:geo_location` and
+/// `image:title`) must not be empty and must not contain characters that are
+/// not allowed in XML (e.g. control characters).
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{Image, SitemapWriterBuilder, Url};
+/// use std::io::Cursor;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut writer = SitemapWriterBuilder::new()
+///     .namespace(Image::PREFIX, Image::NAMESPACE_URI)
+///     .start(Cursor::new(Vec::new()))?;
+/// writer.write(
+///     Url::loc("http://www.example.com/")?
+///         .image(Image::loc("http://www.example.com/a.jpg")?)?
+///         .image(
+///             Image::loc("http://www.example.com/b.jpg")?
+///                 .caption("Fish & chips")?
+///                 .license("http://www.example.com/license")?,
+///         )?,
+/// )?;
+/// let (inner, _) = writer.finish()?;
+/// assert_eq!(
+///     String::from_utf8(inner.into_inner())?,
+///     concat!(
+///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+///         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:image="http://www.google.com/schemas/sitemap-image/1.1">"#,
+///         r#"<url>"#,
+///         r#"<loc>http://www.example.com/</loc>"#,
+///         r#"<image:image><image:loc>http://www.example.com/a.jpg</image:loc></image:image>"#,
+///         r#"<image:image>"#,
+///         r#"<image:loc>http://www.example.com/b.jpg</image:loc>"#,
+///         r#"<image:caption>Fish &amp; chips</image:caption>"#,
+///         r#"<image:license>http://www.example.com/license</image:license>"#,
+///         r#"</image:image>"#,
+///         r#"</url>"#,
+///         r#"</urlset>"#
+///     )
+/// );
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Image<'a> {
+    loc: Cow<'a, str>,
+    caption: Option<Cow<'a, str>>,
+    geo_location: Option<Cow<'a, str>>,
+    title: Option<Cow<'a, str>>,
+    license: Option<Cow<'a, str>>,
+}
+
+impl<'a> Image<'a> {
+    /// The namespace prefix of the image sitemap extension.
+    pub const PREFIX: &'static str = "image";
+
+    /// The namespace URI of the image sitemap extension.
+    pub const NAMESPACE_URI: &'static str = "http://www.google.com/schemas/sitemap-image/1.1";
+
+    /// The max number of `image:image` entries in a `url` entry.
+    pub const MAX_NUMBER_OF_IMAGES: usize = 1_000;
+
+    /// Builds an `image:image` entry with the specified URL as the content
+    /// of the `image:loc` child entry.
+    pub fn loc<S>(loc: S) -> Result<Self>
+    where
+        S: TryInto<Loc<'a>>,
+    {
+        let loc = loc.try_into().map_err(|_| Error::InvalidImage)?;
+        Ok(Self {
+            loc: loc.into_inner(),
+            caption: None,
+            geo_location: None,
+            title: None,
+            license: None,
+        })
+    }
+
+    /// Changes the `image:caption` child entry to the specified text.
+    pub fn caption<S>(mut self, s: S) -> Result<Self>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.caption = Some(text(s)?);
+        Ok(self)
+    }
+
+    /// Changes the `image:geo_location` child entry to the specified text
+    /// (e.g. `"Limerick, Ireland"`).
+    pub fn geo_location<S>(mut self, s: S) -> Result<Self>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.geo_location = Some(text(s)?);
+        Ok(self)
+    }
+
+    /// Changes the `image:title` child entry to the specified text.
+    pub fn title<S>(mut self, s: S) -> Result<Self>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.title = Some(text(s)?);
+        Ok(self)
+    }
+
+    /// Changes the `image:license` child entry to the specified URL.
+    pub fn license<S>(mut self, license: S) -> Result<Self>
+    where
+        S: TryInto<Loc<'a>>,
+    {
+        let license = license.try_into().map_err(|_| Error::InvalidImage)?;
+        self.license = Some(license.into_inner());
+        Ok(self)
+    }
+
+    /// Converts this `Image` into an owned `Image<'static>`.
+    pub fn into_owned(self) -> Image<'static> {
+        Image {
+            loc: Cow::Owned(self.loc.into_owned()),
+            caption: self.caption.map(|s| Cow::Owned(s.into_owned())),
+            geo_location: self.geo_location.map(|s| Cow::Owned(s.into_owned())),
+            title: self.title.map(|s| Cow::Owned(s.into_owned())),
+            license: self.license.map(|s| Cow::Owned(s.into_owned())),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for Image<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut s = serializer.serialize_struct("Image", 5)?;
+        s.serialize_field("loc", &self.loc)?;
+        for (key, value) in [
+            ("caption", &self.caption),
+            ("geo_location", &self.geo_location),
+            ("title", &self.title),
+            ("license", &self.license),
+        ] {
+            match value {
+                Some(value) => s.serialize_field(key, value)?,
+                None => s.skip_field(key)?,
+            }
+        }
+        s.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for Image<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Image")]
+        struct Repr {
+            loc: Loc<'static>,
+            caption: Option<String>,
+            geo_location: Option<String>,
+            title: Option<String>,
+            license: Option<Loc<'static>>,
+        }
+
+        let repr = Repr::deserialize(deserializer)?;
+        let image = || -> Result<Image<'static>> {
+            let mut image = Image::loc(repr.loc)?;
+            if let Some(caption) = repr.caption {
+                image = image.caption(caption)?;
+            }
+            if let Some(geo_location) = repr.geo_location {
+                image = image.geo_location(geo_location)?;
+            }
+            if let Some(title) = repr.title {
+                image = image.title(title)?;
+            }
+            if let Some(license) = repr.license {
+                image = image.license(license)?;
+            }
+            Ok(image)
+        };
+        image().map_err(|_| serde::de::Error::custom("invalid image"))
+    }
+}
+
+fn text<'a, S>(s: S) -> Result<Cow<'a, str>>
+where
+    S: Into<Cow<'a, str>>,
+{
+    let s = s.into();
+    if !is_text(&s) {
+        return Err(Error::InvalidImage.into());
+    }
+    Ok(s)
+}
+
+impl<'a> UrlExtension for Image<'a> {
+    fn namespace(&self) -> (&str, &str) {
+        (Self::PREFIX, Self::NAMESPACE_URI)
+    }
+
+    fn write(&self, writer: &mut ExtensionWriter<'_>) -> Result<()> {
+        writer.start_tag("image")?.element("loc", &self.loc)?;
+        let children = [
+            ("caption", &self.caption),
+            ("geo_location", &self.geo_location),
+            ("title", &self.title),
+            ("license", &self.license),
+        ];
+        for (name, content) in children {
+            if let Some(content) = content {
+                writer.element(name, content)?;
+            }
+        }
+        writer.end_tag("image")?;
+        Ok(())
+    }
+}
+
+/// Returns `true` if the extension is in the image sitemap namespace. Each
+/// such extension counts as one `image:image` entry towards
+/// [`Image::MAX_NUMBER_OF_IMAGES`].
+pub(crate) fn is_image(extension: &dyn UrlExtension) -> bool {
+    extension.namespace().1 == Image::NAMESPACE_URI
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+    use crate::sitemap_xml_writer::SitemapXmlWriter;
+
+    #[test]
+    fn test_loc() -> anyhow::Result<()> {
+        assert_eq!(
+            Image::loc("http://www.example.com/a.jpg")?.loc,
+            "http://www.example.com/a.jpg"
+        );
+        assert!(matches!(
+            Image::loc("a".repeat(2048).as_str()).map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidImage)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_child_entries() -> anyhow::Result<()> {
+        let image = Image::loc("http://www.example.com/a.jpg")?
+            .license("http://www.example.com/license")?
+            .title("<Title>")?
+            .geo_location("Limerick, Ireland")?
+            .caption("a\tb")?;
+        let mut xml_writer = SitemapXmlWriter::new(Vec::new(), false, false);
+        let mut writer = ExtensionWriter::new(&mut xml_writer, Image::PREFIX);
+        image.into_owned().write(&mut writer)?;
+        assert_eq!(
+            String::from_utf8(xml_writer.into_inner())?,
+            concat!(
+                "<image:image>",
+                "<image:loc>http://www.example.com/a.jpg</image:loc>",
+                "<image:caption>a\tb</image:caption>",
+                "<image:geo_location>Limerick, Ireland</image:geo_location>",
+                "<image:title>&lt;Title&gt;</image:title>",
+                "<image:license>http://www.example.com/license</image:license>",
+                "</image:image>"
+            )
+        );
+
+        let image = Image::loc("http://www.example.com/a.jpg")?;
+        assert!(image.clone().caption("").is_err());
+        assert!(image.clone().title(" ").is_err());
+        assert!(image.clone().geo_location("a\u{0}b").is_err());
+        assert!(image.clone().caption("a\u{FFFF}").is_err());
+        assert!(image.license("a".repeat(2048).as_str()).is_err());
+        Ok(())
+    }
+}
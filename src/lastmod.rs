@@ -1,43 +1,275 @@
-use once_cell::sync::Lazy;
-use regex::Regex;
-use std::{borrow::Cow, fmt::Debug};
+use std::{borrow::Cow, cmp::Ordering, fmt::Debug};
 
-#[cfg(feature = "time")]
-use time::format_description::well_known::Iso8601;
+use crate::date_time::W3cDateTime;
+
+#[cfg(feature = "regex")]
+use crate::date_time::days_in_month;
 
-static DATE_RE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r#"\A-?([1-9][0-9]{3,}|0[0-9]{3})-(0[1-9]|1[0-2])-(0[1-9]|[12][0-9]|3[01])(Z|(\+|-)((0[0-9]|1[0-3]):[0-5][0-9]|14:00))?\z"#
-            ).unwrap()
+#[cfg(feature = "regex")]
+static DATE_RE: once_cell::sync::Lazy<regex::Regex> = once_cell::sync::Lazy::new(|| {
+    regex::Regex::new(r#"\A-?([1-9][0-9]{3,}|0[0-9]{3})-(0[1-9]|1[0-2])-(0[1-9]|[12][0-9]|3[01])(Z|(\+|-)((0[0-9]|1[0-3]):[0-5][0-9]|14:00))?\z"#).unwrap()
 });
 
-static DATE_TIME_RE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(
-                r#"\A-?([1-9][0-9]{3,}|0[0-9]{3})-(0[1-9]|1[0-2])-(0[1-9]|[12][0-9]|3[01])T(([01][0-9]|2[0-3]):[0-5][0-9]:[0-5][0-9](\.[0-9]+)?|(24:00:00(\.0+)?))(Z|(\+|-)((0[0-9]|1[0-3]):[0-5][0-9]|14:00))?\z"#
-            ).unwrap()
+#[cfg(feature = "regex")]
+static DATE_TIME_RE: once_cell::sync::Lazy<regex::Regex> = once_cell::sync::Lazy::new(|| {
+    regex::Regex::new(r#"\A-?([1-9][0-9]{3,}|0[0-9]{3})-(0[1-9]|1[0-2])-(0[1-9]|[12][0-9]|3[01])T(([01][0-9]|2[0-3]):[0-5][0-9]:[0-5][0-9](\.[0-9]+)?|(24:00:00(\.0+)?))(Z|(\+|-)((0[0-9]|1[0-3]):[0-5][0-9]|14:00))?\z"#).unwrap()
 });
 
 // TODO: Error
-#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
-#[error("error")]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Error;
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("error")
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The handling of the missing offset when converting a date and time without
+/// timezone (e.g. `chrono::NaiveDateTime`) to a [`Lastmod`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum NaiveDateTimePolicy {
+    /// Writes it without timezone (e.g. `2004-12-23T18:00:15`).
+    #[default]
+    OmitOffset,
+    /// Writes it as UTC (e.g. `2004-12-23T18:00:15+00:00`).
+    AssumeUtc,
+    /// Returns an error.
+    Reject,
+}
+
 /// A `lastmod` child entry.
+///
+/// The canonical form used by [`std::fmt::Display`] and
+/// [`std::str::FromStr`] is the W3C Datetime string (e.g. `"2005-01-01"` or
+/// `"2004-12-23T18:00:15+00:00"`).
+///
+/// A date must exist in the calendar (e.g. `"2023-02-30"` is rejected).
+///
+/// `Lastmod` values are ordered by the instant they represent. A date without
+/// time is treated as `00:00:00`, and a value without timezone is treated as
+/// UTC. Values representing the same instant are ordered by their strings.
+///
+/// ```rust
+/// # use sitemap_xml_writer::Lastmod;
+/// # fn main() -> anyhow::Result<()> {
+/// assert!(Lastmod::try_from("2005-01-01T09:00:00+09:00")? < Lastmod::try_from("2005-01-01T00:00:01Z")?);
+/// let lastmods = ["2004-12-23", "2005-01-01", "2004-12-23T18:00:15+00:00"]
+///     .into_iter()
+///     .map(Lastmod::try_from)
+///     .collect::<Result<Vec<Lastmod>, _>>()?;
+/// assert_eq!(lastmods.into_iter().max().map(|l| l.to_string()), Some("2005-01-01".to_string()));
+/// #     Ok(())
+/// # }
+/// ```
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Lastmod<'a>(Cow<'a, str>);
 
 impl<'a> Lastmod<'a> {
+    /// Creates a `Lastmod` without validation.
+    ///
+    /// It is intended for pre-validated inputs in hot loops (e.g. the same
+    /// few timestamps repeated millions of times). The caller must ensure
+    /// that `lastmod` is a valid W3C Datetime string. An invalid value is
+    /// written as is (but escaped), and [`Lastmod::date_time`] returns
+    /// `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::Lastmod;
+    ///
+    /// let lastmod = Lastmod::new_unchecked("2005-01-01");
+    /// assert_eq!(lastmod.to_string(), "2005-01-01");
+    /// ```
+    pub fn new_unchecked<S>(lastmod: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        Self(lastmod.into())
+    }
+
+    /// Converts this `Lastmod` into an owned `Lastmod<'static>`.
+    pub fn into_owned(self) -> Lastmod<'static> {
+        Lastmod(Cow::Owned(self.0.into_owned()))
+    }
+
     pub(crate) fn into_inner(self) -> Cow<'a, str> {
         self.0
     }
+
+    /// Returns the parsed components of this `Lastmod`, or `None` if it was
+    /// created by [`Lastmod::new_unchecked`] with an invalid value.
+    pub fn date_time(&self) -> Option<W3cDateTime<'_>> {
+        W3cDateTime::parse(self.0.as_ref())
+    }
+
+    #[cfg(feature = "regex")]
+    fn is_valid(s: &str) -> bool {
+        // <https://www.w3.org/TR/xmlschema11-2/#date>
+        // <https://www.w3.org/TR/xmlschema11-2/#dateTime>
+        let captures = match DATE_RE.captures(s).or_else(|| DATE_TIME_RE.captures(s)) {
+            Some(captures) => captures,
+            None => return false,
+        };
+        let (year, month, day) = (&captures[1], &captures[2], &captures[3]);
+        match (year.parse::<i128>(), month.parse::<u8>(), day.parse::<u8>()) {
+            // the same year limit as the built-in validator
+            (Ok(y), Ok(month), Ok(day)) if year.len() <= 30 => {
+                let y = if s.starts_with('-') { -y } else { y };
+                day <= days_in_month(y, month)
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg(not(feature = "regex"))]
+    fn is_valid(s: &str) -> bool {
+        // <https://www.w3.org/TR/xmlschema11-2/#date>
+        // <https://www.w3.org/TR/xmlschema11-2/#dateTime>
+        W3cDateTime::parse(s).is_some()
+    }
+
+    /// Converts a `chrono::NaiveDateTime` to a `Lastmod`. The `policy` is the
+    /// handling of the missing offset.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::{Lastmod, NaiveDateTimePolicy};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let value = chrono::NaiveDate::from_ymd_opt(2004, 12, 23)
+    ///     .and_then(|d| d.and_hms_opt(18, 0, 15))
+    ///     .expect("valid date time");
+    /// let lastmod = Lastmod::from_naive_date_time(value, NaiveDateTimePolicy::AssumeUtc)?;
+    /// assert_eq!(lastmod.to_string(), "2004-12-23T18:00:15+00:00");
+    /// assert!(Lastmod::from_naive_date_time(value, NaiveDateTimePolicy::Reject).is_err());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn from_naive_date_time(
+        value: ::chrono::NaiveDateTime,
+        policy: NaiveDateTimePolicy,
+    ) -> Result<Self, Error> {
+        match policy {
+            NaiveDateTimePolicy::OmitOffset => {
+                Self::from_formatted(value.format("%Y-%m-%dT%H:%M:%S%.f").to_string())
+            }
+            NaiveDateTimePolicy::AssumeUtc => Self::try_from(value.and_utc()),
+            NaiveDateTimePolicy::Reject => Err(Error),
+        }
+    }
+
+    /// Converts a `time::PrimitiveDateTime` to a `Lastmod`. The `policy` is
+    /// the handling of the missing offset.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::{Lastmod, NaiveDateTimePolicy};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let value = time::macros::datetime!(2004-12-23 18:00:15);
+    /// let lastmod = Lastmod::from_primitive_date_time(value, NaiveDateTimePolicy::AssumeUtc)?;
+    /// assert_eq!(lastmod.to_string(), "2004-12-23T18:00:15+00:00");
+    /// assert!(Lastmod::from_primitive_date_time(value, NaiveDateTimePolicy::Reject).is_err());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "time")]
+    pub fn from_primitive_date_time(
+        value: time::PrimitiveDateTime,
+        policy: NaiveDateTimePolicy,
+    ) -> Result<Self, Error> {
+        match policy {
+            NaiveDateTimePolicy::OmitOffset => {
+                Self::from_formatted(format_time_date_time(value.date(), value.time())?)
+            }
+            NaiveDateTimePolicy::AssumeUtc => Self::try_from(value.assume_utc()),
+            NaiveDateTimePolicy::Reject => Err(Error),
+        }
+    }
+
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    fn from_formatted(s: String) -> Result<Self, Error> {
+        // e.g. `+10000-01-01` (years after 9999) is not a W3C Datetime
+        if !Self::is_valid(&s) {
+            return Err(Error);
+        }
+        Ok(Self(Cow::Owned(s)))
+    }
+
+    fn order_key(&self) -> Option<(i128, &str)> {
+        W3cDateTime::parse(self.0.as_ref()).map(|d| d.order_key())
+    }
+}
+
+impl<'a> Ord for Lastmod<'a> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.order_key()
+            .cmp(&other.order_key())
+            .then_with(|| self.0.cmp(&other.0))
+    }
+}
+
+impl<'a> PartialOrd for Lastmod<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a> std::fmt::Display for Lastmod<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0.as_ref())
+    }
+}
+
+impl std::str::FromStr for Lastmod<'static> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Lastmod::try_from(s).map(Lastmod::into_owned)
+    }
+}
+
+/// Borrows a validated `Lastmod` without copying or validating it again.
+///
+/// It allows validating a `Lastmod` once and reusing it across many entries.
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{Lastmod, SitemapWriter, Url};
+/// use std::io::Cursor;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let lastmod = Lastmod::try_from("2005-01-01")?;
+/// let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+/// for i in 0..3 {
+///     let loc = format!("http://www.example.com/{}", i);
+///     writer.write(Url::loc(loc.as_str())?.lastmod(&lastmod)?)?;
+/// }
+/// let mut url_writer = writer.begin_url("http://www.example.com/")?;
+/// url_writer.lastmod(&lastmod)?;
+/// url_writer.end()?;
+/// #     Ok(())
+/// # }
+/// ```
+impl<'a, 'b> From<&'a Lastmod<'b>> for Lastmod<'a> {
+    fn from(value: &'a Lastmod<'b>) -> Self {
+        Self(Cow::Borrowed(value.0.as_ref()))
+    }
 }
 
 impl<'a> TryFrom<&'a str> for Lastmod<'a> {
     type Error = Error;
 
     fn try_from(value: &'a str) -> Result<Self, Self::Error> {
-        // <https://www.w3.org/TR/xmlschema11-2/#date>
-        // <https://www.w3.org/TR/xmlschema11-2/#dateTime>
-        if !DATE_RE.is_match(value) && !DATE_TIME_RE.is_match(value) {
+        if !Self::is_valid(value) {
             return Err(Error);
         }
         Ok(Self(Cow::Borrowed(value)))
@@ -54,7 +286,7 @@ where
 
     fn try_from(value: ::chrono::DateTime<T>) -> Result<Self, Self::Error> {
         let s = value.to_rfc3339();
-        Ok(Self(Cow::Owned(s)))
+        Self::from_formatted(s)
     }
 }
 
@@ -65,7 +297,19 @@ impl<'a> TryFrom<::chrono::NaiveDate> for Lastmod<'a> {
     fn try_from(value: ::chrono::NaiveDate) -> Result<Self, Self::Error> {
         // `chrono::NaiveDate` debug output format is "%Y-%m-%d"
         let s = format!("{:?}", value);
-        Ok(Self(Cow::Owned(s)))
+        Self::from_formatted(s)
+    }
+}
+
+/// Converts a `chrono::NaiveDateTime` to a `Lastmod` without timezone (e.g.
+/// `2004-12-23T18:00:15`). Use [`Lastmod::from_naive_date_time`] to assume
+/// UTC or reject it instead.
+#[cfg(feature = "chrono")]
+impl<'a> TryFrom<::chrono::NaiveDateTime> for Lastmod<'a> {
+    type Error = Error;
+
+    fn try_from(value: ::chrono::NaiveDateTime) -> Result<Self, Self::Error> {
+        Self::from_naive_date_time(value, NaiveDateTimePolicy::default())
     }
 }
 
@@ -76,7 +320,7 @@ impl<'a> TryFrom<time::Date> for Lastmod<'a> {
     fn try_from(value: time::Date) -> Result<Self, Self::Error> {
         let format = time::macros::format_description!("[year]-[month]-[day]");
         let s = value.format(&format).map_err(|_| Error)?;
-        Ok(Self(Cow::Owned(s)))
+        Self::from_formatted(s)
     }
 }
 
@@ -85,8 +329,75 @@ impl<'a> TryFrom<time::OffsetDateTime> for Lastmod<'a> {
     type Error = Error;
 
     fn try_from(value: time::OffsetDateTime) -> Result<Self, Self::Error> {
-        let s = value.format(&Iso8601::DEFAULT).map_err(|_| Error)?;
-        Ok(Self(Cow::Owned(s)))
+        let format =
+            time::macros::format_description!("[offset_hour sign:mandatory]:[offset_minute]");
+        let mut s = format_time_date_time(value.date(), value.time())?;
+        s.push_str(&value.offset().format(&format).map_err(|_| Error)?);
+        Self::from_formatted(s)
+    }
+}
+
+/// Converts a `time::PrimitiveDateTime` to a `Lastmod` without timezone (e.g.
+/// `2004-12-23T18:00:15`). Use [`Lastmod::from_primitive_date_time`] to
+/// assume UTC or reject it instead.
+#[cfg(feature = "time")]
+impl<'a> TryFrom<time::PrimitiveDateTime> for Lastmod<'a> {
+    type Error = Error;
+
+    fn try_from(value: time::PrimitiveDateTime) -> Result<Self, Self::Error> {
+        Self::from_primitive_date_time(value, NaiveDateTimePolicy::default())
+    }
+}
+
+#[cfg(feature = "time")]
+impl<'a> TryFrom<time::UtcDateTime> for Lastmod<'a> {
+    type Error = Error;
+
+    fn try_from(value: time::UtcDateTime) -> Result<Self, Self::Error> {
+        let mut s = format_time_date_time(value.date(), value.time())?;
+        s.push('Z');
+        Self::from_formatted(s)
+    }
+}
+
+/// Formats a date and time as `YYYY-MM-DDThh:mm:ss`, with the fraction only
+/// if it is not zero.
+#[cfg(feature = "time")]
+fn format_time_date_time(date: time::Date, time: time::Time) -> Result<String, Error> {
+    let value = time::PrimitiveDateTime::new(date, time);
+    if value.nanosecond() == 0 {
+        let format =
+            time::macros::format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
+        value.format(&format)
+    } else {
+        let format = time::macros::format_description!(
+            "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond]"
+        );
+        value.format(&format)
+    }
+    .map_err(|_| Error)
+}
+
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for Lastmod<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.0.as_ref())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for Lastmod<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Lastmod::try_from(s.as_str())
+            .map(Lastmod::into_owned)
+            .map_err(|_| serde::de::Error::custom("invalid lastmod"))
     }
 }
 
@@ -104,6 +415,114 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_from_ref() -> anyhow::Result<()> {
+        let owned = "2005-01-01".parse::<Lastmod>()?;
+        let borrowed = Lastmod::from(&owned);
+        assert_eq!(borrowed, owned);
+        assert!(matches!(borrowed.into_inner(), Cow::Borrowed(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_and_from_str() -> anyhow::Result<()> {
+        for s in ["2005-01-01", "2004-12-23T18:00:15+00:00"] {
+            let lastmod = s.parse::<Lastmod>()?;
+            assert_eq!(lastmod.to_string(), s);
+            assert_eq!(lastmod, Lastmod::try_from(s)?);
+        }
+        assert!("2005-13-01".parse::<Lastmod>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_days_in_month() {
+        for s in [
+            "2024-02-29",
+            "2000-02-29",
+            "-0004-02-29",
+            "2005-04-30T00:00:00Z",
+        ] {
+            assert!(Lastmod::try_from(s).is_ok(), "{}", s);
+        }
+        for s in [
+            "2023-02-29",
+            "1900-02-29",
+            "2023-02-30",
+            "2005-04-31T00:00:00Z",
+        ] {
+            assert!(Lastmod::try_from(s).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_date_time() -> anyhow::Result<()> {
+        let lastmod = Lastmod::try_from("2004-12-23T18:00:15+00:00")?;
+        assert_eq!(
+            lastmod.date_time().map(|d| (d.year(), d.month(), d.day())),
+            Some((2004, 12, 23))
+        );
+        assert_eq!(Lastmod::new_unchecked("2005-02-30").date_time(), None);
+        assert_eq!(Lastmod::new_unchecked("invalid").date_time(), None);
+        Ok(())
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_is_valid_agrees_with_parser() {
+        let years = [
+            "2005", "-2005", "2000", "1900", "0000", "-0004", "12345", "012345",
+        ];
+        let years = years.iter().map(|y| y.to_string()).chain([
+            format!("1{}", "0".repeat(29)),
+            format!("1{}", "0".repeat(30)),
+        ]);
+        let dates = [
+            "-01-01", "-02-28", "-02-29", "-02-30", "-04-31", "-12-31", "-13-01",
+        ];
+        for year in years {
+            for date in dates {
+                for time in ["", "T24:00:00", "T12:60:00", "T23:59:59.9+09:00"] {
+                    let s = format!("{}{}{}", year, date, time);
+                    assert_eq!(
+                        Lastmod::is_valid(&s),
+                        W3cDateTime::parse(&s).is_some(),
+                        "{}",
+                        s
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ord() -> anyhow::Result<()> {
+        let mut lastmods = [
+            "2005-01-01T00:00:00.5Z",
+            "2005-01-01",
+            "2004-12-31T23:00:00-02:00",
+            "2005-01-01T00:00:00Z",
+            "2005-01-01T09:00:00+09:00",
+            "2004-12-23T18:00:15+00:00",
+        ]
+        .into_iter()
+        .map(Lastmod::try_from)
+        .collect::<Result<Vec<Lastmod>, _>>()?;
+        lastmods.sort();
+        assert_eq!(
+            lastmods.iter().map(Lastmod::to_string).collect::<Vec<_>>(),
+            vec![
+                "2004-12-23T18:00:15+00:00",
+                "2005-01-01",
+                "2005-01-01T00:00:00Z",
+                "2005-01-01T09:00:00+09:00",
+                "2005-01-01T00:00:00.5Z",
+                "2004-12-31T23:00:00-02:00",
+            ]
+        );
+        Ok(())
+    }
+
     #[cfg(feature = "chrono")]
     #[test]
     fn test_chrono_date_time() -> anyhow::Result<()> {
@@ -133,6 +552,39 @@ mod tests {
             "%Y-%m-%d",
         )?)?;
         assert_eq!(lastmod.into_inner(), "2023-01-02");
+
+        // `+10000-01-01`
+        let value = ::chrono::NaiveDate::from_ymd_opt(10000, 1, 1).expect("valid date");
+        assert!(Lastmod::try_from(value).is_err());
+        assert!(Lastmod::try_from(value.and_hms_opt(0, 0, 0).expect("valid time")).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_naive_date_time() -> anyhow::Result<()> {
+        let value =
+            ::chrono::NaiveDateTime::parse_from_str("2004-12-23T18:00:15", "%Y-%m-%dT%H:%M:%S")?;
+        assert_eq!(
+            Lastmod::try_from(value)?.into_inner(),
+            "2004-12-23T18:00:15"
+        );
+        assert_eq!(
+            Lastmod::try_from(value.and_utc())?.into_inner(),
+            "2004-12-23T18:00:15+00:00"
+        );
+
+        let value = ::chrono::NaiveDateTime::parse_from_str(
+            "2004-12-23T18:00:15.123",
+            "%Y-%m-%dT%H:%M:%S%.f",
+        )?;
+        let lastmod = Lastmod::try_from(value)?;
+        assert_eq!(lastmod.into_inner(), "2004-12-23T18:00:15.123");
+        assert_eq!(
+            Lastmod::from_naive_date_time(value, NaiveDateTimePolicy::AssumeUtc)?.into_inner(),
+            "2004-12-23T18:00:15.123+00:00"
+        );
+        assert!(Lastmod::from_naive_date_time(value, NaiveDateTimePolicy::Reject).is_err());
         Ok(())
     }
 
@@ -150,7 +602,46 @@ mod tests {
     fn test_time_offset_date_time() -> anyhow::Result<()> {
         #[rustfmt::skip]
         let lastmod = Lastmod::try_from(time::macros::datetime!(2004-12-23 18:00:15 +00:00))?;
-        assert_eq!(lastmod.into_inner(), "2004-12-23T18:00:15.000000000Z");
+        assert_eq!(lastmod.into_inner(), "2004-12-23T18:00:15+00:00");
+        #[rustfmt::skip]
+        let lastmod = Lastmod::try_from(time::macros::datetime!(2004-12-23 18:00:15.5 -05:30))?;
+        assert_eq!(lastmod.into_inner(), "2004-12-23T18:00:15.5-05:30");
+        Ok(())
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_time_primitive_date_time() -> anyhow::Result<()> {
+        #[rustfmt::skip]
+        let lastmod = Lastmod::try_from(time::macros::datetime!(2004-12-23 18:00:15))?;
+        assert_eq!(lastmod.into_inner(), "2004-12-23T18:00:15");
+        #[rustfmt::skip]
+        let lastmod = Lastmod::try_from(time::macros::datetime!(2004-12-23 18:00:15.123))?;
+        assert_eq!(lastmod.into_inner(), "2004-12-23T18:00:15.123");
+
+        #[rustfmt::skip]
+        let value = time::macros::datetime!(2004-12-23 18:00:15);
+        assert_eq!(
+            Lastmod::from_primitive_date_time(value, NaiveDateTimePolicy::OmitOffset)?.into_inner(),
+            "2004-12-23T18:00:15"
+        );
+        assert_eq!(
+            Lastmod::from_primitive_date_time(value, NaiveDateTimePolicy::AssumeUtc)?.into_inner(),
+            "2004-12-23T18:00:15+00:00"
+        );
+        assert!(Lastmod::from_primitive_date_time(value, NaiveDateTimePolicy::Reject).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_time_utc_date_time() -> anyhow::Result<()> {
+        #[rustfmt::skip]
+        let lastmod = Lastmod::try_from(time::macros::utc_datetime!(2004-12-23 18:00:15))?;
+        assert_eq!(lastmod.into_inner(), "2004-12-23T18:00:15Z");
+        #[rustfmt::skip]
+        let lastmod = Lastmod::try_from(time::macros::utc_datetime!(2004-12-23 18:00:15.120))?;
+        assert_eq!(lastmod.into_inner(), "2004-12-23T18:00:15.12Z");
         Ok(())
     }
 }
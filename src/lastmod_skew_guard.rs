@@ -0,0 +1,155 @@
+use std::{
+    borrow::Cow,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::date_time::{civil_from_days, W3cDateTime};
+
+/// The action taken when a `lastmod` is too far in the future.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LastmodSkewAction {
+    /// Returns an error.
+    #[default]
+    Reject,
+    /// Replaces the `lastmod` with the current time (UTC).
+    Clamp,
+}
+
+/// The reason a `lastmod` is rejected by a [`LastmodSkewGuard`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum LastmodSkewError {
+    /// The `lastmod` is not a W3C Datetime (e.g. created without validation).
+    Invalid,
+    /// The `lastmod` is too far in the future.
+    Future,
+}
+
+/// A guard against `lastmod` values too far in the future (e.g. caused by
+/// clock skew or bad data).
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{LastmodSkewAction, LastmodSkewGuard, SitemapWriterBuilder, Url};
+/// use std::{io::Cursor, time::Duration};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let guard = LastmodSkewGuard::new(Duration::from_secs(24 * 60 * 60))
+///     .on_violation(LastmodSkewAction::Reject);
+/// let mut writer = SitemapWriterBuilder::new()
+///     .lastmod_skew_guard(guard)
+///     .start(Cursor::new(Vec::new()))?;
+/// writer.write(Url::loc("http://www.example.com/")?.lastmod("2005-01-01")?)?;
+/// assert!(writer
+///     .write(Url::loc("http://www.example.com/")?.lastmod("9999-01-01")?)
+///     .is_err());
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct LastmodSkewGuard {
+    max_skew: Duration,
+    action: LastmodSkewAction,
+    now: Option<SystemTime>,
+}
+
+impl LastmodSkewGuard {
+    /// Creates a new `LastmodSkewGuard` allowing `lastmod` values up to
+    /// `max_skew` after the current time.
+    pub fn new(max_skew: Duration) -> Self {
+        Self {
+            max_skew,
+            action: LastmodSkewAction::default(),
+            now: None,
+        }
+    }
+
+    /// Sets the action taken when a `lastmod` is too far in the future. The
+    /// default is [`LastmodSkewAction::Reject`].
+    pub fn on_violation(mut self, action: LastmodSkewAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Fixes the current time instead of using [`SystemTime::now`].
+    pub fn now(mut self, now: SystemTime) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    /// Returns `Ok` with the `lastmod` to write, or `Err` if it is rejected.
+    pub(crate) fn check<'a>(
+        &self,
+        lastmod: Cow<'a, str>,
+    ) -> Result<Cow<'a, str>, LastmodSkewError> {
+        let now = unix_seconds(self.now.unwrap_or_else(SystemTime::now));
+        let max = now + i128::from(self.max_skew.as_secs());
+        let (seconds, fraction) = W3cDateTime::parse(lastmod.as_ref())
+            .ok_or(LastmodSkewError::Invalid)?
+            .order_key();
+        if seconds < max || (seconds == max && fraction.is_empty()) {
+            return Ok(lastmod);
+        }
+        match self.action {
+            LastmodSkewAction::Reject => Err(LastmodSkewError::Future),
+            LastmodSkewAction::Clamp => Ok(Cow::Owned(format_utc(now))),
+        }
+    }
+}
+
+pub(crate) fn unix_seconds(t: SystemTime) -> i128 {
+    match t.duration_since(UNIX_EPOCH) {
+        Ok(d) => i128::from(d.as_secs()),
+        Err(e) => -i128::from(e.duration().as_secs()),
+    }
+}
+
+fn format_utc(seconds: i128) -> String {
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+    let s = seconds.rem_euclid(86_400);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}+00:00",
+        year,
+        month,
+        day,
+        s / 3_600,
+        s % 3_600 / 60,
+        s % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        // 2005-01-01T00:00:00Z
+        let now = UNIX_EPOCH + Duration::from_secs(1_104_537_600);
+        let guard = LastmodSkewGuard::new(Duration::from_secs(86_400)).now(now);
+        let f = |s: &'static str| guard.check(Cow::Borrowed(s));
+        assert_eq!(f("2004-12-31"), Ok(Cow::Borrowed("2004-12-31")));
+        assert_eq!(f("2005-01-02"), Ok(Cow::Borrowed("2005-01-02")));
+        assert_eq!(
+            f("2005-01-02T09:00:00+09:00"),
+            Ok(Cow::Borrowed("2005-01-02T09:00:00+09:00"))
+        );
+        assert_eq!(f("2005-01-02T00:00:00.1Z"), Err(LastmodSkewError::Future));
+        assert_eq!(f("2005-01-03"), Err(LastmodSkewError::Future));
+        assert_eq!(f("2005-02-30"), Err(LastmodSkewError::Invalid));
+        assert_eq!(f("invalid"), Err(LastmodSkewError::Invalid));
+
+        let guard = guard.on_violation(LastmodSkewAction::Clamp);
+        assert_eq!(
+            guard.check(Cow::Borrowed("2005-01-03")),
+            Ok(Cow::Owned("2005-01-01T00:00:00+00:00".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(0), "1970-01-01T00:00:00+00:00");
+        assert_eq!(format_utc(1_103_824_815), "2004-12-23T18:00:15+00:00");
+        assert_eq!(format_utc(-1), "1969-12-31T23:59:59+00:00");
+    }
+}
@@ -28,10 +28,10 @@
 //!         .changefreq("monthly")?
 //!         .priority("0.8")?,
 //! )?;
-//! writer.end()?;
+//! let (inner, _summary) = writer.finish()?;
 //!
 //! assert_eq!(
-//!     String::from_utf8(writer.into_inner().into_inner())?,
+//!     String::from_utf8(inner.into_inner())?,
 //!     concat!(
 //!         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
 //!         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -60,10 +60,10 @@
 //!     Sitemap::loc("http://www.example.com/sitemap1.xml.gz")?
 //!         .lastmod("2004-10-01T18:23:17+00:00")?
 //! )?;
-//! writer.end()?;
+//! let (inner, _summary) = writer.finish()?;
 //!
 //! assert_eq!(
-//!     String::from_utf8(writer.into_inner().into_inner())?,
+//!     String::from_utf8(inner.into_inner())?,
 //!     concat!(
 //!         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
 //!         r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -77,21 +77,83 @@
 //! #     Ok(())
 //! # }
 //!
+mod alternate;
+mod alternate_cluster;
+mod alternates;
 mod changefreq;
+mod chunked_writer;
+mod date_time;
+pub mod discovery;
+mod error;
+mod escape_audit;
+pub mod features;
+mod google_news_profile;
+mod host_policy;
+mod image;
 mod lastmod;
+mod lastmod_skew_guard;
 mod loc;
+mod news;
+mod pagination;
 mod priority;
+mod rate_limited_writer;
+mod scheme_policy;
 mod sitemap;
+mod sitemap_format;
 mod sitemap_index_writer;
+mod sitemap_index_writer_builder;
 mod sitemap_writer;
+mod sitemap_writer_builder;
 mod sitemap_xml_writer;
+mod sync_all;
 mod url;
+mod url_extension;
+mod url_pattern;
+mod url_template;
+mod url_writer;
+mod video;
+mod write_summary;
 
+pub use self::alternate::Alternate;
+pub use self::alternate_cluster::{AlternateCluster, AlternateViolation};
+pub use self::alternates::Alternates;
 pub use self::changefreq::Changefreq;
-pub use self::lastmod::Lastmod;
+pub use self::chunked_writer::ChunkedWriter;
+pub use self::date_time::{W3cDateTime, W3cTime};
+pub use self::error::{Context, Error, ErrorField, ErrorKind, ErrorWriter};
+pub use self::escape_audit::EscapeAudit;
+pub use self::google_news_profile::GoogleNewsProfile;
+pub use self::host_policy::{HostPolicy, HostPolicyAction};
+pub use self::image::Image;
+pub use self::lastmod::{Lastmod, NaiveDateTimePolicy};
+pub use self::lastmod_skew_guard::{LastmodSkewAction, LastmodSkewGuard};
 pub use self::loc::Loc;
+pub use self::news::News;
+pub use self::pagination::{Pagination, PaginationStyle};
 pub use self::priority::Priority;
+pub use self::rate_limited_writer::RateLimitedWriter;
+pub use self::scheme_policy::SchemePolicy;
 pub use self::sitemap::Sitemap;
+pub use self::sitemap_format::SitemapFormat;
 pub use self::sitemap_index_writer::SitemapIndexWriter;
+pub use self::sitemap_index_writer_builder::SitemapIndexWriterBuilder;
 pub use self::sitemap_writer::SitemapWriter;
+pub use self::sitemap_writer_builder::SitemapWriterBuilder;
+pub use self::sync_all::SyncAll;
 pub use self::url::Url;
+pub use self::url_extension::{ExtensionWriter, UrlExtension};
+pub use self::url_pattern::UrlPattern;
+pub use self::url_template::UrlTemplate;
+pub use self::url_writer::UrlWriter;
+pub use self::video::{
+    Video, VideoPlatform, VideoPrice, VideoPriceType, VideoRelationship, VideoResolution,
+};
+pub use self::write_summary::WriteSummary;
+
+/// The error of [`SitemapIndexWriter`] without the context.
+#[deprecated(note = "use `Error`, which has the context (e.g. the entry index), and `Error::kind`")]
+pub type SitemapIndexWriterError = self::sitemap_index_writer::Error;
+
+/// The error of [`SitemapWriter`] without the context.
+#[deprecated(note = "use `Error`, which has the context (e.g. the entry index), and `Error::kind`")]
+pub type SitemapWriterError = self::sitemap_writer::Error;
@@ -1,20 +1,171 @@
 use std::borrow::Cow;
 
 // TODO: Error
-#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
-#[error("error")]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Error;
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("error")
+    }
+}
+
+impl std::error::Error for Error {}
+
 /// A `loc` child entry.
+///
+/// The canonical form used by [`std::fmt::Display`] and
+/// [`std::str::FromStr`] is the URL string.
+///
+/// A `&str` loc must have a scheme and must not contain whitespace, control
+/// characters or `` "<>\^`{|} ``. With the `url` feature, it must also be parsed
+/// by `::url::Url::parse`.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Loc<'a>(Cow<'a, str>);
 
 impl<'a> Loc<'a> {
+    /// Creates a `Loc` without validation.
+    ///
+    /// It is intended for pre-validated inputs in hot loops. The caller must
+    /// ensure that `loc` is valid (e.g. it was accepted by
+    /// [`Loc::try_from`] before). An invalid value is written as is (but
+    /// escaped).
+    pub fn new_unchecked<S>(loc: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        Self(loc.into())
+    }
+
+    /// Converts this `Loc` into an owned `Loc<'static>`.
+    pub fn into_owned(self) -> Loc<'static> {
+        Loc(Cow::Owned(self.0.into_owned()))
+    }
+
+    /// Returns the canonical form of this `Loc`.
+    ///
+    /// It parses the URL with the `url` crate and re-serializes it, and then
+    /// normalizes the percent-encoding of the path, query and fragment:
+    /// percent-encoded unreserved characters are decoded, other hex digits
+    /// are uppercased, and `+` in the query is encoded as `%20`. Logically
+    /// equal URLs given as `&str` or `::url::Url` have the same canonical form.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::Loc;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let loc = Loc::try_from("HTTP://Example.com/%7euser/?q=a+b&r=%2f")?;
+    /// assert_eq!(
+    ///     loc.canonicalize()?.to_string(),
+    ///     "http://example.com/~user/?q=a%20b&r=%2F"
+    /// );
+    /// #     Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "url")]
+    pub fn canonicalize(&self) -> Result<Loc<'static>, Error> {
+        let mut url = url::Url::parse(self.0.as_ref()).map_err(|_| Error)?;
+        let path = normalize_percent_encoding(url.path(), false);
+        url.set_path(&path);
+        if let Some(query) = url.query() {
+            let query = normalize_percent_encoding(query, true);
+            url.set_query(Some(&query));
+        }
+        if let Some(fragment) = url.fragment() {
+            let fragment = normalize_percent_encoding(fragment, false);
+            url.set_fragment(Some(&fragment));
+        }
+        Loc::try_from(url)
+    }
+
+    /// Returns the Unicode Normalization Form C (NFC) of this `Loc`.
+    ///
+    /// Visually identical URLs from different sources (e.g. `"é"` as one or
+    /// two code points) have the same NFC form. Use it before
+    /// percent-encoding. The NFC form is validated again like
+    /// [`Loc::try_from`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::Loc;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let loc = Loc::try_from("http://example.com/cafe\u{301}")?;
+    /// assert_eq!(loc.nfc()?.to_string(), "http://example.com/caf\u{e9}");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "unicode-normalization")]
+    pub fn nfc(&self) -> Result<Loc<'static>, Error> {
+        use unicode_normalization::UnicodeNormalization;
+        // the NFC form may be invalid (e.g. U+1FEF becomes "`")
+        self.0.nfc().collect::<String>().parse()
+    }
+
     pub(crate) fn into_inner(self) -> Cow<'a, str> {
         self.0
     }
 }
 
+#[cfg(feature = "url")]
+fn normalize_percent_encoding(s: &str, query: bool) -> String {
+    fn hex(b: u8) -> Option<u8> {
+        (b as char).to_digit(16).map(|d| d as u8)
+    }
+
+    let bytes = s.as_bytes();
+    let mut normalized = String::with_capacity(s.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => match (
+                bytes.get(i + 1).copied().and_then(hex),
+                bytes.get(i + 2).copied().and_then(hex),
+            ) {
+                (Some(h), Some(l)) => {
+                    let c = (h << 4 | l) as char;
+                    if c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~') {
+                        normalized.push(c);
+                    } else {
+                        normalized.push_str(&format!("%{:02X}", c as u8));
+                    }
+                    i += 3;
+                    continue;
+                }
+                _ => normalized.push('%'),
+            },
+            b'+' if query => normalized.push_str("%20"),
+            _ => normalized.push(bytes[i] as char),
+        }
+        i += 1;
+    }
+    normalized
+}
+
+impl<'a> std::fmt::Display for Loc<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0.as_ref())
+    }
+}
+
+impl std::str::FromStr for Loc<'static> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Loc::try_from(s).map(Loc::into_owned)
+    }
+}
+
+/// Borrows a validated `Loc` without copying or validating it again.
+impl<'a, 'b> From<&'a Loc<'b>> for Loc<'a> {
+    fn from(value: &'a Loc<'b>) -> Self {
+        Self(Cow::Borrowed(value.0.as_ref()))
+    }
+}
+
 impl<'a> TryFrom<&'a str> for Loc<'a> {
     type Error = Error;
 
@@ -22,15 +173,45 @@ impl<'a> TryFrom<&'a str> for Loc<'a> {
         if value.chars().count() >= 2048 {
             return Err(Error);
         }
-        // too slow
-        // let u = url::Url::parse(value).map_err(|_| Error)?;
-        // if u.as_str() != value {
-        //     return Err(Error);
-        // }
+        if !is_valid_syntax(value) {
+            return Err(Error);
+        }
+        #[cfg(feature = "url")]
+        url::Url::parse(value).map_err(|_| Error)?;
         Ok(Self(Cow::Borrowed(value)))
     }
 }
 
+/// A lightweight syntactic check independent of the `url` feature. It
+/// requires a scheme and rejects whitespace, control characters and the ASCII
+/// characters not allowed in URLs (`` "<>\^`{|} ``).
+fn is_valid_syntax(s: &str) -> bool {
+    // <https://www.rfc-editor.org/rfc/rfc3986#section-3.1>
+    let is_valid_scheme = match s.split_once(':') {
+        Some((scheme, _)) => {
+            let mut bytes = scheme.bytes();
+            bytes.next().is_some_and(|b| b.is_ascii_alphabetic())
+                && bytes.all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.'))
+        }
+        None => false,
+    };
+    is_valid_scheme
+        && !s.chars().any(|c| {
+            c.is_whitespace()
+                || c.is_control()
+                || matches!(c, '"' | '<' | '>' | '\\' | '^' | '`' | '{' | '|' | '}')
+        })
+}
+
+impl<'a> TryFrom<&'a [u8]> for Loc<'a> {
+    type Error = Error;
+
+    fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
+        let s = std::str::from_utf8(value).map_err(|_| Error)?;
+        Self::try_from(s)
+    }
+}
+
 #[cfg(feature = "url")]
 impl<'a> TryFrom<url::Url> for Loc<'a> {
     type Error = Error;
@@ -44,6 +225,29 @@ impl<'a> TryFrom<url::Url> for Loc<'a> {
     }
 }
 
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for Loc<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.0.as_ref())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for Loc<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Loc::try_from(s.as_str())
+            .map(Loc::into_owned)
+            .map_err(|_| serde::de::Error::custom("invalid loc"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -66,6 +270,131 @@ mod tests {
 
         let s = "https://example.com/path";
         assert_eq!(Loc::try_from(s)?.into_inner(), s);
+
+        // the syntax check does not depend on the `url` feature
+        assert!(Loc::try_from("https://example.com/a b").is_err());
+        assert!(Loc::try_from("https://example.com/<a>").is_err());
+        assert!(Loc::try_from("example.com/").is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn test_url_parse() {
+        assert!(Loc::try_from("https://example.com:99999/").is_err());
+        assert!(Loc::try_from("https://[::1/").is_err());
+    }
+
+    #[test]
+    fn test_from_ref() -> anyhow::Result<()> {
+        let owned = "https://example.com/".parse::<Loc>()?;
+        let borrowed = Loc::from(&owned);
+        assert_eq!(borrowed, owned);
+        assert!(matches!(borrowed.into_inner(), Cow::Borrowed(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_and_from_str() -> anyhow::Result<()> {
+        let s = "https://example.com/?a=1&b=2";
+        let loc = s.parse::<Loc>()?;
+        assert_eq!(loc.to_string(), s);
+        assert_eq!(loc, Loc::try_from(s)?);
+        assert!("a".repeat(2048).parse::<Loc>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bytes() -> anyhow::Result<()> {
+        let b = "https://example.com/日本".as_bytes();
+        let loc = Loc::try_from(b)?.into_inner();
+        assert!(matches!(loc, Cow::Borrowed(_)));
+        assert_eq!(loc, "https://example.com/日本");
+
+        assert!(Loc::try_from(b"https://example.com/\xff".as_slice()).is_err());
+
+        let s = format!("https://example.com/{}", "a".repeat(2028));
+        assert!(Loc::try_from(s.as_bytes()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_valid_syntax() {
+        assert!(is_valid_syntax("https://example.com/"));
+        assert!(is_valid_syntax("https://example.com/?a=1&b=%20#c"));
+        assert!(is_valid_syntax("https://example.com/日本"));
+        assert!(is_valid_syntax("urn:isbn:0451450523"));
+        assert!(!is_valid_syntax("example.com/"));
+        assert!(!is_valid_syntax("/path"));
+        assert!(!is_valid_syntax(":path"));
+        assert!(!is_valid_syntax("1http://example.com/"));
+        assert!(!is_valid_syntax("ht tp://example.com/"));
+        assert!(!is_valid_syntax("https://example.com/a b"));
+        assert!(!is_valid_syntax("https://example.com/a\tb"));
+        assert!(!is_valid_syntax("https://example.com/a\u{3000}b"));
+        for c in ['"', '<', '>', '\\', '^', '`', '{', '|', '}'] {
+            assert!(!is_valid_syntax(&format!("https://example.com/{}", c)));
+        }
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn test_nfc() -> anyhow::Result<()> {
+        let f = |s: &str| -> anyhow::Result<String> { Ok(Loc::try_from(s)?.nfc()?.to_string()) };
+        assert_eq!(f("http://example.com/")?, "http://example.com/");
+        assert_eq!(
+            f("http://example.com/caf\u{e9}")?,
+            "http://example.com/caf\u{e9}"
+        );
+        assert_eq!(
+            f("http://example.com/cafe\u{301}")?,
+            "http://example.com/caf\u{e9}"
+        );
+        assert_eq!(
+            f("http://example.com/\u{304b}\u{3099}")?,
+            "http://example.com/\u{304c}"
+        );
+        // not decoded
+        assert_eq!(
+            f("http://example.com/e%CC%81")?,
+            "http://example.com/e%CC%81"
+        );
+        // U+1FEF (GREEK VARIA) becomes U+0060 (GRAVE ACCENT)
+        assert!(Loc::try_from("http://example.com/\u{1fef}")?.nfc().is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn test_canonicalize() -> anyhow::Result<()> {
+        let f = |s: &str| -> anyhow::Result<String> {
+            Ok(Loc::try_from(s)?.canonicalize()?.to_string())
+        };
+        assert_eq!(f("http://example.com")?, "http://example.com/");
+        assert_eq!(f("http://example.com/a%7eb")?, "http://example.com/a~b");
+        assert_eq!(f("http://example.com/a%2fb")?, "http://example.com/a%2Fb");
+        assert!(f("http://example.com/a b").is_err());
+        assert_eq!(
+            f("http://example.com/?q=a+b")?,
+            "http://example.com/?q=a%20b"
+        );
+        assert_eq!(
+            f("http://example.com/?q=%e3%81%82")?,
+            "http://example.com/?q=%E3%81%82"
+        );
+        assert_eq!(
+            f("http://example.com/?q=100%")?,
+            "http://example.com/?q=100%"
+        );
+        assert_eq!(f("http://example.com/#a+b%7E")?, "http://example.com/#a+b~");
+        assert!(f("example.com").is_err());
+
+        let mut url = url::Url::parse("http://example.com/")?;
+        url.query_pairs_mut().append_pair("q", "a b");
+        assert_eq!(
+            Loc::try_from(url)?.canonicalize()?,
+            Loc::try_from("http://example.com/?q=a%20b")?.canonicalize()?
+        );
         Ok(())
     }
 }
@@ -0,0 +1,430 @@
+use std::borrow::Cow;
+
+use crate::{
+    lastmod::Lastmod,
+    sitemap_writer::Error,
+    sitemap_xml_writer::is_text,
+    url_extension::{ExtensionWriter, UrlExtension},
+};
+
+type Result<T, E = crate::Error> = std::result::Result<T, E>;
+
+/// A `news:news` entry of the
+/// [news sitemap extension](https://developers.google.com/search/docs/crawling-indexing/sitemaps/news-sitemap).
+///
+/// It is added to a `url` entry by [`crate::Url::news`]. The namespace must
+/// be declared by [`crate::SitemapWriterBuilder::namespace`] with
+/// [`News::PREFIX`] and [`News::NAMESPACE_URI`].
+///
+/// The `news:language` must be an ISO 639 language code (two or three
+/// letters), or `zh-cn` (Simplified Chinese) or `zh-tw` (Traditional
+/// Chinese). A two-letter code must be an ISO 639-1 code. A three-letter code
+/// is not checked against the ISO 639-2 or ISO 639-3 code tables.
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{News, SitemapWriterBuilder, Url};
+/// use std::io::Cursor;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut writer = SitemapWriterBuilder::new()
+///     .namespace(News::PREFIX, News::NAMESPACE_URI)
+///     .start(Cursor::new(Vec::new()))?;
+/// writer.write(
+///     Url::loc("http://www.example.org/business/article55.html")?.news(
+///         News::new("The Example Times", "en", "2008-12-23", "Companies A, B in Merger Talks")?
+///             .keywords(["business", "merger", "acquisition"])?
+///             .stock_tickers(["NASDAQ:A", "NASDAQ:B"])?,
+///     )?,
+/// )?;
+/// let (inner, _) = writer.finish()?;
+/// assert_eq!(
+///     String::from_utf8(inner.into_inner())?,
+///     concat!(
+///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+///         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:news="http://www.google.com/schemas/sitemap-news/0.9">"#,
+///         r#"<url>"#,
+///         r#"<loc>http://www.example.org/business/article55.html</loc>"#,
+///         r#"<news:news>"#,
+///         r#"<news:publication>"#,
+///         r#"<news:name>The Example Times</news:name>"#,
+///         r#"<news:language>en</news:language>"#,
+///         r#"</news:publication>"#,
+///         r#"<news:publication_date>2008-12-23</news:publication_date>"#,
+///         r#"<news:title>Companies A, B in Merger Talks</news:title>"#,
+///         r#"<news:keywords>business, merger, acquisition</news:keywords>"#,
+///         r#"<news:stock_tickers>NASDAQ:A, NASDAQ:B</news:stock_tickers>"#,
+///         r#"</news:news>"#,
+///         r#"</url>"#,
+///         r#"</urlset>"#
+///     )
+/// );
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct News<'a> {
+    name: Cow<'a, str>,
+    language: Cow<'a, str>,
+    publication_date: Cow<'a, str>,
+    title: Cow<'a, str>,
+    keywords: Option<String>,
+    stock_tickers: Option<String>,
+}
+
+impl<'a> News<'a> {
+    /// The namespace prefix of the news sitemap extension.
+    pub const PREFIX: &'static str = "news";
+
+    /// The namespace URI of the news sitemap extension.
+    pub const NAMESPACE_URI: &'static str = "http://www.google.com/schemas/sitemap-news/0.9";
+
+    /// The max number of stock tickers in the `news:stock_tickers` child
+    /// entry.
+    pub const MAX_NUMBER_OF_STOCK_TICKERS: usize = 5;
+
+    /// Builds a `news:news` entry with the required `news:name` and
+    /// `news:language` (of `news:publication`), `news:publication_date` and
+    /// `news:title` child entries.
+    ///
+    /// The language code is written in lowercase. The publication date is a
+    /// W3C Datetime date or datetime, and accepts the same values as
+    /// [`crate::Url::lastmod`].
+    pub fn new<N, L, D, T>(name: N, language: L, publication_date: D, title: T) -> Result<Self>
+    where
+        N: Into<Cow<'a, str>>,
+        L: Into<Cow<'a, str>>,
+        D: TryInto<Lastmod<'a>>,
+        T: Into<Cow<'a, str>>,
+    {
+        let publication_date = publication_date
+            .try_into()
+            .map_err(|_| Error::InvalidNewsPublicationDate)?;
+        Ok(Self {
+            name: text(name)?,
+            language: language_code(language)?,
+            publication_date: publication_date.into_inner(),
+            title: text(title)?,
+            keywords: None,
+            stock_tickers: None,
+        })
+    }
+
+    /// Changes the `news:keywords` child entry to the specified keywords.
+    /// They are written as a comma-separated list, so a keyword must not
+    /// contain a comma.
+    pub fn keywords<I, S>(mut self, keywords: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let keywords = list(keywords)?;
+        self.keywords = Some(keywords.join(", "));
+        Ok(self)
+    }
+
+    /// Changes the `news:stock_tickers` child entry to the specified stock
+    /// tickers (e.g. `"NASDAQ:AMAT"`). They are written as a comma-separated
+    /// list.
+    ///
+    /// It returns an error if there are more than
+    /// [`News::MAX_NUMBER_OF_STOCK_TICKERS`] stock tickers.
+    pub fn stock_tickers<I, S>(mut self, stock_tickers: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let stock_tickers = list(stock_tickers)?;
+        if stock_tickers.len() > Self::MAX_NUMBER_OF_STOCK_TICKERS {
+            return Err(Error::MaxNumberOfNewsStockTickers.into());
+        }
+        self.stock_tickers = Some(stock_tickers.join(", "));
+        Ok(self)
+    }
+
+    pub(crate) fn publication_date(&self) -> &str {
+        &self.publication_date
+    }
+
+    /// Converts this `News` into an owned `News<'static>`.
+    pub fn into_owned(self) -> News<'static> {
+        News {
+            name: Cow::Owned(self.name.into_owned()),
+            language: Cow::Owned(self.language.into_owned()),
+            publication_date: Cow::Owned(self.publication_date.into_owned()),
+            title: Cow::Owned(self.title.into_owned()),
+            keywords: self.keywords,
+            stock_tickers: self.stock_tickers,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for News<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut s = serializer.serialize_struct("News", 6)?;
+        s.serialize_field("name", &self.name)?;
+        s.serialize_field("language", &self.language)?;
+        s.serialize_field("publication_date", &self.publication_date)?;
+        s.serialize_field("title", &self.title)?;
+        for (key, value) in [
+            ("keywords", &self.keywords),
+            ("stock_tickers", &self.stock_tickers),
+        ] {
+            match value {
+                Some(value) => s.serialize_field(key, &value.split(", ").collect::<Vec<&str>>())?,
+                None => s.skip_field(key)?,
+            }
+        }
+        s.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for News<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "News")]
+        struct Repr {
+            name: String,
+            language: String,
+            publication_date: Lastmod<'static>,
+            title: String,
+            keywords: Option<Vec<String>>,
+            stock_tickers: Option<Vec<String>>,
+        }
+
+        let repr = Repr::deserialize(deserializer)?;
+        let news = || -> Result<News<'static>> {
+            let mut news = News::new(repr.name, repr.language, repr.publication_date, repr.title)?;
+            if let Some(keywords) = repr.keywords {
+                news = news.keywords(keywords)?;
+            }
+            if let Some(stock_tickers) = repr.stock_tickers {
+                news = news.stock_tickers(stock_tickers)?;
+            }
+            Ok(news)
+        };
+        news().map_err(|_| serde::de::Error::custom("invalid news"))
+    }
+}
+
+fn text<'a, S>(s: S) -> Result<Cow<'a, str>>
+where
+    S: Into<Cow<'a, str>>,
+{
+    let s = s.into();
+    if !is_text(&s) {
+        return Err(Error::InvalidNews.into());
+    }
+    Ok(s)
+}
+
+// ISO 639-1 codes (without the deprecated codes)
+const ISO_639_1: &[&str] = &[
+    "aa", "ab", "ae", "af", "ak", "am", "an", "ar", "as", "av", "ay", "az", "ba", "be", "bg", "bi",
+    "bm", "bn", "bo", "br", "bs", "ca", "ce", "ch", "co", "cr", "cs", "cu", "cv", "cy", "da", "de",
+    "dv", "dz", "ee", "el", "en", "eo", "es", "et", "eu", "fa", "ff", "fi", "fj", "fo", "fr", "fy",
+    "ga", "gd", "gl", "gn", "gu", "gv", "ha", "he", "hi", "ho", "hr", "ht", "hu", "hy", "hz", "ia",
+    "id", "ie", "ig", "ii", "ik", "io", "is", "it", "iu", "ja", "jv", "ka", "kg", "ki", "kj", "kk",
+    "kl", "km", "kn", "ko", "kr", "ks", "ku", "kv", "kw", "ky", "la", "lb", "lg", "li", "ln", "lo",
+    "lt", "lu", "lv", "mg", "mh", "mi", "mk", "ml", "mn", "mr", "ms", "mt", "my", "na", "nb", "nd",
+    "ne", "ng", "nl", "nn", "no", "nr", "nv", "ny", "oc", "oj", "om", "or", "os", "pa", "pi", "pl",
+    "ps", "pt", "qu", "rm", "rn", "ro", "ru", "rw", "sa", "sc", "sd", "se", "sg", "si", "sk", "sl",
+    "sm", "sn", "so", "sq", "sr", "ss", "st", "su", "sv", "sw", "ta", "te", "tg", "th", "ti", "tk",
+    "tl", "tn", "to", "tr", "ts", "tt", "tw", "ty", "ug", "uk", "ur", "uz", "ve", "vi", "vo", "wa",
+    "wo", "xh", "yi", "yo", "za", "zh", "zu",
+];
+
+fn language_code<'a, S>(s: S) -> Result<Cow<'a, str>>
+where
+    S: Into<Cow<'a, str>>,
+{
+    let s = s.into();
+    let s = if s.bytes().any(|b| b.is_ascii_uppercase()) {
+        Cow::Owned(s.to_ascii_lowercase())
+    } else {
+        s
+    };
+    let valid = match s.len() {
+        2 => ISO_639_1.binary_search(&s.as_ref()).is_ok(),
+        3 => s.bytes().all(|b| b.is_ascii_lowercase()),
+        _ => s == "zh-cn" || s == "zh-tw",
+    };
+    if !valid {
+        return Err(Error::InvalidNewsLanguage.into());
+    }
+    Ok(s)
+}
+
+fn list<I, S>(items: I) -> Result<Vec<String>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let items = items
+        .into_iter()
+        .map(|item| {
+            let item = item.as_ref().trim();
+            if !is_text(item) || item.contains(',') {
+                return Err(Error::InvalidNews.into());
+            }
+            Ok(item.to_string())
+        })
+        .collect::<Result<Vec<String>>>()?;
+    if items.is_empty() {
+        return Err(Error::InvalidNews.into());
+    }
+    Ok(items)
+}
+
+impl<'a> UrlExtension for News<'a> {
+    fn namespace(&self) -> (&str, &str) {
+        (Self::PREFIX, Self::NAMESPACE_URI)
+    }
+
+    fn write(&self, writer: &mut ExtensionWriter<'_>) -> Result<()> {
+        writer
+            .start_tag("news")?
+            .start_tag("publication")?
+            .element("name", &self.name)?
+            .element("language", &self.language)?
+            .end_tag("publication")?
+            .element("publication_date", &self.publication_date)?
+            .element("title", &self.title)?;
+        if let Some(keywords) = self.keywords.as_ref() {
+            writer.element("keywords", keywords)?;
+        }
+        if let Some(stock_tickers) = self.stock_tickers.as_ref() {
+            writer.element("stock_tickers", stock_tickers)?;
+        }
+        writer.end_tag("news")?;
+        Ok(())
+    }
+}
+
+/// Returns `true` if the extension is in the news sitemap namespace. Each
+/// such extension counts as one `news:news` entry.
+pub(crate) fn is_news(extension: &dyn UrlExtension) -> bool {
+    extension.namespace().1 == News::NAMESPACE_URI
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+    use crate::sitemap_xml_writer::SitemapXmlWriter;
+
+    fn news() -> Result<News<'static>> {
+        News::new("Name", "en", "2008-12-23", "Title")
+    }
+
+    #[test]
+    fn test_new() {
+        assert!(news().is_ok());
+        assert!(matches!(
+            News::new("", "en", "2008-12-23", "Title").map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidNews)
+        ));
+        assert!(News::new("Name", "en", "2008-12-23", "a\u{0}").is_err());
+    }
+
+    #[test]
+    fn test_language() -> anyhow::Result<()> {
+        for (language, expected) in [
+            ("en", "en"),
+            ("EN", "en"),
+            ("zu", "zu"),
+            ("ast", "ast"),
+            ("zh-cn", "zh-cn"),
+            ("zh-TW", "zh-tw"),
+        ] {
+            let news = News::new("Name", language, "2008-12-23", "Title")?;
+            assert_eq!(news.language, expected);
+        }
+        for language in [
+            "", " ", "e", "xx", "bh", "e1", "en-us", "zh-hk", "eng1", "ça",
+        ] {
+            assert!(
+                matches!(
+                    News::new("Name", language, "2008-12-23", "Title").map_err(|e| e.kind()),
+                    Err(ErrorKind::InvalidNewsLanguage)
+                ),
+                "{}",
+                language
+            );
+        }
+        assert_eq!(ISO_639_1.len(), 183);
+        assert!(ISO_639_1.windows(2).all(|w| w[0] < w[1]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_publication_date() -> anyhow::Result<()> {
+        for date in [
+            "2008-12-23",
+            "2008-12-23T01:02:03Z",
+            "2008-12-23T01:02:03.45+09:00",
+        ] {
+            let news = News::new("Name", "en", date, "Title")?;
+            assert_eq!(news.publication_date, date);
+        }
+        for date in ["", "2008-13-01", "2008-12-23T01:02", "yesterday"] {
+            assert!(matches!(
+                News::new("Name", "en", date, "Title").map_err(|e| e.kind()),
+                Err(ErrorKind::InvalidNewsPublicationDate)
+            ));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_keywords_and_stock_tickers() -> anyhow::Result<()> {
+        let n = news()?
+            .stock_tickers([" NASDAQ:A ", "NYSE:B"])?
+            .keywords(vec!["a & b".to_string()])?;
+        let mut xml_writer = SitemapXmlWriter::new(Vec::new(), false, false);
+        let mut writer = ExtensionWriter::new(&mut xml_writer, News::PREFIX);
+        n.into_owned().write(&mut writer)?;
+        assert_eq!(
+            String::from_utf8(xml_writer.into_inner())?,
+            concat!(
+                "<news:news>",
+                "<news:publication><news:name>Name</news:name><news:language>en</news:language></news:publication>",
+                "<news:publication_date>2008-12-23</news:publication_date>",
+                "<news:title>Title</news:title>",
+                "<news:keywords>a &amp; b</news:keywords>",
+                "<news:stock_tickers>NASDAQ:A, NYSE:B</news:stock_tickers>",
+                "</news:news>"
+            )
+        );
+
+        assert!(news()?.stock_tickers(["A", "B", "C", "D", "E"]).is_ok());
+        assert!(matches!(
+            news()?
+                .stock_tickers(["A", "B", "C", "D", "E", "F"])
+                .map_err(|e| e.kind()),
+            Err(ErrorKind::MaxNumberOfNewsStockTickers)
+        ));
+        assert!(matches!(
+            news()?
+                .stock_tickers(Vec::<&str>::new())
+                .map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidNews)
+        ));
+        assert!(matches!(
+            news()?.keywords(["a,b"]).map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidNews)
+        ));
+        assert!(news()?.keywords(["a", ""]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{loc::Loc, priority::Priority, sitemap_writer::Error, url::Url};
+
+type Result<T, E = crate::Error> = std::result::Result<T, E>;
+
+/// The style of the page URLs generated by [`Pagination`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PaginationStyle {
+    /// `http://www.example.com/posts?page=2`
+    #[default]
+    Query,
+    /// `http://www.example.com/posts/page/2/`
+    Path,
+}
+
+/// A source of `url` entries for a paginated listing.
+///
+/// The first page is the base URL itself. The following pages are
+/// generated in the [`PaginationStyle`].
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{Pagination, PaginationStyle, SitemapWriter};
+/// use std::io::Cursor;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let pagination = Pagination::new("http://www.example.com/posts", 3)
+///     .style(PaginationStyle::Path)
+///     .priority(0.8, 0.2, 0.5)?;
+/// let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+/// for url in pagination.urls() {
+///     writer.write(url?)?;
+/// }
+/// let (inner, _) = writer.finish()?;
+/// assert_eq!(String::from_utf8(inner.into_inner())?, concat!(
+///     r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+///     r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+///     r#"<url><loc>http://www.example.com/posts</loc><priority>0.8</priority></url>"#,
+///     r#"<url><loc>http://www.example.com/posts/page/2/</loc><priority>0.6</priority></url>"#,
+///     r#"<url><loc>http://www.example.com/posts/page/3/</loc><priority>0.5</priority></url>"#,
+///     r#"</urlset>"#
+/// ));
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pagination {
+    base: String,
+    pages: usize,
+    style: PaginationStyle,
+    priority: Option<(f64, f64, f64)>,
+}
+
+impl Pagination {
+    /// Creates a new `Pagination` of `pages` pages starting at `base`.
+    pub fn new<S: Into<String>>(base: S, pages: usize) -> Self {
+        Self {
+            base: base.into(),
+            pages,
+            style: PaginationStyle::default(),
+            priority: None,
+        }
+    }
+
+    /// Sets the style of the page URLs. The default is
+    /// [`PaginationStyle::Query`].
+    pub fn style(mut self, style: PaginationStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the `priority` child entries. The first page has `first`, and
+    /// each following page has `step` less than the previous one, but not
+    /// less than `min`. The values are rounded to one decimal place.
+    pub fn priority(mut self, first: f64, step: f64, min: f64) -> Result<Self> {
+        for p in [first, min] {
+            Priority::try_from(p).map_err(|_| Error::InvalidPriority)?;
+        }
+        if !(step.is_finite() && step >= 0.0) {
+            return Err(Error::InvalidPriority.into());
+        }
+        self.priority = Some((first, step, min));
+        Ok(self)
+    }
+
+    /// Returns the `url` entry for the page (1-based).
+    pub fn url(&self, page: usize) -> Result<Url<'static>> {
+        let loc = match (page, self.style) {
+            (1, _) => self.base.clone(),
+            (_, PaginationStyle::Query) => {
+                let sep = if self.base.contains('?') { '&' } else { '?' };
+                format!("{}{}page={}", self.base, sep, page)
+            }
+            (_, PaginationStyle::Path) => {
+                format!("{}/page/{}/", self.base.trim_end_matches('/'), page)
+            }
+        };
+        let loc = Loc::try_from(loc.as_str())
+            .map_err(|_| Error::InvalidLoc)?
+            .into_owned();
+        let url = Url::new(loc);
+        match self.priority {
+            Some((first, step, min)) => {
+                let p = (first - step * page.saturating_sub(1) as f64).max(min);
+                let p = format!("{:.1}", p);
+                let priority = Priority::try_from(p.as_str())
+                    .map_err(|_| Error::InvalidPriority)?
+                    .into_owned();
+                Ok(url.with_priority(priority))
+            }
+            None => Ok(url),
+        }
+    }
+
+    /// Returns an iterator of the `url` entries for all pages.
+    pub fn urls(&self) -> impl Iterator<Item = Result<Url<'static>>> + '_ {
+        (1..=self.pages).map(|page| self.url(page))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url() -> anyhow::Result<()> {
+        let pagination = Pagination::new("http://www.example.com/posts", 3);
+        assert_eq!(pagination.url(1)?.loc, "http://www.example.com/posts");
+        assert_eq!(
+            pagination.url(2)?.loc,
+            "http://www.example.com/posts?page=2"
+        );
+        assert_eq!(pagination.url(2)?.priority, None);
+
+        let pagination = Pagination::new("http://www.example.com/posts?tag=a", 3);
+        assert_eq!(
+            pagination.url(3)?.loc,
+            "http://www.example.com/posts?tag=a&page=3"
+        );
+
+        let pagination =
+            Pagination::new("http://www.example.com/posts/", 3).style(PaginationStyle::Path);
+        assert_eq!(pagination.url(1)?.loc, "http://www.example.com/posts/");
+        assert_eq!(
+            pagination.url(2)?.loc,
+            "http://www.example.com/posts/page/2/"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_priority() -> anyhow::Result<()> {
+        let pagination = Pagination::new("http://www.example.com/", 5).priority(1.0, 0.1, 0.7)?;
+        let priorities = pagination
+            .urls()
+            .map(|url| url.map(|url| url.priority.unwrap().to_string()))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(priorities, ["1.0", "0.9", "0.8", "0.7", "0.7"]);
+
+        let pagination = Pagination::new("http://www.example.com/", 1);
+        assert!(pagination.clone().priority(1.1, 0.1, 0.0).is_err());
+        assert!(pagination.clone().priority(1.0, 0.1, -0.1).is_err());
+        assert!(pagination.clone().priority(1.0, -0.1, 0.0).is_err());
+        assert!(pagination.priority(1.0, f64::NAN, 0.0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_urls() -> anyhow::Result<()> {
+        assert_eq!(
+            Pagination::new("http://www.example.com/", 0).urls().count(),
+            0
+        );
+        assert_eq!(
+            Pagination::new("http://www.example.com/", 3).urls().count(),
+            3
+        );
+        Ok(())
+    }
+}
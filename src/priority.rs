@@ -1,27 +1,57 @@
-use once_cell::sync::Lazy;
-use regex::Regex;
 use std::borrow::Cow;
 
-static RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r#"\A(\+|-)?([0-9]+(\.[0-9]*)?|\.[0-9]+)\z"#).unwrap());
+#[cfg(feature = "regex")]
+static RE: once_cell::sync::Lazy<regex::Regex> = once_cell::sync::Lazy::new(|| {
+    regex::Regex::new(r#"\A(\+|-)?([0-9]+(\.[0-9]*)?|\.[0-9]+)\z"#).unwrap()
+});
 
-#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Error {
-    #[error("format")]
     Format,
-    #[error("range")]
     Range,
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Error::Format => "format",
+            Error::Range => "range",
+        })
+    }
+}
+
+impl std::error::Error for Error {}
+
 /// A `priority` child entry.
+///
+/// The canonical form used by [`std::fmt::Display`] and
+/// [`std::str::FromStr`] is the decimal string (e.g. `"0.8"`).
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Priority<'a>(Cow<'a, str>);
 
 impl<'a> Priority<'a> {
+    /// Creates a `Priority` without validation.
+    ///
+    /// It is intended for pre-validated inputs in hot loops. The caller must
+    /// ensure that `priority` is a decimal string between `0.0` and `1.0`.
+    /// An invalid value is written as is (but escaped).
+    pub fn new_unchecked<S>(priority: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        Self(priority.into())
+    }
+
+    /// Converts this `Priority` into an owned `Priority<'static>`.
+    pub fn into_owned(self) -> Priority<'static> {
+        Priority(Cow::Owned(self.0.into_owned()))
+    }
+
     pub(crate) fn into_inner(self) -> Cow<'a, str> {
         self.0
     }
 
+    #[cfg(feature = "regex")]
     fn is_valid_format(s: &str) -> Result<(), Error> {
         // <https://www.w3.org/TR/xmlschema11-2/#decimal>
         if RE.is_match(s) {
@@ -31,6 +61,22 @@ impl<'a> Priority<'a> {
         }
     }
 
+    #[cfg(not(feature = "regex"))]
+    fn is_valid_format(s: &str) -> Result<(), Error> {
+        // <https://www.w3.org/TR/xmlschema11-2/#decimal>
+        let s = s.strip_prefix(['+', '-']).unwrap_or(s);
+        let (integer, fraction) = s.split_once('.').unwrap_or((s, ""));
+        let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if (!integer.is_empty() || !fraction.is_empty())
+            && is_digits(integer)
+            && is_digits(fraction)
+        {
+            Ok(())
+        } else {
+            Err(Error::Format)
+        }
+    }
+
     fn is_valid_range(f: f64) -> Result<(), Error> {
         if (0.0..=1.0).contains(&f) {
             Ok(())
@@ -40,6 +86,27 @@ impl<'a> Priority<'a> {
     }
 }
 
+impl<'a> std::fmt::Display for Priority<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0.as_ref())
+    }
+}
+
+impl std::str::FromStr for Priority<'static> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Priority::try_from(s).map(Priority::into_owned)
+    }
+}
+
+/// Borrows a validated `Priority` without copying or validating it again.
+impl<'a, 'b> From<&'a Priority<'b>> for Priority<'a> {
+    fn from(value: &'a Priority<'b>) -> Self {
+        Self(Cow::Borrowed(value.0.as_ref()))
+    }
+}
+
 impl<'a> TryFrom<&'a str> for Priority<'a> {
     type Error = Error;
 
@@ -62,6 +129,29 @@ impl<'a> TryFrom<f64> for Priority<'a> {
     }
 }
 
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for Priority<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.0.as_ref())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for Priority<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Priority::try_from(s.as_str())
+            .map(Priority::into_owned)
+            .map_err(|_| serde::de::Error::custom("invalid priority"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -82,6 +172,36 @@ mod tests {
         assert!(Priority::is_valid_format("inf").is_err());
         assert!(Priority::is_valid_format("+infinity").is_err());
         assert!(Priority::is_valid_format("NaN").is_err());
+        assert!(Priority::is_valid_format("").is_err());
+        assert!(Priority::is_valid_format(".").is_err());
+        assert!(Priority::is_valid_format("+").is_err());
+        assert!(Priority::is_valid_format("+-1").is_err());
+        assert!(Priority::is_valid_format("1.").is_ok());
+        assert!(Priority::is_valid_format("1.0.0").is_err());
+        assert!(Priority::is_valid_format(" 1").is_err());
+        assert!(Priority::is_valid_format("１").is_err());
+    }
+
+    #[cfg(not(feature = "regex"))]
+    #[test]
+    fn test_is_valid_format_agrees_with_regex() {
+        let re = regex::Regex::new(r#"\A(\+|-)?([0-9]+(\.[0-9]*)?|\.[0-9]+)\z"#).unwrap();
+        let parts = ["", "+", "-", "0", "1", "12", ".", "e", " "];
+        for a in parts {
+            for b in parts {
+                for c in parts {
+                    for d in parts {
+                        let s = format!("{}{}{}{}", a, b, c, d);
+                        assert_eq!(
+                            Priority::is_valid_format(&s).is_ok(),
+                            re.is_match(&s),
+                            "{}",
+                            s
+                        );
+                    }
+                }
+            }
+        }
     }
 
     #[test]
@@ -121,4 +241,26 @@ mod tests {
         assert!(Priority::try_from("1.1").is_err());
         Ok(())
     }
+
+    #[test]
+    fn test_from_ref() -> anyhow::Result<()> {
+        let owned = "0.8".parse::<Priority>()?;
+        let borrowed = Priority::from(&owned);
+        assert_eq!(borrowed, owned);
+        assert!(matches!(borrowed.into_inner(), Cow::Borrowed(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_and_from_str() -> anyhow::Result<()> {
+        for s in ["0.0", "+0.5", "1"] {
+            let priority = s.parse::<Priority>()?;
+            assert_eq!(priority.to_string(), s);
+            assert_eq!(priority, Priority::try_from(s)?);
+        }
+        assert_eq!(Priority::try_from(0.8)?.to_string(), "0.8");
+        assert_eq!("1.1".parse::<Priority>(), Err(Error::Range));
+        assert_eq!("a".parse::<Priority>(), Err(Error::Format));
+        Ok(())
+    }
 }
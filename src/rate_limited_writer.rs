@@ -0,0 +1,146 @@
+use std::{
+    io::Write,
+    time::{Duration, Instant},
+};
+
+use crate::sync_all::SyncAll;
+
+/// A writer that limits the number of bytes written per second to the
+/// underlying writer.
+///
+/// It is intended to be used as the underlying writer of
+/// [`crate::SitemapWriter`] or [`crate::SitemapIndexWriter`] when streaming
+/// to a remote sink (e.g. a shared uplink). It blocks the current thread
+/// until the bytes written so far fit in the budget. The writers pass each
+/// entry to it at once, so an entry is not delayed in the middle unless the
+/// underlying writer accepts only a part of it.
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{RateLimitedWriter, SitemapWriter};
+/// use std::io::Cursor;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let inner = RateLimitedWriter::new(Cursor::new(Vec::new()), 1024 * 1024);
+/// let mut writer = SitemapWriter::start(inner)?;
+/// writer.write("http://www.example.com/")?;
+/// let (inner, _) = writer.finish()?;
+/// assert!(String::from_utf8(inner.into_inner().into_inner())?.ends_with("</urlset>"));
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RateLimitedWriter<W> {
+    inner: W,
+    bytes_per_second: u64,
+    start: Option<Instant>,
+    written: u64,
+}
+
+impl<W> RateLimitedWriter<W> {
+    /// Creates a new `RateLimitedWriter` that writes at most
+    /// `bytes_per_second` bytes per second to `inner`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes_per_second` is 0.
+    pub fn new(inner: W, bytes_per_second: u64) -> Self {
+        assert!(
+            bytes_per_second > 0,
+            "bytes_per_second must be greater than 0"
+        );
+        Self {
+            inner,
+            bytes_per_second,
+            start: None,
+            written: 0,
+        }
+    }
+
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps this `RateLimitedWriter<W>`, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Returns the time to wait before the next write.
+    fn delay(&self, now: Instant) -> Duration {
+        let start = match self.start {
+            Some(start) => start,
+            None => return Duration::ZERO,
+        };
+        let seconds = self.written / self.bytes_per_second;
+        let nanos = u128::from(self.written % self.bytes_per_second) * 1_000_000_000
+            / u128::from(self.bytes_per_second);
+        let next = start + Duration::new(seconds, nanos as u32);
+        next.saturating_duration_since(now)
+    }
+}
+
+impl<W: Write> Write for RateLimitedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let now = Instant::now();
+        let delay = self.delay(now);
+        if !delay.is_zero() {
+            std::thread::sleep(delay);
+        }
+        self.start.get_or_insert(now);
+        let n = self.inner.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: SyncAll> SyncAll for RateLimitedWriter<W> {
+    fn sync_all(&mut self) -> std::io::Result<()> {
+        self.inner.sync_all()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay() {
+        let mut writer = RateLimitedWriter::new(Vec::<u8>::new(), 1_000);
+        let now = Instant::now();
+        assert_eq!(writer.delay(now), Duration::ZERO);
+        writer.start = Some(now);
+        writer.written = 1_500;
+        assert_eq!(writer.delay(now), Duration::from_millis(1_500));
+        assert_eq!(
+            writer.delay(now + Duration::from_millis(500)),
+            Duration::from_secs(1)
+        );
+        assert_eq!(writer.delay(now + Duration::from_secs(2)), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_write() -> std::io::Result<()> {
+        let mut writer = RateLimitedWriter::new(Vec::new(), 10_000);
+        let start = Instant::now();
+        writer.write_all(&[b'a'; 500])?;
+        writer.write_all(&[b'b'; 500])?;
+        writer.write_all(&[b'c'; 1])?;
+        // 1,000 bytes at 10,000 bytes per second
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        writer.flush()?;
+        assert_eq!(writer.get_ref().len(), 1_001);
+        Ok(())
+    }
+}
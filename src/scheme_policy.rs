@@ -0,0 +1,85 @@
+/// An allowlist of URL schemes for the `loc` of entries.
+///
+/// The sitemap protocol allows any protocol (e.g. `ftp`), but most sites
+/// want only `http` and `https`. Schemes are matched case-insensitively. An
+/// entry whose `loc` violates it is rejected.
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{SchemePolicy, SitemapWriterBuilder};
+/// use std::io::Cursor;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut writer = SitemapWriterBuilder::new()
+///     .scheme_policy(SchemePolicy::http())
+///     .start(Cursor::new(Vec::new()))?;
+/// writer.write("https://www.example.com/")?;
+/// assert!(writer.write("ftp://ftp.example.com/file.zip").is_err());
+///
+/// let mut writer = SitemapWriterBuilder::new()
+///     .scheme_policy(SchemePolicy::http().allow_scheme("ftp"))
+///     .start(Cursor::new(Vec::new()))?;
+/// writer.write("ftp://ftp.example.com/file.zip")?;
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SchemePolicy {
+    allow: Vec<String>,
+}
+
+impl SchemePolicy {
+    /// Creates a new `SchemePolicy` that allows all schemes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new `SchemePolicy` that allows only `http` and `https`.
+    pub fn http() -> Self {
+        Self::new().allow_scheme("http").allow_scheme("https")
+    }
+
+    /// Adds a scheme (e.g. `"ftp"`) to the allowlist.
+    pub fn allow_scheme<S: Into<String>>(mut self, scheme: S) -> Self {
+        self.allow.push(scheme.into());
+        self
+    }
+
+    /// Returns `true` if the `loc` is allowed by this policy.
+    pub fn is_allowed(&self, loc: &str) -> bool {
+        if self.allow.is_empty() {
+            return true;
+        }
+        match loc.split_once(':') {
+            Some((scheme, _)) => self.allow.iter().any(|s| s.eq_ignore_ascii_case(scheme)),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_allowed() {
+        let policy = SchemePolicy::new();
+        assert!(policy.is_allowed("http://www.example.com/"));
+        assert!(policy.is_allowed("ftp://ftp.example.com/"));
+
+        let policy = SchemePolicy::http();
+        assert!(policy.is_allowed("http://www.example.com/"));
+        assert!(policy.is_allowed("HTTPS://www.example.com/"));
+        assert!(!policy.is_allowed("ftp://ftp.example.com/"));
+        assert!(!policy.is_allowed("sftp://ftp.example.com/"));
+        assert!(!policy.is_allowed("www.example.com"));
+
+        let policy = SchemePolicy::http()
+            .allow_scheme("ftp")
+            .allow_scheme("sftp");
+        assert!(policy.is_allowed("ftp://ftp.example.com/"));
+        assert!(policy.is_allowed("sftp://ftp.example.com/"));
+        assert!(!policy.is_allowed("file:///tmp/a"));
+    }
+}
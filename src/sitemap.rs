@@ -1,8 +1,8 @@
 use std::borrow::Cow;
 
-use crate::{sitemap_index_writer::Error, Lastmod, Loc};
+use crate::{sitemap_format::SitemapFormat, sitemap_index_writer::Error, Lastmod, Loc};
 
-type Result<T, E = Error> = std::result::Result<T, E>;
+type Result<T, E = crate::Error> = std::result::Result<T, E>;
 
 /// A builder for `sitemap` entry.
 ///
@@ -39,10 +39,11 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 pub struct Sitemap<'a> {
     pub(crate) loc: Cow<'a, str>,
     pub(crate) lastmod: Option<Cow<'a, str>>,
+    pub(crate) format: Option<SitemapFormat>,
 }
 
 impl<'a> TryFrom<&'a str> for Sitemap<'a> {
-    type Error = Error;
+    type Error = crate::Error;
 
     fn try_from(value: &'a str) -> Result<Self, Self::Error> {
         Self::loc(value)
@@ -53,9 +54,8 @@ impl<'a> Sitemap<'a> {
     /// Builds a `sitemap` entry with the specified URL as the content of the
     /// `loc` child entry.
     ///
-    /// If you want to ensure that the URL is Valid, use `::url::Url`. If you
-    /// use &str, the URL is assumed to be valid and only the length check and
-    /// XML entity escaping are performed.
+    /// A `&str` URL is checked for its syntax and length (see [`Loc`]) and
+    /// escaped.
     ///
     /// # Examples
     ///
@@ -80,8 +80,49 @@ impl<'a> Sitemap<'a> {
     where
         S: TryInto<Loc<'a>>,
     {
-        let loc = loc.try_into().map_err(|_| Error::InvalidLoc)?.into_inner();
-        Ok(Self { loc, lastmod: None })
+        let loc = loc.try_into().map_err(|_| Error::InvalidLoc)?;
+        Ok(Self::new(loc))
+    }
+
+    /// Builds a `sitemap` entry with the specified validated `Loc`.
+    ///
+    /// Unlike [`Sitemap::loc`], it does not return `Result`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sitemap_xml_writer::{Lastmod, Loc, Sitemap};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let loc = Loc::try_from("http://www.example.com/sitemap1.xml.gz")?;
+    /// let lastmod = Lastmod::try_from("2004-10-01T18:23:17+00:00")?;
+    /// Sitemap::new(loc).with_lastmod(lastmod);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn new(loc: Loc<'a>) -> Self {
+        Self {
+            loc: loc.into_inner(),
+            lastmod: None,
+            format: None,
+        }
+    }
+
+    /// Changes the `loc` child entry to the specified validated `Loc`.
+    pub fn with_loc(mut self, loc: Loc<'a>) -> Self {
+        self.loc = loc.into_inner();
+        self
+    }
+
+    /// Changes the `lastmod` child entry to the specified validated `Lastmod`.
+    pub fn with_lastmod(mut self, lastmod: Lastmod<'a>) -> Self {
+        self.lastmod = Some(lastmod.into_inner());
+        self
+    }
+
+    /// Changes the format hint of the referenced sitemap file.
+    pub fn with_format(mut self, format: SitemapFormat) -> Self {
+        self.format = Some(format);
+        self
     }
 
     /// Changes the `lastmod` child entry to the specified date or datetime.
@@ -127,4 +168,106 @@ impl<'a> Sitemap<'a> {
         self.lastmod = Some(lastmod);
         Ok(self)
     }
+
+    /// Changes the format hint of the referenced sitemap file.
+    ///
+    /// The format hint is not written to the sitemap index file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sitemap_xml_writer::{Sitemap, SitemapFormat};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let sitemap = Sitemap::loc("http://www.example.com/sitemap1.txt")?.format("txt")?;
+    /// assert_eq!(sitemap.format_hint(), Some(SitemapFormat::Txt));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn format<S>(mut self, s: S) -> Result<Self>
+    where
+        S: TryInto<SitemapFormat>,
+    {
+        let format = s.try_into().map_err(|_| Error::InvalidFormat)?;
+        self.format = Some(format);
+        Ok(self)
+    }
+
+    /// Returns the format hint of the referenced sitemap file.
+    pub fn format_hint(&self) -> Option<SitemapFormat> {
+        self.format
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for Sitemap<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut s = serializer.serialize_struct("Sitemap", 3)?;
+        s.serialize_field("loc", &self.loc)?;
+        match &self.lastmod {
+            Some(lastmod) => s.serialize_field("lastmod", lastmod)?,
+            None => s.skip_field("lastmod")?,
+        }
+        match &self.format {
+            Some(format) => s.serialize_field("format", format)?,
+            None => s.skip_field("format")?,
+        }
+        s.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for Sitemap<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Sitemap")]
+        struct Repr<'b> {
+            loc: Loc<'b>,
+            lastmod: Option<Lastmod<'b>>,
+            format: Option<SitemapFormat>,
+        }
+
+        let repr = Repr::deserialize(deserializer)?;
+        Ok(Self {
+            loc: repr.loc.into_inner(),
+            lastmod: repr.lastmod.map(Lastmod::into_inner),
+            format: repr.format,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serde() -> anyhow::Result<()> {
+        let sitemap = Sitemap::loc("http://www.example.com/sitemap1.txt")?
+            .lastmod("2005-01-01")?
+            .format("txt")?;
+        let json = serde_json::to_string(&sitemap)?;
+        assert_eq!(
+            json,
+            r#"{"loc":"http://www.example.com/sitemap1.txt","lastmod":"2005-01-01","format":"txt"}"#
+        );
+        let sitemap = serde_json::from_str::<Sitemap>(&json)?;
+        assert_eq!(sitemap.format_hint(), Some(SitemapFormat::Txt));
+        assert_eq!(serde_json::to_string(&sitemap)?, json);
+
+        let json = r#"{"loc":"http://www.example.com/sitemap1.xml"}"#;
+        assert_eq!(
+            serde_json::to_string(&serde_json::from_str::<Sitemap>(json)?)?,
+            json
+        );
+
+        let json = r#"{"loc":"http://www.example.com/sitemap1.xml","lastmod":"x"}"#;
+        assert!(serde_json::from_str::<Sitemap>(json).is_err());
+        Ok(())
+    }
 }
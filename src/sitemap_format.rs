@@ -0,0 +1,113 @@
+/// The format of a sitemap file referenced by a `sitemap` entry.
+///
+/// It is a hint for managing mixed-format sitemap sets and is not written to
+/// the sitemap index file.
+///
+/// The canonical form used by [`std::fmt::Display`] and
+/// [`std::str::FromStr`] is the lowercase name (e.g. `"xml"`).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum SitemapFormat {
+    /// `xml` (a sitemap XML file)
+    #[default]
+    Xml,
+    /// `txt` (a text file with one URL per line)
+    Txt,
+    /// `rss` (an RSS 2.0 or Atom 1.0 feed)
+    Rss,
+}
+
+impl SitemapFormat {
+    /// Returns the media type of this format.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::SitemapFormat;
+    ///
+    /// assert_eq!(SitemapFormat::Txt.content_type(), "text/plain");
+    /// ```
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            SitemapFormat::Xml => "application/xml",
+            SitemapFormat::Txt => "text/plain",
+            SitemapFormat::Rss => "application/rss+xml",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Error;
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("error")
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl AsRef<str> for SitemapFormat {
+    fn as_ref(&self) -> &str {
+        match self {
+            SitemapFormat::Xml => "xml",
+            SitemapFormat::Txt => "txt",
+            SitemapFormat::Rss => "rss",
+        }
+    }
+}
+
+impl std::fmt::Display for SitemapFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+impl std::str::FromStr for SitemapFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "xml" => Ok(SitemapFormat::Xml),
+            "txt" => Ok(SitemapFormat::Txt),
+            "rss" => Ok(SitemapFormat::Rss),
+            _ => Err(Error),
+        }
+    }
+}
+
+impl TryFrom<&str> for SitemapFormat {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> anyhow::Result<()> {
+        use SitemapFormat::*;
+        for (v, s, t) in [
+            (Xml, "xml", "application/xml"),
+            (Txt, "txt", "text/plain"),
+            (Rss, "rss", "application/rss+xml"),
+        ] {
+            assert_eq!(SitemapFormat::try_from(s)?, v);
+            assert_eq!(s.parse::<SitemapFormat>()?, v);
+            assert_eq!(v.as_ref(), s);
+            assert_eq!(v.to_string(), s);
+            assert_eq!(v.content_type(), t);
+        }
+        assert_eq!(SitemapFormat::default(), Xml);
+        assert!("XML".parse::<SitemapFormat>().is_err());
+        Ok(())
+    }
+}
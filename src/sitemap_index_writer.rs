@@ -1,21 +1,87 @@
-use std::io::Write;
+use std::{borrow::Cow, io::Write};
 
 use self::private::SealedTryIntoSitemap;
 
-use super::{sitemap_xml_writer::SitemapXmlWriter, Sitemap};
+use super::{
+    error::ErrorKind,
+    host_policy::{normalize_loc, HostPolicy, HostPolicyAction},
+    lastmod_skew_guard::{LastmodSkewError, LastmodSkewGuard},
+    scheme_policy::SchemePolicy,
+    sitemap_index_writer_builder::SitemapIndexWriterBuilder,
+    sitemap_xml_writer::SitemapXmlWriter,
+    sync_all::SyncAll,
+    write_summary::WriteSummary,
+    Sitemap,
+};
 
-#[derive(Debug, thiserror::Error)]
+/// The error of [`SitemapIndexWriter`] and the `sitemap` entry builder,
+/// without the context of [`crate::Error`].
+#[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
-    #[error("invalid lastmod")]
+    FutureLastmod,
+    HostNotAllowed,
+    InvalidElementOrder,
+    InvalidFormat,
     InvalidLastmod,
-    #[error("invalid loc")]
     InvalidLoc,
-    #[error("io")]
-    Io(#[from] std::io::Error),
-    #[error("max byte length is 50 MiB (52,428,800 bytes)")]
+    Io(std::io::Error),
     MaxByteLength,
-    #[error("max number of sitemaps is 50,000")]
     MaxNumberOfSitemaps,
+    SchemeNotAllowed,
+    SelfReferentialLoc,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Error::FutureLastmod => "future lastmod",
+            Error::HostNotAllowed => "host not allowed",
+            Error::InvalidElementOrder => "invalid element order",
+            Error::InvalidFormat => "invalid format",
+            Error::InvalidLastmod => "invalid lastmod",
+            Error::InvalidLoc => "invalid loc",
+            Error::Io(_) => "io",
+            Error::MaxByteLength => "max byte length is 50 MiB (52,428,800 bytes)",
+            Error::MaxNumberOfSitemaps => "max number of sitemaps is 50,000",
+            Error::SchemeNotAllowed => "scheme not allowed",
+            Error::SelfReferentialLoc => "self-referential loc",
+        })
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Error::Io(value)
+    }
+}
+
+impl Error {
+    /// Returns the kind of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::FutureLastmod => ErrorKind::FutureLastmod,
+            Error::HostNotAllowed => ErrorKind::HostNotAllowed,
+            Error::InvalidElementOrder => ErrorKind::InvalidElementOrder,
+            Error::InvalidFormat => ErrorKind::InvalidFormat,
+            Error::InvalidLastmod => ErrorKind::InvalidLastmod,
+            Error::InvalidLoc => ErrorKind::InvalidLoc,
+            Error::Io(_) => ErrorKind::Io,
+            Error::MaxByteLength => ErrorKind::MaxByteLength,
+            Error::MaxNumberOfSitemaps => ErrorKind::MaxNumberOfSitemaps,
+            Error::SchemeNotAllowed => ErrorKind::SchemeNotAllowed,
+            Error::SelfReferentialLoc => ErrorKind::SelfReferentialLoc,
+        }
+    }
 }
 
 impl From<crate::sitemap_xml_writer::Error> for Error {
@@ -27,6 +93,15 @@ impl From<crate::sitemap_xml_writer::Error> for Error {
     }
 }
 
+impl From<LastmodSkewError> for Error {
+    fn from(value: LastmodSkewError) -> Self {
+        match value {
+            LastmodSkewError::Invalid => Error::InvalidLastmod,
+            LastmodSkewError::Future => Error::FutureLastmod,
+        }
+    }
+}
+
 type Result<T, E = Error> = std::result::Result<T, E>;
 
 /// A writer for sitemap index file.
@@ -42,10 +117,10 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 /// # fn main() -> anyhow::Result<()> {
 /// let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
 /// writer.write("http://www.example.com/sitemap1.xml.gz")?;
-/// writer.end()?;
+/// let (inner, _) = writer.finish()?;
 ///
 /// assert_eq!(
-///     String::from_utf8(writer.into_inner().into_inner())?,
+///     String::from_utf8(inner.into_inner())?,
 ///     concat!(
 ///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
 ///         r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -75,10 +150,10 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 ///     Sitemap::loc("http://www.example.com/sitemap2.xml.gz")?
 ///         .lastmod("2005-01-01")?,
 /// )?;
-/// writer.end()?;
+/// let (inner, _) = writer.finish()?;
 ///
 /// assert_eq!(
-///     String::from_utf8(writer.into_inner().into_inner())?,
+///     String::from_utf8(inner.into_inner())?,
 ///     concat!(
 ///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
 ///         r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -116,10 +191,10 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 ///         // `::chrono::NaiveDate` support
 ///         .lastmod(::chrono::NaiveDate::parse_from_str("2005-01-01", "%Y-%m-%d")?)?,
 /// )?;
-/// writer.end()?;
+/// let (inner, _) = writer.finish()?;
 ///
 /// assert_eq!(
-///     String::from_utf8(writer.into_inner().into_inner())?,
+///     String::from_utf8(inner.into_inner())?,
 ///     concat!(
 ///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
 ///         r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -157,16 +232,16 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 ///         // `::time::Date` support
 ///         .lastmod(::time::macros::date!(2005-01-01))?,
 /// )?;
-/// writer.end()?;
+/// let (inner, _) = writer.finish()?;
 ///
 /// assert_eq!(
-///     String::from_utf8(writer.into_inner().into_inner())?,
+///     String::from_utf8(inner.into_inner())?,
 ///     concat!(
 ///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
 ///         r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
 ///         r#"<sitemap>"#,
 ///         r#"<loc>http://www.example.com/sitemap1.xml.gz</loc>"#,
-///         r#"<lastmod>2004-10-01T18:23:17.000000000Z</lastmod>"#,
+///         r#"<lastmod>2004-10-01T18:23:17+00:00</lastmod>"#,
 ///         r#"</sitemap>"#,
 ///         r#"<sitemap>"#,
 ///         r#"<loc>http://www.example.com/sitemap2.xml.gz</loc>"#,
@@ -190,9 +265,8 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 /// let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
 /// writer.write(
 ///     // <https://crates.io/crates/url> support
-///     // If you want to ensure that the URL is Valid, use `::url::Url`.
-///     // If you use &str, the URL is assumed to be valid and only the length
-///     // check and XML entity escaping are performed.
+///     // If you use &str, the URL is also parsed with `::url::Url::parse`
+///     // (see `Loc`).
 ///     Sitemap::loc(::url::Url::parse("http://www.example.com/sitemap1.xml.gz")?)?
 ///         .lastmod("2004-10-01T18:23:17+00:00")?
 /// )?;
@@ -200,10 +274,10 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 ///     Sitemap::loc(::url::Url::parse("http://www.example.com/sitemap2.xml.gz")?)?
 ///         .lastmod("2005-01-01")?,
 /// )?;
-/// writer.end()?;
+/// let (inner, _) = writer.finish()?;
 ///
 /// assert_eq!(
-///     String::from_utf8(writer.into_inner().into_inner())?,
+///     String::from_utf8(inner.into_inner())?,
 ///     concat!(
 ///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
 ///         r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -224,72 +298,395 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 ///
 pub struct SitemapIndexWriter<W: Write> {
     writer: SitemapXmlWriter<W>,
+    #[cfg(feature = "url")]
+    canonicalize_locs: bool,
+    #[cfg(feature = "unicode-normalization")]
+    normalize_locs: bool,
+    host_policy: HostPolicy,
+    scheme_policy: SchemePolicy,
+    lastmod_skew_guard: Option<LastmodSkewGuard>,
+    self_locs: Vec<String>,
     number_of_sitemaps: usize,
+    number_of_entries: usize,
+    ended: bool,
+    deferred_error: Option<crate::Error>,
 }
 
 impl<W: Write> SitemapIndexWriter<W> {
     const MAX_NUMBER_OF_SITEMAPS: usize = 50_000;
 
     /// Creates a new `SitemapIndexWriter<W>`. At the same time, write the XML declaration and an opening `<sitemapindex>` tag.
-    pub fn start(inner: W) -> Result<Self> {
-        Self::start_inner(inner, false)
+    pub fn start(inner: W) -> Result<Self, crate::Error> {
+        SitemapIndexWriterBuilder::new().start(inner)
     }
 
     /// Creates a new `SitemapIndexWriter<W>` with indentation enabled. At the same time, write the XML declaration and an opening `<sitemapindex>` tag.
-    pub fn start_with_indent(inner: W) -> Result<Self> {
-        Self::start_inner(inner, true)
+    pub fn start_with_indent(inner: W) -> Result<Self, crate::Error> {
+        SitemapIndexWriterBuilder::new().indent(true).start(inner)
     }
 
     /// Writes a `sitemap` element.
-    pub fn write<'a, S>(&mut self, sitemap: S) -> Result<()>
+    ///
+    /// If the `loc` violates the [`HostPolicy`], it returns an error or skips
+    /// the element according to [`HostPolicy::action`].
+    ///
+    /// The returned error has the index of the entry (see
+    /// [`crate::Context::entry_index`]).
+    pub fn write<'a, S>(&mut self, sitemap: S) -> Result<(), crate::Error>
     where
         S: SealedTryIntoSitemap<'a>,
     {
-        if self.number_of_sitemaps + 1 > Self::MAX_NUMBER_OF_SITEMAPS {
-            return Err(Error::MaxNumberOfSitemaps);
+        self.number_of_entries += 1;
+        sitemap
+            .try_into_sitemap()
+            .and_then(|sitemap| self.write_entry(sitemap).map_err(crate::Error::from))
+            .map_err(|e| e.with_entry_index(self.number_of_entries - 1))
+    }
+
+    /// Returns the error deferred by [`Extend::extend`], and clears it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::SitemapIndexWriter;
+    /// use std::io::Cursor;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let too_long = format!("http://www.example.com/{}", "a".repeat(2048));
+    /// let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
+    /// writer.extend(["http://www.example.com/sitemap1.xml", too_long.as_str()]);
+    /// assert!(writer.deferred_result().is_err());
+    /// assert!(writer.deferred_result().is_ok());
+    /// let (_, summary) = writer.finish()?;
+    /// assert_eq!(summary.number_of_entries(), 1);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn deferred_result(&mut self) -> Result<(), crate::Error> {
+        match self.deferred_error.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
         }
-        self.number_of_sitemaps += 1;
+    }
 
-        let sitemap: Sitemap<'a> = sitemap.try_into_sitemap()?;
-        self.writer.start_tag(b"sitemap")?;
+    /// Writes a closing `</sitemapindex>` tag. It does nothing if the tag
+    /// has already been written, so it may be called before
+    /// [`SitemapIndexWriter::finish`].
+    ///
+    /// It returns the error deferred by [`Extend::extend`] (if any) instead
+    /// (see [`SitemapIndexWriter::deferred_result`]).
+    pub fn end(&mut self) -> Result<(), crate::Error> {
+        self.deferred_result()?;
+        if self.ended {
+            return Ok(());
+        }
+        self.ended = true;
+        self.writer
+            .end_document(b"sitemapindex")
+            .map_err(Error::from)?;
+        Ok(())
+    }
 
-        let content = sitemap.loc;
-        self.writer.element(b"loc", content.as_ref())?;
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        self.writer.get_ref()
+    }
 
-        if let Some(content) = sitemap.lastmod {
-            self.writer.element(b"lastmod", content.as_ref())?;
-        }
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// Writing directly to the underlying writer is not counted towards the
+    /// max byte length and may corrupt the output.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.writer.get_mut()
+    }
 
-        self.writer.end_tag(b"sitemap")?;
-        Ok(())
+    /// Writes a closing `</sitemapindex>` tag, and returns the underlying writer and
+    /// a summary of the written file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::SitemapIndexWriter;
+    /// use std::io::Cursor;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
+    /// writer.write("http://www.example.com/sitemap1.xml.gz")?;
+    /// let (inner, summary) = writer.finish()?;
+    /// assert_eq!(summary.number_of_entries(), 1);
+    /// assert_eq!(summary.byte_length(), inner.get_ref().len());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn finish(mut self) -> Result<(W, WriteSummary), crate::Error> {
+        self.end()?;
+        let summary = WriteSummary::new(self.number_of_sitemaps, self.writer.byte_length());
+        Ok((self.writer.into_inner(), summary))
     }
 
-    /// Writes a closing `</sitemapindex>` tag.
-    pub fn end(&mut self) -> Result<()> {
-        self.writer.end_tag(b"sitemapindex")?;
-        Ok(())
+    /// Closes the file early (e.g. on shutdown): writes a closing
+    /// `</sitemapindex>` tag immediately, flushes the underlying writer, and returns
+    /// the underlying writer and a summary marked as truncated.
+    ///
+    /// The output is a valid sitemap index file containing the entries
+    /// written so far, even after [`SitemapIndexWriter::end`] or a failed
+    /// write: each `sitemap` element is buffered until it is complete, so a
+    /// failed write leaves nothing of it in the output, and room for the
+    /// closing tag is kept within the max byte length. The error deferred by
+    /// [`Extend::extend`] (if any) is discarded.
+    ///
+    /// An underlying writer that fails after accepting a part of an element
+    /// (e.g. a short write followed by an error) still corrupts the output.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::SitemapIndexWriter;
+    /// use std::io::Cursor;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
+    /// writer.write("http://www.example.com/sitemap1.xml")?;
+    /// // shutdown requested
+    /// let (_, summary) = writer.abort_and_close()?;
+    /// assert!(summary.is_truncated());
+    /// assert_eq!(summary.number_of_entries(), 1);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn abort_and_close(mut self) -> Result<(W, WriteSummary), crate::Error> {
+        self.deferred_error = None;
+        let (mut inner, summary) = self.finish()?;
+        inner.flush().map_err(Error::from)?;
+        Ok((inner, summary.truncated()))
     }
 
-    /// Unwraps this `SitemapIndexWrite<W>`, returning the underlying writer.
+    /// Writes a closing `</sitemapindex>` tag, and starts a new file on `inner`
+    /// with the same options. At the same time, write the XML declaration
+    /// and an opening `<sitemapindex>` tag. It returns the previous underlying
+    /// writer and a summary of the previous file.
+    ///
+    /// The internal state (e.g. the escaped `loc` prefix) is reused, which
+    /// avoids reallocation when writing many files. The closing tag is not
+    /// written twice if [`SitemapIndexWriter::end`] has already been called.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::SitemapIndexWriter;
+    /// use std::io::Cursor;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
+    /// writer.write("http://www.example.com/sitemap1.xml")?;
+    /// let (first, summary) = writer.reset(Cursor::new(Vec::new()))?;
+    /// assert_eq!(summary.number_of_entries(), 1);
+    /// let (second, summary) = writer.finish()?;
+    /// assert_eq!(summary.number_of_entries(), 0);
+    /// assert!(second.into_inner().starts_with(br#"<?xml version="1.0" encoding="UTF-8"?>"#));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn reset(&mut self, inner: W) -> Result<(W, WriteSummary), crate::Error> {
+        self.end()?;
+        let summary = WriteSummary::new(self.number_of_sitemaps, self.writer.byte_length());
+        let previous = self.writer.reset(inner);
+        self.number_of_sitemaps = 0;
+        self.number_of_entries = 0;
+        self.ended = false;
+        self.writer.declaration().map_err(Error::from)?;
+        self.writer
+            .start_tag_with_default_ns(b"sitemapindex", &[])
+            .map_err(Error::from)?;
+        Ok((previous, summary))
+    }
+
+    /// Unwraps this `SitemapIndexWriter<W>`, returning the underlying writer.
+    ///
+    /// It does not write the closing `</sitemapindex>` tag, so the file is
+    /// truncated if [`SitemapIndexWriter::end`] is forgotten. Use
+    /// [`SitemapIndexWriter::finish`] instead.
+    #[deprecated(
+        note = "use `finish`, which writes the closing tag and returns the underlying writer"
+    )]
     pub fn into_inner(self) -> W {
         self.writer.into_inner()
     }
 
-    fn start_inner(inner: W, pretty: bool) -> Result<Self> {
+    pub(crate) fn start_with_builder(inner: W, builder: SitemapIndexWriterBuilder) -> Result<Self> {
         let mut s = Self {
-            writer: SitemapXmlWriter::new(inner, pretty),
+            writer: SitemapXmlWriter::new(inner, builder.indent, builder.trailing_newline),
+            #[cfg(feature = "url")]
+            canonicalize_locs: builder.canonicalize_locs,
+            #[cfg(feature = "unicode-normalization")]
+            normalize_locs: builder.normalize_locs,
+            host_policy: builder.host_policy,
+            scheme_policy: builder.scheme_policy,
+            lastmod_skew_guard: builder.lastmod_skew_guard,
+            self_locs: builder
+                .self_locs
+                .iter()
+                .map(|loc| normalize_loc(loc).into_owned())
+                .collect(),
             number_of_sitemaps: 0_usize,
+            number_of_entries: 0_usize,
+            ended: false,
+            deferred_error: None,
         };
+        s.writer.set_loc_prefix(builder.loc_prefix);
+        s.writer.set_escape_audit(builder.escape_audit);
         s.writer.declaration()?;
-        s.writer.start_tag_with_default_ns(b"sitemapindex")?;
+        s.writer.start_tag_with_default_ns(b"sitemapindex", &[])?;
         Ok(s)
     }
+
+    fn write_entry(&mut self, sitemap: Sitemap<'_>) -> Result<()> {
+        let loc = match self.prepare_loc(sitemap.loc)? {
+            Some(loc) => loc,
+            None => return Ok(()),
+        };
+        let lastmod = match (sitemap.lastmod, &self.lastmod_skew_guard) {
+            (Some(lastmod), Some(guard)) => Some(guard.check(lastmod)?),
+            (lastmod, _) => lastmod,
+        };
+
+        if self.ended {
+            return Err(Error::InvalidElementOrder);
+        }
+        if self.number_of_sitemaps + 1 > Self::MAX_NUMBER_OF_SITEMAPS {
+            return Err(Error::MaxNumberOfSitemaps);
+        }
+        self.number_of_sitemaps += 1;
+        self.writer.begin_entry();
+
+        if let Err(e) = self.write_sitemap(loc.as_ref(), lastmod.as_deref()) {
+            self.writer.rollback_entry();
+            self.number_of_sitemaps -= 1;
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    fn write_sitemap(
+        &mut self,
+        loc: &str,
+        lastmod: Option<&str>,
+    ) -> crate::sitemap_xml_writer::Result<()> {
+        self.writer.start_tag(b"sitemap")?;
+        self.writer.loc(loc)?;
+        if let Some(content) = lastmod {
+            self.writer.element(b"lastmod", content)?;
+        }
+        self.writer.end_tag(b"sitemap")?;
+        self.writer.commit_entry()
+    }
+
+    fn prepare_loc<'a>(&self, loc: Cow<'a, str>) -> Result<Option<Cow<'a, str>>> {
+        #[cfg(feature = "unicode-normalization")]
+        let loc = if self.normalize_locs && !unicode_normalization::is_nfc(loc.as_ref()) {
+            crate::Loc::try_from(loc.as_ref())
+                .and_then(|loc| loc.nfc())
+                .map_err(|_| Error::InvalidLoc)?
+                .into_inner()
+        } else {
+            loc
+        };
+        #[cfg(feature = "url")]
+        let loc = if self.canonicalize_locs {
+            crate::Loc::try_from(loc.as_ref())
+                .and_then(|loc| loc.canonicalize())
+                .map_err(|_| Error::InvalidLoc)?
+                .into_inner()
+        } else {
+            loc
+        };
+        let normalized = normalize_loc(loc.as_ref());
+        if self.self_locs.iter().any(|s| *s == normalized) {
+            return Err(Error::SelfReferentialLoc);
+        }
+        if !self.scheme_policy.is_allowed(loc.as_ref()) {
+            return Err(Error::SchemeNotAllowed);
+        }
+        if self.host_policy.is_allowed(loc.as_ref()) {
+            return Ok(Some(loc));
+        }
+        match self.host_policy.action() {
+            HostPolicyAction::Reject => Err(Error::HostNotAllowed),
+            HostPolicyAction::Skip => Ok(None),
+        }
+    }
+}
+
+impl<W: SyncAll> SitemapIndexWriter<W> {
+    /// Writes a closing `</sitemapindex>` tag, flushes and syncs the underlying
+    /// writer to the disk, and returns the underlying writer and a summary of
+    /// the written file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::SitemapIndexWriter;
+    /// use std::{fs::File, io::BufWriter};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let path = std::env::temp_dir().join("sitemap-xml-writer-doctest-SitemapIndexWriter-finish-sync.xml");
+    /// let mut writer = SitemapIndexWriter::start(BufWriter::new(File::create(&path)?))?;
+    /// writer.write("http://www.example.com/")?;
+    /// writer.finish_sync()?;
+    /// # std::fs::remove_file(&path)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn finish_sync(self) -> Result<(W, WriteSummary), crate::Error> {
+        let (mut inner, summary) = self.finish()?;
+        inner.sync_all().map_err(Error::from)?;
+        Ok((inner, summary))
+    }
+}
+
+/// Writes the `sitemap` elements in order, like
+/// [`SitemapIndexWriter::write`].
+///
+/// Writing stops at the first error. The error is deferred: it is returned by
+/// [`SitemapIndexWriter::deferred_result`] or by the next
+/// [`SitemapIndexWriter::end`] (and [`SitemapIndexWriter::finish`]), and
+/// `extend` does nothing until then.
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{Sitemap, SitemapIndexWriter};
+/// use std::io::Cursor;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
+/// writer.extend([
+///     Sitemap::loc("http://www.example.com/sitemap1.xml")?,
+///     Sitemap::loc("http://www.example.com/sitemap2.xml")?.lastmod("2005-01-01")?,
+/// ]);
+/// let (_, summary) = writer.finish()?;
+/// assert_eq!(summary.number_of_entries(), 2);
+/// #     Ok(())
+/// # }
+/// ```
+impl<'a, W: Write, S: SealedTryIntoSitemap<'a>> Extend<S> for SitemapIndexWriter<W> {
+    fn extend<T: IntoIterator<Item = S>>(&mut self, iter: T) {
+        if self.deferred_error.is_some() {
+            return;
+        }
+        for sitemap in iter {
+            if let Err(e) = self.write(sitemap) {
+                self.deferred_error = Some(e);
+                return;
+            }
+        }
+    }
 }
 
 mod private {
-    use crate::Sitemap;
+    use std::borrow::Cow;
 
-    use super::Error;
+    use crate::{Error, Sitemap};
 
     pub trait SealedTryIntoSitemap<'a> {
         fn try_into_sitemap(self) -> Result<Sitemap<'a>, Error>;
@@ -301,6 +698,16 @@ mod private {
         }
     }
 
+    impl<'a, 'b> SealedTryIntoSitemap<'a> for &'a Sitemap<'b> {
+        fn try_into_sitemap(self) -> Result<Sitemap<'a>, Error> {
+            Ok(Sitemap {
+                loc: Cow::Borrowed(self.loc.as_ref()),
+                lastmod: self.lastmod.as_deref().map(Cow::Borrowed),
+                format: self.format,
+            })
+        }
+    }
+
     impl<'a> SealedTryIntoSitemap<'a> for &'a str {
         fn try_into_sitemap(self) -> Result<Sitemap<'a>, Error> {
             Sitemap::loc(self)
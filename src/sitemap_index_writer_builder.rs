@@ -0,0 +1,146 @@
+use std::io::Write;
+
+use crate::error::Error;
+use crate::escape_audit::{EscapeAudit, EscapeAuditFn};
+use crate::host_policy::HostPolicy;
+use crate::lastmod_skew_guard::LastmodSkewGuard;
+use crate::scheme_policy::SchemePolicy;
+use crate::sitemap_index_writer::SitemapIndexWriter;
+
+type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// A builder for [`SitemapIndexWriter`].
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::SitemapIndexWriterBuilder;
+/// use std::io::Cursor;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut writer = SitemapIndexWriterBuilder::new()
+///     .indent(true)
+///     .trailing_newline(true)
+///     .start(Cursor::new(Vec::new()))?;
+/// writer.write("http://www.example.com/sitemap1.xml.gz")?;
+/// let (inner, _) = writer.finish()?;
+///
+/// assert_eq!(
+///     String::from_utf8(inner.into_inner())?,
+///     r#"<?xml version="1.0" encoding="UTF-8"?>
+/// <sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+///   <sitemap>
+///     <loc>http://www.example.com/sitemap1.xml.gz</loc>
+///   </sitemap>
+/// </sitemapindex>
+/// "#
+/// );
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SitemapIndexWriterBuilder {
+    #[cfg(feature = "url")]
+    pub(crate) canonicalize_locs: bool,
+    pub(crate) escape_audit: Option<EscapeAuditFn>,
+    pub(crate) host_policy: HostPolicy,
+    pub(crate) indent: bool,
+    pub(crate) lastmod_skew_guard: Option<LastmodSkewGuard>,
+    pub(crate) loc_prefix: Option<String>,
+    #[cfg(feature = "unicode-normalization")]
+    pub(crate) normalize_locs: bool,
+    pub(crate) scheme_policy: SchemePolicy,
+    pub(crate) self_locs: Vec<String>,
+    pub(crate) trailing_newline: bool,
+}
+
+impl SitemapIndexWriterBuilder {
+    /// Creates a new `SitemapIndexWriterBuilder` with the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables or disables the canonicalization of the `loc` of each entry
+    /// by [`crate::Loc::canonicalize`]. The default is `false`.
+    #[cfg(feature = "url")]
+    pub fn canonicalize_locs(mut self, canonicalize_locs: bool) -> Self {
+        self.canonicalize_locs = canonicalize_locs;
+        self
+    }
+
+    /// Sets a callback reporting the characters escaped in each child entry
+    /// (see [`EscapeAudit`]). It is intended for debugging. The default is
+    /// none.
+    pub fn escape_audit<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&EscapeAudit<'_>) + Send + Sync + 'static,
+    {
+        self.escape_audit = Some(EscapeAuditFn::new(f));
+        self
+    }
+
+    /// Sets the [`HostPolicy`] applied to the `loc` of each entry. The
+    /// default allows all hosts.
+    pub fn host_policy(mut self, host_policy: HostPolicy) -> Self {
+        self.host_policy = host_policy;
+        self
+    }
+
+    /// Enables or disables indentation. The default is `false`.
+    pub fn indent(mut self, indent: bool) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Sets a [`LastmodSkewGuard`] applied to the `lastmod` of each entry.
+    /// The default allows any `lastmod`.
+    pub fn lastmod_skew_guard(mut self, lastmod_skew_guard: LastmodSkewGuard) -> Self {
+        self.lastmod_skew_guard = Some(lastmod_skew_guard);
+        self
+    }
+
+    /// Sets a common prefix of the `loc` of entries (e.g.
+    /// `"https://www.example.com/products/"`). The escaped prefix is cached,
+    /// and only the rest of each matching `loc` is escaped. The output is the
+    /// same as without it.
+    pub fn loc_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.loc_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Enables or disables the Unicode normalization (NFC) of the `loc` of
+    /// each entry by [`crate::Loc::nfc`]. It is applied before the
+    /// canonicalization. The default is `false`.
+    #[cfg(feature = "unicode-normalization")]
+    pub fn normalize_locs(mut self, normalize_locs: bool) -> Self {
+        self.normalize_locs = normalize_locs;
+        self
+    }
+
+    /// Sets the [`SchemePolicy`] applied to the `loc` of each entry. The
+    /// default allows all schemes.
+    pub fn scheme_policy(mut self, scheme_policy: SchemePolicy) -> Self {
+        self.scheme_policy = scheme_policy;
+        self
+    }
+
+    /// Adds a public URL of the sitemap index file itself. Entries whose `loc` is
+    /// one of them are rejected. Call it more than once for multiple URLs.
+    pub fn self_loc<S: Into<String>>(mut self, loc: S) -> Self {
+        self.self_locs.push(loc.into());
+        self
+    }
+
+    /// Enables or disables a newline (`\n`) after the closing
+    /// `</sitemapindex>` tag. The default is `false`.
+    pub fn trailing_newline(mut self, trailing_newline: bool) -> Self {
+        self.trailing_newline = trailing_newline;
+        self
+    }
+
+    /// Creates a new `SitemapIndexWriter<W>` with the options. At the same
+    /// time, write the XML declaration and an opening `<sitemapindex>` tag.
+    pub fn start<W: Write>(self, inner: W) -> Result<SitemapIndexWriter<W>> {
+        Ok(SitemapIndexWriter::start_with_builder(inner, self)?)
+    }
+}
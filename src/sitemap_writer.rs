@@ -1,27 +1,166 @@
-use std::io::Write;
+use std::{borrow::Cow, io::Write};
 
-use crate::url::Url;
+use crate::{
+    error::ErrorKind,
+    google_news_profile::GoogleNewsProfile,
+    host_policy::{normalize_loc, HostPolicy, HostPolicyAction},
+    image::Image,
+    lastmod_skew_guard::{LastmodSkewError, LastmodSkewGuard},
+    news::{is_news, News},
+    scheme_policy::SchemePolicy,
+    sitemap_writer_builder::SitemapWriterBuilder,
+    sync_all::SyncAll,
+    url::{Extension, Url},
+    url_extension::{is_ncname, ExtensionWriter, UrlExtension},
+    url_writer::UrlWriter,
+    write_summary::WriteSummary,
+    Loc,
+};
 
 use self::private::SealedTryIntoUrl;
 
 use super::sitemap_xml_writer::SitemapXmlWriter;
 
-#[derive(Debug, thiserror::Error)]
+/// The error of [`SitemapWriter`] and the `url` entry builders, without
+/// the context of [`crate::Error`].
+#[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
-    #[error("invalid changefreq")]
+    FutureLastmod,
+    HostNotAllowed,
+    InvalidAlternate,
     InvalidChangefreq,
-    #[error("invalid lastmod")]
+    InvalidElementOrder,
+    InvalidExtension,
+    InvalidHreflang,
+    InvalidImage,
     InvalidLastmod,
-    #[error("invalid loc")]
     InvalidLoc,
-    #[error("invalid priority")]
+    InvalidNews,
+    InvalidNewsLanguage,
+    InvalidNewsPublicationDate,
     InvalidPriority,
-    #[error("io")]
-    Io(#[from] std::io::Error),
-    #[error("max byte length is 50 MiB (52,428,800 bytes)")]
+    InvalidVideo,
+    InvalidVideoDuration,
+    InvalidVideoExpirationDate,
+    InvalidVideoPlatform,
+    InvalidVideoPrice,
+    InvalidVideoPublicationDate,
+    InvalidVideoRating,
+    InvalidVideoRestriction,
+    InvalidVideoTag,
+    InvalidVideoUploader,
+    Io(std::io::Error),
     MaxByteLength,
-    #[error("max number of urls is 50,000")]
+    MaxNumberOfImages,
+    MaxNumberOfNews,
+    MaxNumberOfNewsStockTickers,
+    MaxNumberOfNewsUrls,
     MaxNumberOfUrls,
+    MaxNumberOfVideoTags,
+    SchemeNotAllowed,
+    SelfReferentialLoc,
+    StaleNewsPublicationDate,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Error::FutureLastmod => "future lastmod",
+            Error::HostNotAllowed => "host not allowed",
+            Error::InvalidAlternate => "invalid alternate",
+            Error::InvalidChangefreq => "invalid changefreq",
+            Error::InvalidElementOrder => "invalid element order",
+            Error::InvalidExtension => "invalid extension",
+            Error::InvalidHreflang => "invalid hreflang",
+            Error::InvalidImage => "invalid image",
+            Error::InvalidLastmod => "invalid lastmod",
+            Error::InvalidLoc => "invalid loc",
+            Error::InvalidNews => "invalid news",
+            Error::InvalidNewsLanguage => "invalid news language",
+            Error::InvalidNewsPublicationDate => "invalid news publication date",
+            Error::InvalidPriority => "invalid priority",
+            Error::InvalidVideo => "invalid video",
+            Error::InvalidVideoDuration => "invalid video duration (1 to 28,800 seconds)",
+            Error::InvalidVideoExpirationDate => "invalid video expiration date",
+            Error::InvalidVideoPlatform => "invalid video platform",
+            Error::InvalidVideoPrice => "invalid video price",
+            Error::InvalidVideoPublicationDate => "invalid video publication date",
+            Error::InvalidVideoRating => "invalid video rating (0.0 to 5.0)",
+            Error::InvalidVideoRestriction => "invalid video restriction",
+            Error::InvalidVideoTag => "invalid video tag",
+            Error::InvalidVideoUploader => "invalid video uploader",
+            Error::Io(_) => "io",
+            Error::MaxByteLength => "max byte length is 50 MiB (52,428,800 bytes)",
+            Error::MaxNumberOfImages => "max number of images is 1,000",
+            Error::MaxNumberOfNews => "max number of news is 1",
+            Error::MaxNumberOfNewsStockTickers => "max number of news stock tickers is 5",
+            Error::MaxNumberOfNewsUrls => "max number of urls in a news sitemap is 1,000",
+            Error::MaxNumberOfUrls => "max number of urls is 50,000",
+            Error::MaxNumberOfVideoTags => "max number of video tags is 32",
+            Error::SchemeNotAllowed => "scheme not allowed",
+            Error::SelfReferentialLoc => "self-referential loc",
+            Error::StaleNewsPublicationDate => "news publication date is older than 2 days",
+        })
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Error::Io(value)
+    }
+}
+
+impl Error {
+    /// Returns the kind of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::FutureLastmod => ErrorKind::FutureLastmod,
+            Error::HostNotAllowed => ErrorKind::HostNotAllowed,
+            Error::InvalidAlternate => ErrorKind::InvalidAlternate,
+            Error::InvalidChangefreq => ErrorKind::InvalidChangefreq,
+            Error::InvalidElementOrder => ErrorKind::InvalidElementOrder,
+            Error::InvalidExtension => ErrorKind::InvalidExtension,
+            Error::InvalidHreflang => ErrorKind::InvalidHreflang,
+            Error::InvalidImage => ErrorKind::InvalidImage,
+            Error::InvalidLastmod => ErrorKind::InvalidLastmod,
+            Error::InvalidLoc => ErrorKind::InvalidLoc,
+            Error::InvalidNews => ErrorKind::InvalidNews,
+            Error::InvalidNewsLanguage => ErrorKind::InvalidNewsLanguage,
+            Error::InvalidNewsPublicationDate => ErrorKind::InvalidNewsPublicationDate,
+            Error::InvalidPriority => ErrorKind::InvalidPriority,
+            Error::InvalidVideo => ErrorKind::InvalidVideo,
+            Error::InvalidVideoDuration => ErrorKind::InvalidVideoDuration,
+            Error::InvalidVideoExpirationDate => ErrorKind::InvalidVideoExpirationDate,
+            Error::InvalidVideoPlatform => ErrorKind::InvalidVideoPlatform,
+            Error::InvalidVideoPrice => ErrorKind::InvalidVideoPrice,
+            Error::InvalidVideoPublicationDate => ErrorKind::InvalidVideoPublicationDate,
+            Error::InvalidVideoRating => ErrorKind::InvalidVideoRating,
+            Error::InvalidVideoRestriction => ErrorKind::InvalidVideoRestriction,
+            Error::InvalidVideoTag => ErrorKind::InvalidVideoTag,
+            Error::InvalidVideoUploader => ErrorKind::InvalidVideoUploader,
+            Error::Io(_) => ErrorKind::Io,
+            Error::MaxByteLength => ErrorKind::MaxByteLength,
+            Error::MaxNumberOfImages => ErrorKind::MaxNumberOfImages,
+            Error::MaxNumberOfNews => ErrorKind::MaxNumberOfNews,
+            Error::MaxNumberOfNewsStockTickers => ErrorKind::MaxNumberOfNewsStockTickers,
+            Error::MaxNumberOfNewsUrls => ErrorKind::MaxNumberOfNewsUrls,
+            Error::MaxNumberOfUrls => ErrorKind::MaxNumberOfUrls,
+            Error::MaxNumberOfVideoTags => ErrorKind::MaxNumberOfVideoTags,
+            Error::SchemeNotAllowed => ErrorKind::SchemeNotAllowed,
+            Error::SelfReferentialLoc => ErrorKind::SelfReferentialLoc,
+            Error::StaleNewsPublicationDate => ErrorKind::StaleNewsPublicationDate,
+        }
+    }
 }
 
 impl From<crate::sitemap_xml_writer::Error> for Error {
@@ -33,10 +172,24 @@ impl From<crate::sitemap_xml_writer::Error> for Error {
     }
 }
 
+impl From<LastmodSkewError> for Error {
+    fn from(value: LastmodSkewError) -> Self {
+        match value {
+            LastmodSkewError::Invalid => Error::InvalidLastmod,
+            LastmodSkewError::Future => Error::FutureLastmod,
+        }
+    }
+}
+
 type Result<T, E = Error> = std::result::Result<T, E>;
 
 /// A writer for sitemap file.
 ///
+/// The limits (the max byte length and the max number of URLs) are checked
+/// against the sitemap XML bytes passed to the underlying writer. An
+/// underlying writer that transforms the byte stream (e.g. wraps it in an
+/// HTML `<pre>` for a human-readable mirror) does not affect them.
+///
 /// # Examples
 ///
 /// The following example is a sitemap containing only one URL specified by `&str`.
@@ -48,10 +201,10 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 /// # fn main() -> anyhow::Result<()> {
 /// let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
 /// writer.write("http://www.example.com/")?;
-/// writer.end()?;
+/// let (inner, _) = writer.finish()?;
 ///
 /// assert_eq!(
-///     String::from_utf8(writer.into_inner().into_inner())?,
+///     String::from_utf8(inner.into_inner())?,
 ///     concat!(
 ///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
 ///         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -79,10 +232,10 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 ///         .changefreq("monthly")?
 ///         .priority("0.8")?,
 /// )?;
-/// writer.end()?;
+/// let (inner, _) = writer.finish()?;
 ///
 /// assert_eq!(
-///     String::from_utf8(writer.into_inner().into_inner())?,
+///     String::from_utf8(inner.into_inner())?,
 ///     concat!(
 ///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
 ///         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -115,10 +268,10 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 ///         .changefreq(Changefreq::Monthly)?
 ///         .priority(0.8)?
 /// )?;
-/// writer.end()?;
+/// let (inner, _) = writer.finish()?;
 ///
 /// assert_eq!(
-///     String::from_utf8(writer.into_inner().into_inner())?,
+///     String::from_utf8(inner.into_inner())?,
 ///     concat!(
 ///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
 ///         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -151,10 +304,10 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 ///         .changefreq(Changefreq::Monthly)?
 ///         .priority(0.8)?
 /// )?;
-/// writer.end()?;
+/// let (inner, _) = writer.finish()?;
 ///
 /// assert_eq!(
-///     String::from_utf8(writer.into_inner().into_inner())?,
+///     String::from_utf8(inner.into_inner())?,
 ///     concat!(
 ///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
 ///         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -183,18 +336,17 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 /// writer.write(
 ///     // <https://crates.io/crates/url> support
 ///     // You can specify `::url::Url`.
-///     // If you want to ensure that the URL is valid, use `::url::Url`.
-///     // If you use &str, the URL is assumed to be valid and only the length
-///     // check and XML entity escaping are performed.
+///     // If you use &str, the URL is also parsed with `::url::Url::parse`
+///     // (see `Loc`).
 ///     Url::loc(::url::Url::parse("http://www.example.com/")?)?
 ///         .lastmod("2005-01-01")?
 ///         .changefreq(Changefreq::Monthly)?
 ///         .priority(0.8)?
 /// )?;
-/// writer.end()?;
+/// let (inner, _) = writer.finish()?;
 ///
 /// assert_eq!(
-///     String::from_utf8(writer.into_inner().into_inner())?,
+///     String::from_utf8(inner.into_inner())?,
 ///     concat!(
 ///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
 ///         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -212,80 +364,644 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 /// ```
 pub struct SitemapWriter<W: Write> {
     writer: SitemapXmlWriter<W>,
+    #[cfg(feature = "url")]
+    canonicalize_locs: bool,
+    #[cfg(feature = "unicode-normalization")]
+    normalize_locs: bool,
+    host_policy: HostPolicy,
+    scheme_policy: SchemePolicy,
+    lastmod_skew_guard: Option<LastmodSkewGuard>,
+    google_news_profile: Option<GoogleNewsProfile>,
+    namespaces: Vec<(String, String)>,
+    self_locs: Vec<String>,
     number_of_urls: usize,
+    number_of_entries: usize,
+    provenance_comments: bool,
+    url_open: bool,
+    ended: bool,
+    deferred_error: Option<crate::Error>,
 }
 
 impl<W: Write> SitemapWriter<W> {
     const MAX_NUMBER_OF_URLS: usize = 50_000;
 
     /// Creates a new `SitemapWriter<W>`. At the same time, write the XML declaration and an opening `<urlset>` tag.
-    pub fn start(inner: W) -> Result<Self> {
-        Self::start_inner(inner, false)
+    pub fn start(inner: W) -> Result<Self, crate::Error> {
+        SitemapWriterBuilder::new().start(inner)
     }
 
     /// Creates a new `SitemapWriter<W>` with indentation enabled. At the same time, write the XML declaration and an opening `<urlset>` tag.
-    pub fn start_with_indent(inner: W) -> Result<Self> {
-        Self::start_inner(inner, true)
+    pub fn start_with_indent(inner: W) -> Result<Self, crate::Error> {
+        SitemapWriterBuilder::new().indent(true).start(inner)
     }
 
     /// Writes a `url` element.
-    pub fn write<'a, U>(&mut self, url: U) -> Result<()>
+    ///
+    /// If the `loc` violates the [`HostPolicy`], it returns an error or skips
+    /// the element according to [`HostPolicy::action`].
+    ///
+    /// The returned error has the index of the entry (see
+    /// [`crate::Context::entry_index`]).
+    pub fn write<'a, U>(&mut self, url: U) -> Result<(), crate::Error>
     where
         U: SealedTryIntoUrl<'a>,
     {
+        self.number_of_entries += 1;
+        url.try_into_url()
+            .and_then(|url| self.write_url(url))
+            .map_err(|e| e.with_entry_index(self.entry_index()))
+    }
+
+    /// Starts a `url` element with the specified URL as the content of the
+    /// `loc` child entry, and returns a [`UrlWriter`] to write the remaining
+    /// child entries one by one.
+    ///
+    /// If the returned [`UrlWriter`] is dropped without calling
+    /// [`UrlWriter::end`], the `url` element is closed by the next call to
+    /// [`SitemapWriter::write`], [`SitemapWriter::begin_url`] or
+    /// [`SitemapWriter::end`].
+    ///
+    /// If the `loc` violates the [`HostPolicy`] and [`HostPolicy::action`] is
+    /// [`HostPolicyAction::Skip`], the returned [`UrlWriter`] writes nothing.
+    ///
+    /// The errors returned by it and by the [`UrlWriter`] have the index of
+    /// the entry (see [`crate::Context::entry_index`]).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::{Changefreq, SitemapWriter};
+    /// use std::io::Cursor;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    /// let mut url = writer.begin_url("http://www.example.com/")?;
+    /// url.lastmod("2005-01-01")?;
+    /// url.changefreq(Changefreq::Monthly)?;
+    /// url.end()?;
+    /// let (inner, _) = writer.finish()?;
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(inner.into_inner())?,
+    ///     concat!(
+    ///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+    ///         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+    ///         r#"<url>"#,
+    ///         r#"<loc>http://www.example.com/</loc>"#,
+    ///         r#"<lastmod>2005-01-01</lastmod>"#,
+    ///         r#"<changefreq>monthly</changefreq>"#,
+    ///         r#"</url>"#,
+    ///         r#"</urlset>"#
+    ///     )
+    /// );
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn begin_url<'a, S>(&mut self, loc: S) -> Result<UrlWriter<'_, W>, crate::Error>
+    where
+        S: TryInto<Loc<'a>>,
+    {
+        self.number_of_entries += 1;
+        let begun = loc
+            .try_into()
+            .map_err(|_| Error::InvalidLoc)
+            .and_then(|loc| self.begin_url_with_loc(loc.into_inner()));
+        match begun {
+            Ok(true) => Ok(UrlWriter::new(self)),
+            Ok(false) => Ok(UrlWriter::skipped(self)),
+            Err(e) => Err(crate::Error::from(e).with_entry_index(self.entry_index())),
+        }
+    }
+
+    /// Returns the error deferred by [`Extend::extend`], and clears it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::SitemapWriter;
+    /// use std::io::Cursor;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let too_long = format!("http://www.example.com/{}", "a".repeat(2048));
+    /// let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    /// writer.extend(["http://www.example.com/1", too_long.as_str(), "http://www.example.com/3"]);
+    /// assert!(writer.deferred_result().is_err());
+    /// assert!(writer.deferred_result().is_ok());
+    /// let (_, summary) = writer.finish()?;
+    /// assert_eq!(summary.number_of_entries(), 1);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn deferred_result(&mut self) -> Result<(), crate::Error> {
+        match self.deferred_error.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes a closing `</urlset>` tag. It does nothing if the tag has
+    /// already been written, so it may be called before
+    /// [`SitemapWriter::finish`].
+    ///
+    /// It returns the error deferred by [`Extend::extend`] (if any) instead
+    /// (see [`SitemapWriter::deferred_result`]).
+    pub fn end(&mut self) -> Result<(), crate::Error> {
+        self.deferred_result()?;
+        if self.ended {
+            return Ok(());
+        }
+        self.close_open_url()?;
+        self.ended = true;
+        self.writer.end_document(b"urlset").map_err(Error::from)?;
+        Ok(())
+    }
+
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        self.writer.get_ref()
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// Writing directly to the underlying writer is not counted towards the
+    /// max byte length and may corrupt the output.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.writer.get_mut()
+    }
+
+    /// Writes a closing `</urlset>` tag, and returns the underlying writer and
+    /// a summary of the written file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::SitemapWriter;
+    /// use std::io::Cursor;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    /// writer.write("http://www.example.com/")?;
+    /// let (inner, summary) = writer.finish()?;
+    /// assert_eq!(summary.number_of_entries(), 1);
+    /// assert_eq!(summary.byte_length(), inner.get_ref().len());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn finish(mut self) -> Result<(W, WriteSummary), crate::Error> {
+        self.end()?;
+        let summary = WriteSummary::new(self.number_of_urls, self.writer.byte_length());
+        Ok((self.writer.into_inner(), summary))
+    }
+
+    /// Closes the file early (e.g. on shutdown): writes a closing
+    /// `</urlset>` tag immediately, flushes the underlying writer, and returns
+    /// the underlying writer and a summary marked as truncated.
+    ///
+    /// The output is a valid sitemap file containing the entries written so
+    /// far, even after [`SitemapWriter::end`] or a failed write: each `url`
+    /// element is buffered until it is complete, so a failed write leaves
+    /// nothing of it in the output, and room for the closing tag is kept
+    /// within the max byte length. A `url` element started by
+    /// [`SitemapWriter::begin_url`] is closed, or discarded if it cannot be
+    /// closed. The error deferred by [`Extend::extend`] (if any) is discarded.
+    ///
+    /// An underlying writer that fails after accepting a part of an element
+    /// (e.g. a short write followed by an error) still corrupts the output.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::SitemapWriter;
+    /// use std::io::Cursor;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    /// writer.write("http://www.example.com/")?;
+    /// // shutdown requested
+    /// let (_, summary) = writer.abort_and_close()?;
+    /// assert!(summary.is_truncated());
+    /// assert_eq!(summary.number_of_entries(), 1);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn abort_and_close(mut self) -> Result<(W, WriteSummary), crate::Error> {
+        self.deferred_error = None;
+        // an open `url` element that cannot be closed has been discarded
+        let _ = self.close_open_url();
+        let (mut inner, summary) = self.finish()?;
+        inner.flush().map_err(Error::from)?;
+        Ok((inner, summary.truncated()))
+    }
+
+    /// Writes a closing `</urlset>` tag, and starts a new file on `inner`
+    /// with the same options. At the same time, write the XML declaration
+    /// and an opening `<urlset>` tag. It returns the previous underlying
+    /// writer and a summary of the previous file.
+    ///
+    /// The internal state (e.g. the escaped `loc` prefix) is reused, which
+    /// avoids reallocation when writing many files. The closing tag is not
+    /// written twice if [`SitemapWriter::end`] has already been called.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::SitemapWriter;
+    /// use std::io::Cursor;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    /// writer.write("http://www.example.com/")?;
+    /// let (first, summary) = writer.reset(Cursor::new(Vec::new()))?;
+    /// assert_eq!(summary.number_of_entries(), 1);
+    /// let (second, summary) = writer.finish()?;
+    /// assert_eq!(summary.number_of_entries(), 0);
+    /// assert!(second.into_inner().starts_with(br#"<?xml version="1.0" encoding="UTF-8"?>"#));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn reset(&mut self, inner: W) -> Result<(W, WriteSummary), crate::Error> {
+        self.end()?;
+        let summary = WriteSummary::new(self.number_of_urls, self.writer.byte_length());
+        let previous = self.writer.reset(inner);
+        self.number_of_urls = 0;
+        self.number_of_entries = 0;
+        self.url_open = false;
+        self.ended = false;
+        self.writer.declaration().map_err(Error::from)?;
+        self.writer
+            .start_tag_with_default_ns(b"urlset", &self.namespaces)
+            .map_err(Error::from)?;
+        Ok((previous, summary))
+    }
+
+    /// Unwraps this `SitemapWriter<W>`, returning the underlying writer.
+    ///
+    /// It does not write the closing `</urlset>` tag, so the file is
+    /// truncated if [`SitemapWriter::end`] is forgotten. Use
+    /// [`SitemapWriter::finish`] instead.
+    #[deprecated(
+        note = "use `finish`, which writes the closing tag and returns the underlying writer"
+    )]
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner()
+    }
+
+    pub(crate) fn start_with_builder(inner: W, builder: SitemapWriterBuilder) -> Result<Self> {
+        for (i, (prefix, uri)) in builder.namespaces.iter().enumerate() {
+            if !is_ncname(prefix)
+                || prefix.to_ascii_lowercase().starts_with("xml")
+                || uri.is_empty()
+                || builder.namespaces[..i].iter().any(|(p, _)| p == prefix)
+            {
+                return Err(Error::InvalidExtension);
+            }
+        }
+        let mut s = Self {
+            writer: SitemapXmlWriter::new(inner, builder.indent, builder.trailing_newline),
+            #[cfg(feature = "url")]
+            canonicalize_locs: builder.canonicalize_locs,
+            #[cfg(feature = "unicode-normalization")]
+            normalize_locs: builder.normalize_locs,
+            host_policy: builder.host_policy,
+            scheme_policy: builder.scheme_policy,
+            lastmod_skew_guard: builder.lastmod_skew_guard,
+            google_news_profile: builder.google_news_profile,
+            namespaces: builder.namespaces,
+            self_locs: builder
+                .self_locs
+                .iter()
+                .map(|loc| normalize_loc(loc).into_owned())
+                .collect(),
+            number_of_urls: 0_usize,
+            number_of_entries: 0_usize,
+            provenance_comments: builder.provenance_comments,
+            url_open: false,
+            ended: false,
+            deferred_error: None,
+        };
+        s.writer.set_loc_prefix(builder.loc_prefix);
+        s.writer.set_escape_audit(builder.escape_audit);
+        s.writer.declaration()?;
+        s.writer
+            .start_tag_with_default_ns(b"urlset", &s.namespaces)?;
+        Ok(s)
+    }
+
+    /// Returns the index of the current entry (the last `write` or
+    /// `begin_url` call).
+    pub(crate) fn entry_index(&self) -> usize {
+        self.number_of_entries - 1
+    }
+
+    pub(crate) fn xml_writer(&mut self) -> &mut SitemapXmlWriter<W> {
+        &mut self.writer
+    }
+
+    pub(crate) fn check_lastmod<'a>(&self, lastmod: Cow<'a, str>) -> Result<Cow<'a, str>> {
+        match &self.lastmod_skew_guard {
+            Some(guard) => guard.check(lastmod).map_err(Error::from),
+            None => Ok(lastmod),
+        }
+    }
+
+    pub(crate) fn check_news(&self, news: &News<'_>) -> Result<()> {
+        match &self.google_news_profile {
+            Some(profile) if !profile.is_recent(news.publication_date()) => {
+                Err(Error::StaleNewsPublicationDate)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn check_namespace(&self, extension: &dyn UrlExtension) -> Result<()> {
+        let (prefix, uri) = extension.namespace();
+        if self.namespaces.iter().any(|(p, u)| p == prefix && u == uri) {
+            Ok(())
+        } else {
+            Err(Error::InvalidExtension)
+        }
+    }
+
+    pub(crate) fn write_extension(
+        &mut self,
+        extension: &dyn UrlExtension,
+    ) -> Result<(), crate::Error> {
+        let (prefix, _) = extension.namespace();
+        let mut writer = ExtensionWriter::new(&mut self.writer, prefix);
+        extension.write(&mut writer)?;
+        writer.finish()
+    }
+
+    pub(crate) fn end_url_inner(&mut self) -> Result<()> {
+        if let Err(e) = self
+            .writer
+            .end_tag(b"url")
+            .and_then(|()| self.writer.commit_entry())
+        {
+            self.discard_url();
+            return Err(e.into());
+        }
+        self.url_open = false;
+        Ok(())
+    }
+
+    /// Discards the open `url` element. Nothing of it has been written to the
+    /// underlying writer yet.
+    pub(crate) fn discard_url(&mut self) {
+        if self.url_open {
+            self.writer.rollback_entry();
+            self.number_of_urls -= 1;
+            self.url_open = false;
+        }
+    }
+
+    pub(crate) fn is_url_open(&self) -> bool {
+        self.url_open
+    }
+
+    fn write_url(&mut self, url: Url<'_>) -> Result<(), crate::Error> {
+        let loc = match self.prepare_loc(Cow::Borrowed(url.loc.as_ref()))? {
+            Some(loc) => loc,
+            None => return Ok(()),
+        };
+        let lastmod = url
+            .lastmod
+            .as_deref()
+            .map(|lastmod| self.check_lastmod(Cow::Borrowed(lastmod)))
+            .transpose()?;
+        for alternate in url.alternates.iter() {
+            self.check_namespace(alternate)?;
+        }
+        for extension in url.extensions.iter() {
+            self.check_namespace(extension.as_dyn())?;
+            if let Extension::News(news) = extension {
+                self.check_news(news)?;
+            }
+        }
+        if url.number_of_images() > Image::MAX_NUMBER_OF_IMAGES {
+            return Err(Error::MaxNumberOfImages.into());
+        }
+        if url
+            .extensions
+            .iter()
+            .filter(|extension| is_news(extension.as_dyn()))
+            .count()
+            > 1
+        {
+            return Err(Error::MaxNumberOfNews.into());
+        }
+        self.begin_url_inner(loc, url.provenance.as_deref())?;
+        if let Err(e) = self.write_url_children(lastmod, &url) {
+            self.discard_url();
+            return Err(e);
+        }
+        Ok(self.end_url_inner()?)
+    }
+
+    /// Starts a `url` element for `begin_url`. It returns `false` if the
+    /// entry is skipped.
+    fn begin_url_with_loc(&mut self, loc: Cow<'_, str>) -> Result<bool> {
+        let loc = match self.prepare_loc(loc)? {
+            Some(loc) => loc,
+            None => {
+                self.close_open_url()?;
+                return Ok(false);
+            }
+        };
+        self.begin_url_inner(loc, None)?;
+        Ok(true)
+    }
+
+    fn begin_url_inner(&mut self, loc: Cow<'_, str>, provenance: Option<&str>) -> Result<()> {
+        if self.ended {
+            return Err(Error::InvalidElementOrder);
+        }
+        self.close_open_url()?;
+
         if self.number_of_urls + 1 > Self::MAX_NUMBER_OF_URLS {
             return Err(Error::MaxNumberOfUrls);
         }
+        if self.google_news_profile.is_some()
+            && self.number_of_urls + 1 > GoogleNewsProfile::MAX_NUMBER_OF_URLS
+        {
+            return Err(Error::MaxNumberOfNewsUrls);
+        }
         self.number_of_urls += 1;
+        self.url_open = true;
+        self.writer.begin_entry();
 
-        let url: Url<'a> = url.try_into_url()?;
-        self.writer.start_tag(b"url")?;
+        if let Err(e) = self.write_url_head(loc.as_ref(), provenance) {
+            self.discard_url();
+            return Err(e);
+        }
+        Ok(())
+    }
 
-        let content = url.loc;
-        self.writer.element(b"loc", content.as_ref())?;
+    fn write_url_head(&mut self, loc: &str, provenance: Option<&str>) -> Result<()> {
+        if let Some(provenance) = provenance.filter(|_| self.provenance_comments) {
+            self.writer.comment(provenance)?;
+        }
+        self.writer.start_tag(b"url")?;
+        self.writer.loc(loc)?;
+        Ok(())
+    }
 
-        if let Some(content) = url.lastmod {
-            self.writer.element(b"lastmod", content.as_ref())?;
+    fn write_url_children(
+        &mut self,
+        lastmod: Option<Cow<'_, str>>,
+        url: &Url<'_>,
+    ) -> Result<(), crate::Error> {
+        if let Some(content) = lastmod {
+            self.writer
+                .element(b"lastmod", content.as_ref())
+                .map_err(Error::from)?;
         }
 
         if let Some(content) = url.changefreq {
-            self.writer.element(b"changefreq", content.as_ref())?;
+            self.writer
+                .element(b"changefreq", content.as_ref())
+                .map_err(Error::from)?;
+        }
+
+        if let Some(content) = url.priority.as_deref() {
+            self.writer
+                .element(b"priority", content)
+                .map_err(Error::from)?;
         }
 
-        if let Some(content) = url.priority {
-            self.writer.element(b"priority", content.as_ref())?;
+        for alternate in url.alternates.iter() {
+            self.write_extension(alternate)?;
         }
 
-        self.writer.end_tag(b"url")?;
+        for extension in url.extensions.iter() {
+            self.write_extension(extension.as_dyn())?;
+        }
         Ok(())
     }
 
-    /// Writes a closing `</urlset>` tag.
-    pub fn end(&mut self) -> Result<()> {
-        self.writer.end_tag(b"urlset")?;
+    fn prepare_loc<'a>(&self, loc: Cow<'a, str>) -> Result<Option<Cow<'a, str>>> {
+        #[cfg(feature = "unicode-normalization")]
+        let loc = if self.normalize_locs && !unicode_normalization::is_nfc(loc.as_ref()) {
+            Loc::try_from(loc.as_ref())
+                .and_then(|loc| loc.nfc())
+                .map_err(|_| Error::InvalidLoc)?
+                .into_inner()
+        } else {
+            loc
+        };
+        #[cfg(feature = "url")]
+        let loc = if self.canonicalize_locs {
+            Loc::try_from(loc.as_ref())
+                .and_then(|loc| loc.canonicalize())
+                .map_err(|_| Error::InvalidLoc)?
+                .into_inner()
+        } else {
+            loc
+        };
+        let normalized = normalize_loc(loc.as_ref());
+        if self.self_locs.iter().any(|s| *s == normalized) {
+            return Err(Error::SelfReferentialLoc);
+        }
+        if !self.scheme_policy.is_allowed(loc.as_ref()) {
+            return Err(Error::SchemeNotAllowed);
+        }
+        if self.host_policy.is_allowed(loc.as_ref()) {
+            return Ok(Some(loc));
+        }
+        match self.host_policy.action() {
+            HostPolicyAction::Reject => Err(Error::HostNotAllowed),
+            HostPolicyAction::Skip => Ok(None),
+        }
+    }
+
+    fn close_open_url(&mut self) -> Result<()> {
+        if self.url_open {
+            self.end_url_inner()?;
+        }
         Ok(())
     }
+}
 
-    /// Unwraps this `SitemapWrite<W>`, returning the underlying writer.
-    pub fn into_inner(self) -> W {
-        self.writer.into_inner()
+impl<W: SyncAll> SitemapWriter<W> {
+    /// Writes a closing `</urlset>` tag, flushes and syncs the underlying
+    /// writer to the disk, and returns the underlying writer and a summary of
+    /// the written file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::SitemapWriter;
+    /// use std::{fs::File, io::BufWriter};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let path = std::env::temp_dir().join("sitemap-xml-writer-doctest-SitemapWriter-finish-sync.xml");
+    /// let mut writer = SitemapWriter::start(BufWriter::new(File::create(&path)?))?;
+    /// writer.write("http://www.example.com/")?;
+    /// writer.finish_sync()?;
+    /// # std::fs::remove_file(&path)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn finish_sync(self) -> Result<(W, WriteSummary), crate::Error> {
+        let (mut inner, summary) = self.finish()?;
+        inner.sync_all().map_err(Error::from)?;
+        Ok((inner, summary))
     }
+}
 
-    fn start_inner(inner: W, pretty: bool) -> Result<Self> {
-        let mut s = Self {
-            writer: SitemapXmlWriter::new(inner, pretty),
-            number_of_urls: 0_usize,
-        };
-        s.writer.declaration()?;
-        s.writer.start_tag_with_default_ns(b"urlset")?;
-        Ok(s)
+/// Writes the `url` elements in order, like [`SitemapWriter::write`].
+///
+/// Writing stops at the first error. The error is deferred: it is returned by
+/// [`SitemapWriter::deferred_result`] or by the next
+/// [`SitemapWriter::end`] (and [`SitemapWriter::finish`]), and
+/// `extend` does nothing until then.
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{SitemapWriter, Url};
+/// use std::io::Cursor;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let locs = (1..=3)
+///     .map(|i| format!("http://www.example.com/{}", i))
+///     .collect::<Vec<String>>();
+/// let urls = locs
+///     .iter()
+///     .map(|loc| Url::loc(loc.as_str()))
+///     .collect::<Result<Vec<Url>, _>>()?;
+/// let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+/// writer.extend(urls);
+/// let (_, summary) = writer.finish()?;
+/// assert_eq!(summary.number_of_entries(), 3);
+///
+/// let too_long = format!("http://www.example.com/{}", "a".repeat(2048));
+/// let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+/// writer.extend(["http://www.example.com/", too_long.as_str()]);
+/// assert!(writer.finish().is_err());
+/// #     Ok(())
+/// # }
+/// ```
+impl<'a, W: Write, U: SealedTryIntoUrl<'a>> Extend<U> for SitemapWriter<W> {
+    fn extend<T: IntoIterator<Item = U>>(&mut self, iter: T) {
+        if self.deferred_error.is_some() {
+            return;
+        }
+        for url in iter {
+            if let Err(e) = self.write(url) {
+                self.deferred_error = Some(e);
+                return;
+            }
+        }
     }
 }
 
 mod private {
-    use crate::Url;
+    use std::borrow::Cow;
+
+    use crate::{url::Extension, Url};
 
-    use super::Error;
+    use crate::Error;
 
     pub trait SealedTryIntoUrl<'a> {
         fn try_into_url(self) -> Result<Url<'a>, Error>;
@@ -297,6 +1013,28 @@ mod private {
         }
     }
 
+    impl<'a, 'b> SealedTryIntoUrl<'a> for &'a Url<'b> {
+        fn try_into_url(self) -> Result<Url<'a>, Error> {
+            Ok(Url {
+                loc: Cow::Borrowed(self.loc.as_ref()),
+                lastmod: self.lastmod.as_deref().map(Cow::Borrowed),
+                changefreq: self.changefreq,
+                priority: self.priority.as_deref().map(Cow::Borrowed),
+                provenance: self.provenance.as_deref().map(Cow::Borrowed),
+                alternates: self.alternates.clone(),
+                extensions: self
+                    .extensions
+                    .iter()
+                    .map(|extension| match extension {
+                        // keeps the publication date checked
+                        Extension::News(news) => Extension::News(news.clone()),
+                        extension => Extension::Other(Box::new(extension.as_dyn())),
+                    })
+                    .collect(),
+            })
+        }
+    }
+
     impl<'a> SealedTryIntoUrl<'a> for &'a str {
         fn try_into_url(self) -> Result<Url<'a>, Error> {
             Url::loc(self)
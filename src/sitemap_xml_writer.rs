@@ -1,50 +1,257 @@
 use std::{borrow::Cow, io::Write};
 
-#[derive(Debug, thiserror::Error)]
+use crate::escape_audit::{is_escaped, EscapeAudit, EscapeAuditFn};
+
+#[derive(Debug)]
 pub(crate) enum Error {
-    #[error("io")]
-    Io(#[from] std::io::Error),
-    #[error("max byte length")]
+    Io(std::io::Error),
     MaxByteLength,
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Error::Io(_) => "io",
+            Error::MaxByteLength => "max byte length",
+        })
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::MaxByteLength => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Error::Io(value)
+    }
+}
+
 pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
 
 pub(crate) struct SitemapXmlWriter<W: Write> {
     write: W,
     byte_length: usize,
     indent_level: usize,
+    // the bytes reserved for the closing tag of the root element
+    reserved_byte_length: usize,
+    // the pending entry (its bytes and the state to restore on rollback)
+    entry: Option<(usize, usize)>,
+    entry_buffer: Vec<u8>,
     pretty: bool,
+    trailing_newline: bool,
+    loc_prefix: Option<(String, String)>,
+    escape_audit: Option<(EscapeAuditFn, String)>,
 }
 
 impl<W: Write> SitemapXmlWriter<W> {
     const MAX_BYTE_LENGTH: usize = 52_428_800;
 
-    pub(crate) fn new(write: W, pretty: bool) -> Self {
+    pub(crate) fn new(write: W, pretty: bool, trailing_newline: bool) -> Self {
         Self {
             write,
             byte_length: 0,
             indent_level: 0,
+            reserved_byte_length: 0,
+            entry: None,
+            entry_buffer: Vec::new(),
             pretty,
+            trailing_newline,
+            loc_prefix: None,
+            escape_audit: None,
         }
     }
 
+    pub(crate) fn set_escape_audit(&mut self, escape_audit: Option<EscapeAuditFn>) {
+        self.escape_audit = escape_audit.map(|f| (f, String::new()));
+    }
+
+    pub(crate) fn set_loc_prefix(&mut self, prefix: Option<String>) {
+        self.loc_prefix = prefix.map(|prefix| {
+            let escaped = entity_escape(&prefix).into_owned();
+            (prefix, escaped)
+        });
+    }
+
+    pub(crate) fn byte_length(&self) -> usize {
+        self.byte_length
+    }
+
+    pub(crate) fn get_ref(&self) -> &W {
+        &self.write
+    }
+
+    pub(crate) fn get_mut(&mut self) -> &mut W {
+        &mut self.write
+    }
+
     pub(crate) fn into_inner(self) -> W {
         self.write
     }
 
+    pub(crate) fn reset(&mut self, write: W) -> W {
+        self.byte_length = 0;
+        self.indent_level = 0;
+        self.reserved_byte_length = 0;
+        self.entry = None;
+        self.entry_buffer.clear();
+        std::mem::replace(&mut self.write, write)
+    }
+
+    /// Starts an entry. Its bytes are buffered until `commit_entry`, so that
+    /// a failed entry leaves no partial element in the output.
+    pub(crate) fn begin_entry(&mut self) {
+        self.rollback_entry();
+        self.entry = Some((self.byte_length, self.indent_level));
+    }
+
+    /// Writes the buffered entry to the underlying writer. If it fails, the
+    /// entry is discarded.
+    pub(crate) fn commit_entry(&mut self) -> Result<()> {
+        if self.entry.is_none() {
+            return Ok(());
+        }
+        match self.write.write_all(&self.entry_buffer) {
+            Ok(()) => {
+                self.entry = None;
+                self.entry_buffer.clear();
+                Ok(())
+            }
+            Err(e) => {
+                self.rollback_entry();
+                Err(Error::Io(e))
+            }
+        }
+    }
+
+    /// Discards the buffered entry.
+    pub(crate) fn rollback_entry(&mut self) {
+        if let Some((byte_length, indent_level)) = self.entry.take() {
+            self.byte_length = byte_length;
+            self.indent_level = indent_level;
+            self.entry_buffer.clear();
+        }
+    }
+
+    pub(crate) fn comment(&mut self, text: &str) -> Result<()> {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            if c == '-' && escaped.ends_with('-') {
+                escaped.push(' ');
+            }
+            escaped.push(c);
+        }
+        self.indent()?;
+        self.write(b"<!-- ")?;
+        self.write(escaped.as_bytes())?;
+        self.write(b" -->")?;
+        Ok(())
+    }
+
     pub(crate) fn declaration(&mut self) -> Result<()> {
         self.write(br#"<?xml version="1.0" encoding="UTF-8"?>"#)
     }
 
     pub(crate) fn element(&mut self, name: &[u8], content: &str) -> Result<()> {
+        self.audit(name, content);
+        self.element_without_audit(name, content)
+    }
+
+    pub(crate) fn element_with_attributes(
+        &mut self,
+        name: &[u8],
+        attributes: &[(&str, &str)],
+        content: &str,
+    ) -> Result<()> {
+        self.audit(name, content);
+        self.indent()?;
+        self.write(b"<")?;
+        self.write(name)?;
+        for (attribute_name, value) in attributes {
+            self.write(b" ")?;
+            self.write(attribute_name.as_bytes())?;
+            self.write(b"=\"")?;
+            self.write(entity_escape(value).as_bytes())?;
+            self.write(b"\"")?;
+        }
+        self.write(b">")?;
+        self.write(entity_escape(content).as_bytes())?;
+        self.end_tag_without_indent(name)?;
+        Ok(())
+    }
+
+    pub(crate) fn empty_element(&mut self, name: &[u8], attributes: &[(&str, &str)]) -> Result<()> {
+        self.indent()?;
+        self.write(b"<")?;
+        self.write(name)?;
+        for (attribute_name, value) in attributes {
+            self.write(b" ")?;
+            self.write(attribute_name.as_bytes())?;
+            self.write(b"=\"")?;
+            self.write(entity_escape(value).as_bytes())?;
+            self.write(b"\"")?;
+        }
+        self.write(b"/>")?;
+        Ok(())
+    }
+
+    fn element_without_audit(&mut self, name: &[u8], content: &str) -> Result<()> {
+        self.indent()?;
+        self.start_tag_without_indent(name)?;
+        self.write(entity_escape(content).as_bytes())?;
+        self.end_tag_without_indent(name)?;
+        Ok(())
+    }
+
+    pub(crate) fn loc(&mut self, content: &str) -> Result<()> {
+        if let Some((_, loc)) = self.escape_audit.as_mut() {
+            loc.clear();
+            loc.push_str(content);
+        }
+        self.audit(b"loc", content);
+        let loc_prefix = self.loc_prefix.take();
+        let result = match loc_prefix.as_ref().and_then(|(prefix, escaped)| {
+            content
+                .strip_prefix(prefix.as_str())
+                .map(|suffix| (escaped, suffix))
+        }) {
+            Some((escaped_prefix, suffix)) => {
+                self.element_with_escaped_prefix(b"loc", escaped_prefix, suffix)
+            }
+            None => self.element_without_audit(b"loc", content),
+        };
+        self.loc_prefix = loc_prefix;
+        result
+    }
+
+    fn element_with_escaped_prefix(
+        &mut self,
+        name: &[u8],
+        escaped_prefix: &str,
+        content: &str,
+    ) -> Result<()> {
         self.indent()?;
         self.start_tag_without_indent(name)?;
+        self.write(escaped_prefix.as_bytes())?;
         self.write(entity_escape(content).as_bytes())?;
         self.end_tag_without_indent(name)?;
         Ok(())
     }
 
+    pub(crate) fn end_document(&mut self, name: &[u8]) -> Result<()> {
+        self.reserved_byte_length = 0;
+        self.end_tag(name)?;
+        if self.trailing_newline {
+            self.write(b"\n")?;
+        }
+        Ok(())
+    }
+
     pub(crate) fn end_tag(&mut self, name: &[u8]) -> Result<()> {
         self.indent_level -= 1;
         self.indent()?;
@@ -59,15 +266,41 @@ impl<W: Write> SitemapXmlWriter<W> {
         Ok(())
     }
 
-    pub(crate) fn start_tag_with_default_ns(&mut self, name: &[u8]) -> Result<()> {
+    pub(crate) fn start_tag_with_default_ns(
+        &mut self,
+        name: &[u8],
+        namespaces: &[(String, String)],
+    ) -> Result<()> {
         self.indent()?;
         self.write(b"<")?;
         self.write(name)?;
-        self.write(br#" xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#)?;
+        self.write(br#" xmlns="http://www.sitemaps.org/schemas/sitemap/0.9""#)?;
+        for (prefix, uri) in namespaces {
+            self.write(b" xmlns:")?;
+            self.write(prefix.as_bytes())?;
+            self.write(b"=\"")?;
+            self.write(entity_escape(uri).as_bytes())?;
+            self.write(b"\"")?;
+        }
+        self.write(b">")?;
         self.indent_level += 1;
+        // `end_document` always has room for the closing tag
+        self.reserved_byte_length = usize::from(self.pretty)
+            + b"</>".len()
+            + name.len()
+            + usize::from(self.trailing_newline);
         Ok(())
     }
 
+    fn audit(&self, name: &[u8], content: &str) {
+        if let Some((f, loc)) = self.escape_audit.as_ref() {
+            if content.chars().any(is_escaped) {
+                let name = std::str::from_utf8(name).expect("valid UTF-8");
+                f.call(&EscapeAudit::new(loc, name, content));
+            }
+        }
+    }
+
     fn end_tag_without_indent(&mut self, name: &[u8]) -> Result<()> {
         self.write(b"</")?;
         self.write(name)?;
@@ -94,17 +327,21 @@ impl<W: Write> SitemapXmlWriter<W> {
 
     fn write(&mut self, buf: &[u8]) -> Result<()> {
         let l = buf.len();
-        if self.byte_length + l > Self::MAX_BYTE_LENGTH {
+        if self.byte_length + l + self.reserved_byte_length > Self::MAX_BYTE_LENGTH {
             return Err(Error::MaxByteLength);
         }
         self.byte_length += l;
 
-        self.write.write_all(buf)?;
+        if self.entry.is_some() {
+            self.entry_buffer.extend_from_slice(buf);
+        } else {
+            self.write.write_all(buf)?;
+        }
         Ok(())
     }
 }
 
-fn entity_escape(s: &str) -> Cow<str> {
+pub(crate) fn entity_escape(s: &str) -> Cow<'_, str> {
     let predicate = |b: &u8| -> bool { matches!(b, b'"' | b'&' | b'\'' | b'<' | b'>') };
     let escape = |b: u8| -> &'static [u8] {
         match b {
@@ -141,6 +378,17 @@ fn entity_escape(s: &str) -> Cow<str> {
     }
 }
 
+/// Returns `true` if `s` is not blank and contains only the characters
+/// allowed in XML 1.0 documents.
+pub(crate) fn is_text(s: &str) -> bool {
+    !s.trim().is_empty() && s.chars().all(is_xml_char)
+}
+
+/// Returns `true` if `c` is allowed in XML 1.0 documents.
+fn is_xml_char(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -154,4 +402,84 @@ mod tests {
             "&lt;h1 class=&quot;title&quot;&gt;"
         );
     }
+
+    #[test]
+    fn test_comment() -> anyhow::Result<()> {
+        let mut writer = SitemapXmlWriter::new(Vec::new(), false, false);
+        writer.comment("a")?;
+        writer.comment("a--b---c-")?;
+        assert_eq!(
+            String::from_utf8(writer.into_inner())?,
+            "<!-- a --><!-- a- -b- - -c- -->"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_element_with_attributes() -> anyhow::Result<()> {
+        let mut writer = SitemapXmlWriter::new(Vec::new(), false, false);
+        writer.element_with_attributes(b"a", &[], "1")?;
+        writer.element_with_attributes(b"b", &[("c", "\"&"), ("d", "e")], "<2>")?;
+        assert_eq!(writer.byte_length(), writer.get_ref().len());
+        assert_eq!(
+            String::from_utf8(writer.into_inner())?,
+            r#"<a>1</a><b c="&quot;&amp;" d="e">&lt;2&gt;</b>"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_loc_prefix() -> anyhow::Result<()> {
+        let mut writer = SitemapXmlWriter::new(Vec::new(), false, false);
+        writer.set_loc_prefix(Some("https://example.com/?a=1&b=".to_string()));
+        writer.loc("https://example.com/?a=1&b=<2>")?;
+        writer.loc("https://example.com/c?d&e")?;
+        assert_eq!(writer.byte_length(), writer.get_ref().len());
+        assert_eq!(
+            String::from_utf8(writer.into_inner())?,
+            concat!(
+                "<loc>https://example.com/?a=1&amp;b=&lt;2&gt;</loc>",
+                "<loc>https://example.com/c?d&amp;e</loc>"
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_escape_audit() -> anyhow::Result<()> {
+        use std::sync::{Arc, Mutex};
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let mut writer = SitemapXmlWriter::new(Vec::new(), false, false);
+        writer.set_loc_prefix(Some("https://example.com/?a=1&b=".to_string()));
+        writer.set_escape_audit(Some(EscapeAuditFn::new({
+            let reports = Arc::clone(&reports);
+            move |audit| {
+                reports.lock().unwrap().push((
+                    audit.loc().to_string(),
+                    audit.element().to_string(),
+                    audit.escaped().collect::<String>(),
+                ))
+            }
+        })));
+        writer.loc("https://example.com/?a=1&b=<2>")?;
+        writer.element(b"lastmod", "2005-01-01")?;
+        writer.loc("https://example.com/")?;
+        writer.element(b"x", "'")?;
+        assert_eq!(
+            *reports.lock().unwrap(),
+            [
+                (
+                    "https://example.com/?a=1&b=<2>".to_string(),
+                    "loc".to_string(),
+                    "&<>".to_string()
+                ),
+                (
+                    "https://example.com/".to_string(),
+                    "x".to_string(),
+                    "'".to_string()
+                )
+            ]
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,33 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+/// A writer whose written data can be synced to a durable storage.
+///
+/// It is used by [`crate::SitemapWriter::finish_sync`] and
+/// [`crate::SitemapIndexWriter::finish_sync`].
+pub trait SyncAll: Write {
+    /// Flushes the buffered data and syncs all written data to the disk.
+    fn sync_all(&mut self) -> std::io::Result<()>;
+}
+
+impl SyncAll for File {
+    fn sync_all(&mut self) -> std::io::Result<()> {
+        self.flush()?;
+        File::sync_all(self)
+    }
+}
+
+impl<W: SyncAll> SyncAll for BufWriter<W> {
+    fn sync_all(&mut self) -> std::io::Result<()> {
+        self.flush()?;
+        self.get_mut().sync_all()
+    }
+}
+
+impl<W: SyncAll + ?Sized> SyncAll for &mut W {
+    fn sync_all(&mut self) -> std::io::Result<()> {
+        (**self).sync_all()
+    }
+}
@@ -1,13 +1,31 @@
 use std::borrow::Cow;
 
 use crate::{
-    changefreq::Changefreq, lastmod::Lastmod, loc::Loc, priority::Priority, sitemap_writer::Error,
+    alternate::Alternate,
+    changefreq::Changefreq,
+    image::{is_image, Image},
+    lastmod::Lastmod,
+    loc::Loc,
+    news::{is_news, News},
+    priority::Priority,
+    sitemap_writer::Error,
+    url_extension::UrlExtension,
+    video::Video,
 };
 
-type Result<T, E = Error> = std::result::Result<T, E>;
+type Result<T, E = crate::Error> = std::result::Result<T, E>;
 
 /// A builder for `url` entry.
 ///
+/// With the `serde` feature, it is serialized and deserialized with the
+/// `loc`, `lastmod`, `changefreq`, `priority`, provenance, alternates and
+/// the images, videos and news (e.g. for staging entries as JSON). The
+/// images, videos and news are a list of `extensions` tagged with their
+/// `type` (`"image"`, `"video"` or `"news"`) in the order they are added.
+/// The extensions added by [`Url::extension`] cannot be serialized:
+/// serializing an entry with them returns an error instead of dropping
+/// them.
+///
 /// # Examples
 ///
 /// ```rust
@@ -50,10 +68,33 @@ pub struct Url<'a> {
     pub(crate) lastmod: Option<Cow<'a, str>>,
     pub(crate) changefreq: Option<Changefreq>,
     pub(crate) priority: Option<Cow<'a, str>>,
+    pub(crate) provenance: Option<Cow<'a, str>>,
+    pub(crate) alternates: Vec<Alternate<'a>>,
+    pub(crate) extensions: Vec<Extension<'a>>,
+}
+
+/// An extension of a `url` entry. The built-in extensions keep their type
+/// so that they can be serialized.
+pub(crate) enum Extension<'a> {
+    Image(Image<'a>),
+    News(News<'a>),
+    Video(Box<Video<'a>>),
+    Other(Box<dyn UrlExtension + 'a>),
+}
+
+impl<'a> Extension<'a> {
+    pub(crate) fn as_dyn(&self) -> &(dyn UrlExtension + 'a) {
+        match self {
+            Extension::Image(image) => image,
+            Extension::News(news) => news,
+            Extension::Video(video) => video.as_ref(),
+            Extension::Other(extension) => extension.as_ref(),
+        }
+    }
 }
 
 impl<'a> TryFrom<&'a str> for Url<'a> {
-    type Error = Error;
+    type Error = crate::Error;
 
     fn try_from(value: &'a str) -> Result<Self, Self::Error> {
         Self::loc(value)
@@ -86,13 +127,29 @@ impl<'a> Url<'a> {
     where
         S: TryInto<Loc<'a>>,
     {
-        let loc = loc.try_into().map_err(|_| Error::InvalidLoc)?.into_inner();
-        Ok(Self {
-            loc,
-            lastmod: None,
-            changefreq: None,
-            priority: None,
-        })
+        let loc = loc.try_into().map_err(|_| Error::InvalidLoc)?;
+        Ok(Self::new(loc))
+    }
+
+    /// Builds a `url` entry with the specified URL as the content of the
+    /// `loc` child entry without validation.
+    ///
+    /// It is intended for pre-validated inputs in hot loops. See
+    /// [`Loc::new_unchecked`]. The writer-level checks (e.g.
+    /// [`crate::HostPolicy`]) still apply.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sitemap_xml_writer::{Lastmod, Url};
+    /// let lastmod = Lastmod::new_unchecked("2005-01-01");
+    /// let url = Url::loc_unchecked("http://www.example.com/").with_lastmod(lastmod);
+    /// ```
+    pub fn loc_unchecked<S>(loc: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        Self::new(Loc::new_unchecked(loc))
     }
 
     /// Changes the `changefreq` child entry to the specified value.
@@ -114,8 +171,7 @@ impl<'a> Url<'a> {
     where
         S: TryInto<Changefreq>,
     {
-        let changefreq = s.try_into().map_err(|_| Error::InvalidChangefreq)?;
-        self.changefreq = Some(changefreq);
+        self.set_changefreq(s)?;
         Ok(self)
     }
 
@@ -145,14 +201,155 @@ impl<'a> Url<'a> {
     where
         S: TryInto<Lastmod<'a>>,
     {
-        let lastmod = s
-            .try_into()
-            .map_err(|_| Error::InvalidLastmod)?
-            .into_inner();
-        self.lastmod = Some(lastmod);
+        self.set_lastmod(s)?;
         Ok(self)
     }
 
+    /// Adds an `xhtml:link` entry (see [`Alternate`]) pointing to a localized
+    /// version of the page. The alternates are written after the `priority`
+    /// child entry and before the other extensions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sitemap_xml_writer::{Alternate, Url};
+    /// # fn main() -> anyhow::Result<()> {
+    /// Url::loc("http://www.example.com/en/")?
+    ///     .alternate(Alternate::new("en", "http://www.example.com/en/")?)
+    ///     .alternate(Alternate::new("de", "http://www.example.com/de/")?);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn alternate(mut self, alternate: Alternate<'a>) -> Self {
+        self.alternates.push(alternate);
+        self
+    }
+
+    /// Adds an extension (see [`UrlExtension`]). The elements of the
+    /// extensions are written after the `priority` child entry in the order
+    /// they are added.
+    ///
+    /// An extension in the image sitemap namespace counts as one
+    /// `image:image` entry. If the entry has more than
+    /// [`Image::MAX_NUMBER_OF_IMAGES`] images, [`SitemapWriter::write`]
+    /// returns an error.
+    ///
+    /// With the `serde` feature, an entry with an extension added by this
+    /// method cannot be serialized. Use [`Url::image`], [`Url::news`] and
+    /// [`Url::video`] for the built-in extensions.
+    ///
+    /// [`SitemapWriter::write`]: crate::SitemapWriter::write
+    pub fn extension<E>(mut self, extension: E) -> Self
+    where
+        E: UrlExtension + 'a,
+    {
+        self.extensions.push(Extension::Other(Box::new(extension)));
+        self
+    }
+
+    /// Adds an `image:image` entry (see [`Image`]). It is written as an
+    /// extension in the order it is added.
+    ///
+    /// It returns an error if the entry already has
+    /// [`Image::MAX_NUMBER_OF_IMAGES`] images.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sitemap_xml_writer::{Image, Url};
+    /// # fn main() -> anyhow::Result<()> {
+    /// Url::loc("http://www.example.com/")?
+    ///     .image(Image::loc("http://www.example.com/image.jpg")?)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn image(mut self, image: Image<'a>) -> Result<Self> {
+        if self.number_of_images() >= Image::MAX_NUMBER_OF_IMAGES {
+            return Err(Error::MaxNumberOfImages.into());
+        }
+        self.extensions.push(Extension::Image(image));
+        Ok(self)
+    }
+
+    /// Adds a `news:news` entry (see [`News`]). It is written as an
+    /// extension in the order it is added.
+    ///
+    /// It returns an error if the entry already has a `news:news` entry.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sitemap_xml_writer::{News, Url};
+    /// # fn main() -> anyhow::Result<()> {
+    /// Url::loc("http://www.example.com/article.html")?
+    ///     .news(News::new("The Example Times", "en", "2008-12-23", "Title")?)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn news(mut self, news: News<'a>) -> Result<Self> {
+        if self
+            .extensions
+            .iter()
+            .any(|extension| is_news(extension.as_dyn()))
+        {
+            return Err(Error::MaxNumberOfNews.into());
+        }
+        self.extensions.push(Extension::News(news));
+        Ok(self)
+    }
+
+    /// Adds a `video:video` entry (see [`Video`]). It is written as an
+    /// extension in the order it is added. Call it more than once for a page
+    /// hosting multiple videos.
+    ///
+    /// Each video is validated independently. It returns an error if the
+    /// video has neither `video:content_loc` nor `video:player_loc`, or if
+    /// its `video:expiration_date` is before its `video:publication_date`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sitemap_xml_writer::{Url, Video};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let video = Video::new("http://www.example.com/1.jpg", "Title", "Description")?;
+    /// assert!(Url::loc("http://www.example.com/")?
+    ///     .video(video.clone())
+    ///     .is_err());
+    /// Url::loc("http://www.example.com/")?
+    ///     .video(video.player_loc("http://www.example.com/player?v=1")?)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn video(mut self, video: Video<'a>) -> Result<Self> {
+        video.validate()?;
+        self.extensions.push(Extension::Video(Box::new(video)));
+        Ok(self)
+    }
+
+    /// Changes the provenance of this entry (e.g. the pipeline or the source
+    /// line that produced it).
+    ///
+    /// It is written as an XML comment before the `url` element only if
+    /// [`crate::SitemapWriterBuilder::provenance_comments`] is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sitemap_xml_writer::Url;
+    /// # fn main() -> anyhow::Result<()> {
+    /// Url::loc("http://www.example.com/")?
+    ///     .provenance("products.csv:42");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn provenance<S>(mut self, s: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.provenance = Some(s.into());
+        self
+    }
+
     /// Changes the `priority` child entry to the specified value.
     ///
     /// # Examples
@@ -169,6 +366,148 @@ impl<'a> Url<'a> {
     /// # }
     /// ```
     pub fn priority<S>(mut self, s: S) -> Result<Self>
+    where
+        S: TryInto<Priority<'a>>,
+    {
+        self.set_priority(s)?;
+        Ok(self)
+    }
+
+    /// Builds a `url` entry with the specified validated `Loc`.
+    ///
+    /// Unlike [`Url::loc`], it does not return `Result`. The `with_*` methods
+    /// set the child entries from validated values in the same way.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sitemap_xml_writer::{Changefreq, Lastmod, Loc, Priority, Url};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let loc = Loc::try_from("http://www.example.com/")?;
+    /// let lastmod = Lastmod::try_from("2005-01-01")?;
+    /// let priority = Priority::try_from(0.8)?;
+    /// Url::new(loc)
+    ///     .with_lastmod(lastmod)
+    ///     .with_changefreq(Changefreq::Monthly)
+    ///     .with_priority(priority);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn new(loc: Loc<'a>) -> Self {
+        Self {
+            loc: loc.into_inner(),
+            lastmod: None,
+            changefreq: None,
+            priority: None,
+            provenance: None,
+            alternates: Vec::new(),
+            extensions: Vec::new(),
+        }
+    }
+
+    /// Changes the `loc` child entry to the specified validated `Loc`.
+    pub fn with_loc(mut self, loc: Loc<'a>) -> Self {
+        self.loc = loc.into_inner();
+        self
+    }
+
+    /// Changes the `changefreq` child entry to the specified value.
+    pub fn with_changefreq(mut self, changefreq: Changefreq) -> Self {
+        self.changefreq = Some(changefreq);
+        self
+    }
+
+    /// Changes the `lastmod` child entry to the specified validated `Lastmod`.
+    pub fn with_lastmod(mut self, lastmod: Lastmod<'a>) -> Self {
+        self.lastmod = Some(lastmod.into_inner());
+        self
+    }
+
+    /// Changes the `priority` child entry to the specified validated
+    /// `Priority`.
+    pub fn with_priority(mut self, priority: Priority<'a>) -> Self {
+        self.priority = Some(priority.into_inner());
+        self
+    }
+
+    /// Changes the `loc` child entry to the specified URL.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sitemap_xml_writer::Url;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut url = Url::loc("http://www.example.com/")?;
+    /// url.set_loc("http://www.example.com/about")?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn set_loc<S>(&mut self, loc: S) -> Result<&mut Self>
+    where
+        S: TryInto<Loc<'a>>,
+    {
+        self.loc = loc.try_into().map_err(|_| Error::InvalidLoc)?.into_inner();
+        Ok(self)
+    }
+
+    /// Changes the `changefreq` child entry to the specified value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sitemap_xml_writer::Url;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut url = Url::loc("http://www.example.com/")?;
+    /// url.set_changefreq("monthly")?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn set_changefreq<S>(&mut self, s: S) -> Result<&mut Self>
+    where
+        S: TryInto<Changefreq>,
+    {
+        let changefreq = s.try_into().map_err(|_| Error::InvalidChangefreq)?;
+        self.changefreq = Some(changefreq);
+        Ok(self)
+    }
+
+    /// Changes the `lastmod` child entry to the specified date or datetime.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sitemap_xml_writer::Url;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut url = Url::loc("http://www.example.com/")?;
+    /// url.set_lastmod("2005-01-01")?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn set_lastmod<S>(&mut self, s: S) -> Result<&mut Self>
+    where
+        S: TryInto<Lastmod<'a>>,
+    {
+        let lastmod = s
+            .try_into()
+            .map_err(|_| Error::InvalidLastmod)?
+            .into_inner();
+        self.lastmod = Some(lastmod);
+        Ok(self)
+    }
+
+    /// Changes the `priority` child entry to the specified value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sitemap_xml_writer::Url;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut url = Url::loc("http://www.example.com/")?;
+    /// url.set_priority(0.8)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn set_priority<S>(&mut self, s: S) -> Result<&mut Self>
     where
         S: TryInto<Priority<'a>>,
     {
@@ -179,4 +518,338 @@ impl<'a> Url<'a> {
         self.priority = Some(priority);
         Ok(self)
     }
+
+    /// Removes the `changefreq` child entry.
+    pub fn clear_changefreq(&mut self) -> &mut Self {
+        self.changefreq = None;
+        self
+    }
+
+    /// Removes the `lastmod` child entry.
+    pub fn clear_lastmod(&mut self) -> &mut Self {
+        self.lastmod = None;
+        self
+    }
+
+    /// Removes the `priority` child entry.
+    pub fn clear_priority(&mut self) -> &mut Self {
+        self.priority = None;
+        self
+    }
+
+    /// Removes the extensions (including the alternates and the images).
+    pub fn clear_extensions(&mut self) -> &mut Self {
+        self.alternates.clear();
+        self.extensions.clear();
+        self
+    }
+
+    /// Removes the provenance of this entry.
+    pub fn clear_provenance(&mut self) -> &mut Self {
+        self.provenance = None;
+        self
+    }
+
+    /// Returns the number of extensions in the image sitemap namespace.
+    pub(crate) fn number_of_images(&self) -> usize {
+        self.extensions
+            .iter()
+            .filter(|extension| is_image(extension.as_dyn()))
+            .count()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for Url<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::{Error, SerializeStruct};
+
+        #[derive(serde::Serialize)]
+        #[serde(tag = "type", rename_all = "lowercase")]
+        enum ExtensionRepr<'r, 'b> {
+            Image(&'r Image<'b>),
+            News(&'r News<'b>),
+            Video(&'r Video<'b>),
+        }
+
+        let extensions = self
+            .extensions
+            .iter()
+            .map(|extension| match extension {
+                Extension::Image(image) => Ok(ExtensionRepr::Image(image)),
+                Extension::News(news) => Ok(ExtensionRepr::News(news)),
+                Extension::Video(video) => Ok(ExtensionRepr::Video(video)),
+                Extension::Other(_) => Err(S::Error::custom(
+                    "extensions added by `Url::extension` cannot be serialized",
+                )),
+            })
+            .collect::<Result<Vec<ExtensionRepr>, S::Error>>()?;
+        let mut s = serializer.serialize_struct("Url", 7)?;
+        s.serialize_field("loc", &self.loc)?;
+        match &self.lastmod {
+            Some(lastmod) => s.serialize_field("lastmod", lastmod)?,
+            None => s.skip_field("lastmod")?,
+        }
+        match &self.changefreq {
+            Some(changefreq) => s.serialize_field("changefreq", changefreq)?,
+            None => s.skip_field("changefreq")?,
+        }
+        match &self.priority {
+            Some(priority) => s.serialize_field("priority", priority)?,
+            None => s.skip_field("priority")?,
+        }
+        match &self.provenance {
+            Some(provenance) => s.serialize_field("provenance", provenance)?,
+            None => s.skip_field("provenance")?,
+        }
+        if self.alternates.is_empty() {
+            s.skip_field("alternates")?;
+        } else {
+            s.serialize_field("alternates", &self.alternates)?;
+        }
+        if extensions.is_empty() {
+            s.skip_field("extensions")?;
+        } else {
+            s.serialize_field("extensions", &extensions)?;
+        }
+        s.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for Url<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Url")]
+        struct Repr<'b> {
+            loc: Loc<'b>,
+            lastmod: Option<Lastmod<'b>>,
+            changefreq: Option<Changefreq>,
+            priority: Option<Priority<'b>>,
+            provenance: Option<String>,
+            #[serde(default)]
+            alternates: Vec<Alternate<'static>>,
+            #[serde(default)]
+            extensions: Vec<ExtensionRepr>,
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(tag = "type", rename_all = "lowercase")]
+        enum ExtensionRepr {
+            Image(Image<'static>),
+            News(News<'static>),
+            Video(Box<Video<'static>>),
+        }
+
+        let repr = Repr::deserialize(deserializer)?;
+        let mut url = Self {
+            loc: repr.loc.into_inner(),
+            lastmod: repr.lastmod.map(Lastmod::into_inner),
+            changefreq: repr.changefreq,
+            priority: repr.priority.map(Priority::into_inner),
+            provenance: repr.provenance.map(Cow::Owned),
+            alternates: repr.alternates,
+            extensions: Vec::new(),
+        };
+        for extension in repr.extensions {
+            url = match extension {
+                ExtensionRepr::Image(image) => url.image(image),
+                ExtensionRepr::News(news) => url.news(news),
+                ExtensionRepr::Video(video) => url.video(*video),
+            }
+            .map_err(serde::de::Error::custom)?;
+        }
+        Ok(url)
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::{VideoPlatform, VideoPrice, VideoPriceType, VideoRelationship, VideoResolution};
+
+    #[test]
+    fn test_serde() -> anyhow::Result<()> {
+        let url = Url::loc("http://www.example.com/")?
+            .lastmod("2005-01-01")?
+            .changefreq("monthly")?
+            .priority("0.8")?;
+        let json = serde_json::to_string(&url)?;
+        assert_eq!(
+            json,
+            r#"{"loc":"http://www.example.com/","lastmod":"2005-01-01","changefreq":"monthly","priority":"0.8"}"#
+        );
+        let url = serde_json::from_str::<Url>(&json)?;
+        assert_eq!(serde_json::to_string(&url)?, json);
+
+        let json = r#"{"loc":"http://www.example.com/"}"#;
+        assert_eq!(
+            serde_json::to_string(&serde_json::from_str::<Url>(json)?)?,
+            json
+        );
+
+        assert!(serde_json::from_str::<Url>(r#"{"lastmod":"2005-01-01"}"#).is_err());
+        let json = r#"{"loc":"http://www.example.com/","lastmod":"2005-13-01"}"#;
+        assert!(serde_json::from_str::<Url>(json).is_err());
+        let json = r#"{"loc":"http://www.example.com/","changefreq":"sometimes"}"#;
+        assert!(serde_json::from_str::<Url>(json).is_err());
+        let json = r#"{"loc":"http://www.example.com/","priority":"1.1"}"#;
+        assert!(serde_json::from_str::<Url>(json).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_serde_round_trip_all_fields() -> anyhow::Result<()> {
+        let url = Url::loc("http://www.example.com/en/")?
+            .lastmod("2004-12-23T18:00:15+00:00")?
+            .changefreq("weekly")?
+            .priority("0.5")?
+            .provenance("pages.csv:1")
+            .alternate(Alternate::new("en", "http://www.example.com/en/")?)
+            .alternate(Alternate::new("x-default", "http://www.example.com/")?)
+            .image(
+                Image::loc("http://www.example.com/en/a.jpg")?
+                    .caption("Caption")?
+                    .geo_location("Limerick, Ireland")?
+                    .title("Title")?
+                    .license("http://www.example.com/license")?,
+            )?
+            .video(
+                Video::new("http://www.example.com/1.jpg", "Title", "Description")?
+                    .content_loc("http://www.example.com/1.mp4")?
+                    .player_loc("http://www.example.com/player?v=1")?
+                    .duration(600)?
+                    .expiration_date("2009-11-05T19:20:30+08:00")?
+                    .rating(4.2)?
+                    .view_count(12345)
+                    .publication_date("2007-11-05T19:20:30+08:00")?
+                    .tag("steak")?
+                    .tag("grilling")?
+                    .family_friendly(true)
+                    .restriction(VideoRelationship::Allow, ["IE", "GB"])?
+                    .price(
+                        VideoPrice::new(1.99, "EUR")?
+                            .price_type(VideoPriceType::Rent)
+                            .resolution(VideoResolution::Hd),
+                    )
+                    .price(VideoPrice::new(6.0, "USD")?)
+                    .requires_subscription(false)
+                    .uploader_with_info("GrillyMcGrillerson", "http://www.example.com/users/1")?
+                    .platform(
+                        VideoRelationship::Deny,
+                        [VideoPlatform::Mobile, VideoPlatform::Tv],
+                    )?
+                    .live(false),
+            )?
+            .news(
+                News::new("The Example Times", "EN", "2008-12-23", "Title")?
+                    .keywords(["business", "merger"])?
+                    .stock_tickers(["NASDAQ:A", "NASDAQ:B"])?,
+            )?
+            .image(Image::loc("http://www.example.com/en/b.jpg")?)?;
+        let json = serde_json::to_string(&url)?;
+        let deserialized = serde_json::from_str::<Url>(&json)?;
+        assert_eq!(serde_json::to_string(&deserialized)?, json);
+
+        // Destructuring every field makes a new field fail to compile until
+        // it is covered by the serde impl and this test.
+        let Url {
+            loc,
+            lastmod,
+            changefreq,
+            priority,
+            provenance,
+            alternates,
+            extensions,
+        } = deserialized;
+        assert_eq!(loc, url.loc);
+        assert_eq!(lastmod, url.lastmod);
+        assert_eq!(changefreq, url.changefreq);
+        assert_eq!(priority, url.priority);
+        assert_eq!(provenance, url.provenance);
+        assert_eq!(alternates, url.alternates);
+        assert_eq!(extensions.len(), 4);
+        for (actual, expected) in extensions.iter().zip(url.extensions.iter()) {
+            match (actual, expected) {
+                (Extension::Image(a), Extension::Image(e)) => assert_eq!(a, e),
+                (Extension::News(a), Extension::News(e)) => assert_eq!(a, e),
+                (Extension::Video(a), Extension::Video(e)) => assert_eq!(a, e),
+                _ => panic!("extension type mismatch"),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_serde_extensions() -> anyhow::Result<()> {
+        let url = Url::loc("http://www.example.com/en/")?
+            .provenance("pages.csv:1")
+            .alternate(Alternate::new("en", "http://www.example.com/en/")?)
+            .alternate(Alternate::new("de", "http://www.example.com/de/")?);
+        let json = serde_json::to_string(&url)?;
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"loc":"http://www.example.com/en/","provenance":"pages.csv:1","#,
+                r#""alternates":[{"hreflang":"en","href":"http://www.example.com/en/"},"#,
+                r#"{"hreflang":"de","href":"http://www.example.com/de/"}]}"#
+            )
+        );
+        let deserialized = serde_json::from_str::<Url>(&json)?;
+        assert_eq!(deserialized.provenance, url.provenance);
+        assert_eq!(deserialized.alternates, url.alternates);
+        assert_eq!(serde_json::to_string(&deserialized)?, json);
+
+        let json = r#"{"loc":"http://www.example.com/","alternates":[{"hreflang":"en_US","href":"http://www.example.com/"}]}"#;
+        assert!(serde_json::from_str::<Url>(json).is_err());
+
+        let url = Url::loc("http://www.example.com/")?
+            .image(Image::loc("http://www.example.com/image.jpg")?)?
+            .video(
+                Video::new("http://www.example.com/1.jpg", "Title", "Description")?
+                    .player_loc("http://www.example.com/player?v=1")?
+                    .restriction(VideoRelationship::Allow, ["us"])?,
+            )?
+            .news(News::new("The Example Times", "en", "2008-12-23", "Title")?)?;
+        let json = serde_json::to_string(&url)?;
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"loc":"http://www.example.com/","extensions":["#,
+                r#"{"type":"image","loc":"http://www.example.com/image.jpg"},"#,
+                r#"{"type":"video","thumbnail_loc":"http://www.example.com/1.jpg","#,
+                r#""title":"Title","description":"Description","#,
+                r#""player_loc":"http://www.example.com/player?v=1","#,
+                r#""restriction":{"relationship":"allow","countries":["US"]}},"#,
+                r#"{"type":"news","name":"The Example Times","language":"en","#,
+                r#""publication_date":"2008-12-23","title":"Title"}]}"#
+            )
+        );
+        assert_eq!(
+            serde_json::to_string(&serde_json::from_str::<Url>(&json)?)?,
+            json
+        );
+
+        // the builders validate the deserialized extensions
+        let json = r#"{"loc":"http://www.example.com/","extensions":[{"type":"image","loc":"x"}]}"#;
+        assert!(serde_json::from_str::<Url>(json).is_err());
+        let json = r#"{"loc":"http://www.example.com/","extensions":[{"type":"video","thumbnail_loc":"http://www.example.com/1.jpg","title":"Title","description":"Description"}]}"#;
+        assert!(serde_json::from_str::<Url>(json).is_err());
+        let json = r#"{"loc":"http://www.example.com/","extensions":[{"type":"news","name":"N","language":"english","publication_date":"2008-12-23","title":"T"}]}"#;
+        assert!(serde_json::from_str::<Url>(json).is_err());
+        let json =
+            r#"{"loc":"http://www.example.com/","extensions":[{"type":"rating","value":5}]}"#;
+        assert!(serde_json::from_str::<Url>(json).is_err());
+
+        let url = Url::loc("http://www.example.com/")?
+            .extension(Image::loc("http://www.example.com/image.jpg")?);
+        assert!(serde_json::to_string(&url).is_err());
+        Ok(())
+    }
 }
@@ -0,0 +1,278 @@
+use std::io::Write;
+
+use crate::{sitemap_writer::Error, sitemap_xml_writer::SitemapXmlWriter};
+
+type Result<T, E = crate::Error> = std::result::Result<T, E>;
+
+/// An extension of `url` entries in a custom namespace (e.g. a vendor
+/// extension).
+///
+/// The namespace must be declared by
+/// [`crate::SitemapWriterBuilder::namespace`] before writing entries with
+/// the extension. The elements are written after the `priority` child entry
+/// in the order the extensions are added to the [`crate::Url`].
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{Error, ExtensionWriter, SitemapWriterBuilder, Url, UrlExtension};
+/// use std::io::Cursor;
+///
+/// struct Rating(u8);
+///
+/// impl UrlExtension for Rating {
+///     fn namespace(&self) -> (&str, &str) {
+///         ("rating", "http://www.example.com/schemas/rating/1.0")
+///     }
+///
+///     fn write(&self, writer: &mut ExtensionWriter<'_>) -> Result<(), Error> {
+///         writer
+///             .start_tag("rating")?
+///             .element("stars", &self.0.to_string())?
+///             .end_tag("rating")?;
+///         Ok(())
+///     }
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut writer = SitemapWriterBuilder::new()
+///     .namespace("rating", "http://www.example.com/schemas/rating/1.0")
+///     .start(Cursor::new(Vec::new()))?;
+/// writer.write(Url::loc("http://www.example.com/")?.extension(Rating(4)))?;
+/// let (inner, _) = writer.finish()?;
+/// assert_eq!(
+///     String::from_utf8(inner.into_inner())?,
+///     concat!(
+///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+///         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:rating="http://www.example.com/schemas/rating/1.0">"#,
+///         r#"<url>"#,
+///         r#"<loc>http://www.example.com/</loc>"#,
+///         r#"<rating:rating><rating:stars>4</rating:stars></rating:rating>"#,
+///         r#"</url>"#,
+///         r#"</urlset>"#
+///     )
+/// );
+/// #     Ok(())
+/// # }
+/// ```
+pub trait UrlExtension {
+    /// Returns the namespace prefix and the namespace URI of the elements
+    /// written by this extension.
+    fn namespace(&self) -> (&str, &str);
+
+    /// Writes the elements of this extension.
+    fn write(&self, writer: &mut ExtensionWriter<'_>) -> Result<()>;
+}
+
+impl<T: UrlExtension + ?Sized> UrlExtension for &T {
+    fn namespace(&self) -> (&str, &str) {
+        (**self).namespace()
+    }
+
+    fn write(&self, writer: &mut ExtensionWriter<'_>) -> Result<()> {
+        (**self).write(writer)
+    }
+}
+
+/// A writer for the elements of a [`UrlExtension`].
+///
+/// The names are local names. They are qualified with the namespace prefix
+/// of the extension, and the contents are XML-escaped. The elements must be
+/// balanced.
+pub struct ExtensionWriter<'a> {
+    writer: &'a mut dyn XmlWrite,
+    prefix: &'a str,
+    open: Vec<String>,
+}
+
+impl<'a> ExtensionWriter<'a> {
+    pub(crate) fn new<W: Write>(writer: &'a mut SitemapXmlWriter<W>, prefix: &'a str) -> Self {
+        Self {
+            writer,
+            prefix,
+            open: Vec::new(),
+        }
+    }
+
+    /// Writes an element with the text content.
+    pub fn element(&mut self, name: &str, content: &str) -> Result<&mut Self> {
+        let name = self.qualified_name(name)?;
+        self.writer
+            .element(name.as_bytes(), content)
+            .map_err(Error::from)?;
+        Ok(self)
+    }
+
+    /// Writes an element with the attributes and the text content. The
+    /// attribute names are not qualified, and the values are XML-escaped.
+    pub fn element_with_attributes(
+        &mut self,
+        name: &str,
+        attributes: &[(&str, &str)],
+        content: &str,
+    ) -> Result<&mut Self> {
+        let name = self.qualified_name(name)?;
+        if !attributes.iter().all(|(name, _)| is_ncname(name)) {
+            return Err(Error::InvalidExtension.into());
+        }
+        self.writer
+            .element_with_attributes(name.as_bytes(), attributes, content)
+            .map_err(Error::from)?;
+        Ok(self)
+    }
+
+    /// Writes an empty element with the attributes. The attribute names are
+    /// not qualified, and the values are XML-escaped.
+    pub fn empty_element(&mut self, name: &str, attributes: &[(&str, &str)]) -> Result<&mut Self> {
+        let name = self.qualified_name(name)?;
+        if !attributes.iter().all(|(name, _)| is_ncname(name)) {
+            return Err(Error::InvalidExtension.into());
+        }
+        self.writer
+            .empty_element(name.as_bytes(), attributes)
+            .map_err(Error::from)?;
+        Ok(self)
+    }
+
+    /// Writes an opening tag.
+    pub fn start_tag(&mut self, name: &str) -> Result<&mut Self> {
+        let name = self.qualified_name(name)?;
+        self.writer
+            .start_tag(name.as_bytes())
+            .map_err(Error::from)?;
+        self.open.push(name);
+        Ok(self)
+    }
+
+    /// Writes a closing tag. It must match the last opening tag.
+    pub fn end_tag(&mut self, name: &str) -> Result<&mut Self> {
+        let name = self.qualified_name(name)?;
+        if self.open.last() != Some(&name) {
+            return Err(Error::InvalidExtension.into());
+        }
+        self.open.pop();
+        self.writer.end_tag(name.as_bytes()).map_err(Error::from)?;
+        Ok(self)
+    }
+
+    pub(crate) fn finish(self) -> Result<()> {
+        if !self.open.is_empty() {
+            return Err(Error::InvalidExtension.into());
+        }
+        Ok(())
+    }
+
+    fn qualified_name(&self, name: &str) -> Result<String> {
+        if !is_ncname(name) {
+            return Err(Error::InvalidExtension.into());
+        }
+        Ok(format!("{}:{}", self.prefix, name))
+    }
+}
+
+pub(crate) trait XmlWrite {
+    fn element(&mut self, name: &[u8], content: &str) -> crate::sitemap_xml_writer::Result<()>;
+    fn element_with_attributes(
+        &mut self,
+        name: &[u8],
+        attributes: &[(&str, &str)],
+        content: &str,
+    ) -> crate::sitemap_xml_writer::Result<()>;
+    fn empty_element(
+        &mut self,
+        name: &[u8],
+        attributes: &[(&str, &str)],
+    ) -> crate::sitemap_xml_writer::Result<()>;
+    fn end_tag(&mut self, name: &[u8]) -> crate::sitemap_xml_writer::Result<()>;
+    fn start_tag(&mut self, name: &[u8]) -> crate::sitemap_xml_writer::Result<()>;
+}
+
+impl<W: Write> XmlWrite for SitemapXmlWriter<W> {
+    fn element(&mut self, name: &[u8], content: &str) -> crate::sitemap_xml_writer::Result<()> {
+        SitemapXmlWriter::element(self, name, content)
+    }
+
+    fn element_with_attributes(
+        &mut self,
+        name: &[u8],
+        attributes: &[(&str, &str)],
+        content: &str,
+    ) -> crate::sitemap_xml_writer::Result<()> {
+        SitemapXmlWriter::element_with_attributes(self, name, attributes, content)
+    }
+
+    fn empty_element(
+        &mut self,
+        name: &[u8],
+        attributes: &[(&str, &str)],
+    ) -> crate::sitemap_xml_writer::Result<()> {
+        SitemapXmlWriter::empty_element(self, name, attributes)
+    }
+
+    fn end_tag(&mut self, name: &[u8]) -> crate::sitemap_xml_writer::Result<()> {
+        SitemapXmlWriter::end_tag(self, name)
+    }
+
+    fn start_tag(&mut self, name: &[u8]) -> crate::sitemap_xml_writer::Result<()> {
+        SitemapXmlWriter::start_tag(self, name)
+    }
+}
+
+/// Returns `true` if `s` is a valid XML name without colons (limited to
+/// ASCII).
+pub(crate) fn is_ncname(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_ncname() {
+        assert!(is_ncname("image"));
+        assert!(is_ncname("geo_location"));
+        assert!(is_ncname("a-b.c1"));
+        assert!(!is_ncname(""));
+        assert!(!is_ncname("1a"));
+        assert!(!is_ncname("-a"));
+        assert!(!is_ncname("a:b"));
+        assert!(!is_ncname("a b"));
+        assert!(!is_ncname("a>"));
+    }
+
+    #[test]
+    fn test_extension_writer() -> anyhow::Result<()> {
+        let mut xml_writer = SitemapXmlWriter::new(Vec::new(), false, false);
+        let mut writer = ExtensionWriter::new(&mut xml_writer, "x");
+        writer
+            .start_tag("a")?
+            .element("b", "<&>")?
+            .element_with_attributes("c", &[("d", "'")], "e")?
+            .empty_element("f", &[("g", "&"), ("h", "i")])?
+            .end_tag("a")?;
+        writer.finish()?;
+        assert_eq!(
+            String::from_utf8(xml_writer.into_inner())?,
+            r#"<x:a><x:b>&lt;&amp;&gt;</x:b><x:c d="&apos;">e</x:c><x:f g="&amp;" h="i"/></x:a>"#
+        );
+
+        let mut xml_writer = SitemapXmlWriter::new(Vec::new(), false, false);
+        let mut writer = ExtensionWriter::new(&mut xml_writer, "x");
+        assert!(writer.element("a:b", "c").is_err());
+        assert!(writer
+            .element_with_attributes("a", &[("b c", "d")], "e")
+            .is_err());
+        assert!(writer.empty_element("a", &[("b:c", "d")]).is_err());
+        assert!(writer.end_tag("a").is_err());
+        writer.start_tag("a")?;
+        assert!(writer.end_tag("b").is_err());
+        assert!(writer.finish().is_err());
+        Ok(())
+    }
+}
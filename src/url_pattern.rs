@@ -0,0 +1,166 @@
+use std::fmt::Display;
+
+use crate::{lastmod::Lastmod, loc::Loc, sitemap_writer::Error, url::Url};
+
+type Result<T, E = crate::Error> = std::result::Result<T, E>;
+
+/// A URL pattern with one placeholder (e.g. `https://www.example.com/products/{id}`)
+/// to generate `url` entries from values.
+///
+/// Each value is formatted with [`std::fmt::Display`] and percent-encoded,
+/// except for the unreserved characters (`A-Z`, `a-z`, `0-9`, `-`, `.`, `_`
+/// and `~`), before replacing the placeholder.
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{SitemapWriter, UrlPattern};
+/// use std::io::Cursor;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let pattern = UrlPattern::new("http://www.example.com/products/{id}")?;
+/// let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+/// for url in pattern.expand([1, 2, 3]) {
+///     writer.write(url?)?;
+/// }
+/// let (_, summary) = writer.finish()?;
+/// assert_eq!(summary.number_of_entries(), 3);
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UrlPattern {
+    prefix: String,
+    suffix: String,
+}
+
+impl UrlPattern {
+    /// Creates a new `UrlPattern`. The pattern must contain exactly one
+    /// placeholder `{name}`.
+    pub fn new(pattern: &str) -> Result<Self> {
+        let (prefix, rest) = pattern.split_once('{').ok_or(Error::InvalidLoc)?;
+        let (_, suffix) = rest.split_once('}').ok_or(Error::InvalidLoc)?;
+        if suffix.contains(['{', '}']) {
+            return Err(Error::InvalidLoc.into());
+        }
+        Ok(Self {
+            prefix: prefix.to_string(),
+            suffix: suffix.to_string(),
+        })
+    }
+
+    /// Builds a `url` entry by replacing the placeholder with the value.
+    pub fn url<V: Display>(&self, value: V) -> Result<Url<'static>> {
+        let mut s = String::with_capacity(self.prefix.len() + self.suffix.len() + 16);
+        s.push_str(&self.prefix);
+        for b in value.to_string().bytes() {
+            if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+                s.push(b as char);
+            } else {
+                s.push_str(&format!("%{:02X}", b));
+            }
+        }
+        s.push_str(&self.suffix);
+        let loc = Loc::try_from(s.as_str())
+            .map_err(|_| Error::InvalidLoc)?
+            .into_owned();
+        Ok(Url::new(loc))
+    }
+
+    /// Returns an iterator of `url` entries for the values.
+    pub fn expand<'a, I>(&'a self, values: I) -> impl Iterator<Item = Result<Url<'static>>> + 'a
+    where
+        I: IntoIterator,
+        I::IntoIter: 'a,
+        I::Item: Display,
+    {
+        values.into_iter().map(move |value| self.url(value))
+    }
+
+    /// Returns an iterator of `url` entries for the values, with the
+    /// `lastmod` child entries looked up by the callback.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use sitemap_xml_writer::UrlPattern;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let pattern = UrlPattern::new("http://www.example.com/products/{id}")?;
+    /// let urls = pattern
+    ///     .expand_with_lastmod([1, 2], |id| (*id == 1).then_some("2005-01-01"))
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(urls.len(), 2);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn expand_with_lastmod<'a, I, F, L>(
+        &'a self,
+        values: I,
+        mut lastmod: F,
+    ) -> impl Iterator<Item = Result<Url<'static>>> + 'a
+    where
+        I: IntoIterator,
+        I::IntoIter: 'a,
+        I::Item: Display,
+        F: FnMut(&I::Item) -> Option<L> + 'a,
+        L: TryInto<Lastmod<'static>>,
+    {
+        values.into_iter().map(move |value| {
+            let url = self.url(&value)?;
+            match lastmod(&value) {
+                Some(l) => url.lastmod(l),
+                None => Ok(url),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        assert!(UrlPattern::new("http://www.example.com/{id}").is_ok());
+        assert!(UrlPattern::new("http://www.example.com/{id}/reviews").is_ok());
+        assert!(UrlPattern::new("http://www.example.com/").is_err());
+        assert!(UrlPattern::new("http://www.example.com/{id").is_err());
+        assert!(UrlPattern::new("http://www.example.com/{a}/{b}").is_err());
+    }
+
+    #[test]
+    fn test_url() -> anyhow::Result<()> {
+        let pattern = UrlPattern::new("http://www.example.com/{id}/reviews")?;
+        assert_eq!(pattern.url(1)?.loc, "http://www.example.com/1/reviews");
+        assert_eq!(
+            pattern.url("a b/c")?.loc,
+            "http://www.example.com/a%20b%2Fc/reviews"
+        );
+        assert_eq!(
+            pattern.url("日")?.loc,
+            "http://www.example.com/%E6%97%A5/reviews"
+        );
+        let pattern = UrlPattern::new(&format!(
+            "http://www.example.com/{}{{id}}",
+            "a".repeat(2020)
+        ))?;
+        assert!(pattern.url(1)?.loc.len() < 2048);
+        assert!(pattern.url("b".repeat(100)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_expand_with_lastmod() -> anyhow::Result<()> {
+        let pattern = UrlPattern::new("http://www.example.com/{id}")?;
+        let urls = pattern
+            .expand_with_lastmod(vec![1, 2], |id| (*id == 2).then_some("2005-01-01"))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(urls[0].lastmod, None);
+        assert_eq!(urls[1].lastmod.as_deref(), Some("2005-01-01"));
+
+        let mut urls = pattern.expand_with_lastmod(vec![1], |_| Some("x"));
+        assert!(urls.next().is_some_and(|u| u.is_err()));
+        Ok(())
+    }
+}
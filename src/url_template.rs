@@ -0,0 +1,134 @@
+use crate::{
+    changefreq::Changefreq, lastmod::Lastmod, loc::Loc, priority::Priority, sitemap_writer::Error,
+    url::Url,
+};
+
+type Result<T, E = crate::Error> = std::result::Result<T, E>;
+
+/// A template for `url` entries with pre-set child entries.
+///
+/// The child entries are validated once, and each entry produced by
+/// [`UrlTemplate::loc`] borrows them.
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{SitemapWriter, UrlTemplate};
+/// use std::io::Cursor;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let template = UrlTemplate::new()
+///     .lastmod("2005-01-01")?
+///     .changefreq("monthly")?
+///     .priority("0.8")?;
+/// let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+/// writer.write(template.loc("http://www.example.com/a")?)?;
+/// writer.write(template.loc("http://www.example.com/b")?)?;
+/// let (inner, _) = writer.finish()?;
+/// assert_eq!(
+///     String::from_utf8(inner.into_inner())?,
+///     concat!(
+///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+///         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+///         r#"<url><loc>http://www.example.com/a</loc><lastmod>2005-01-01</lastmod><changefreq>monthly</changefreq><priority>0.8</priority></url>"#,
+///         r#"<url><loc>http://www.example.com/b</loc><lastmod>2005-01-01</lastmod><changefreq>monthly</changefreq><priority>0.8</priority></url>"#,
+///         r#"</urlset>"#
+///     )
+/// );
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct UrlTemplate<'a> {
+    lastmod: Option<Lastmod<'a>>,
+    changefreq: Option<Changefreq>,
+    priority: Option<Priority<'a>>,
+}
+
+impl<'a> UrlTemplate<'a> {
+    /// Creates a new `UrlTemplate` without child entries.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Changes the `changefreq` child entry to the specified value.
+    pub fn changefreq<S>(mut self, s: S) -> Result<Self>
+    where
+        S: TryInto<Changefreq>,
+    {
+        self.changefreq = Some(s.try_into().map_err(|_| Error::InvalidChangefreq)?);
+        Ok(self)
+    }
+
+    /// Changes the `lastmod` child entry to the specified value.
+    pub fn lastmod<S>(mut self, s: S) -> Result<Self>
+    where
+        S: TryInto<Lastmod<'a>>,
+    {
+        self.lastmod = Some(s.try_into().map_err(|_| Error::InvalidLastmod)?);
+        Ok(self)
+    }
+
+    /// Changes the `priority` child entry to the specified value.
+    pub fn priority<S>(mut self, s: S) -> Result<Self>
+    where
+        S: TryInto<Priority<'a>>,
+    {
+        self.priority = Some(s.try_into().map_err(|_| Error::InvalidPriority)?);
+        Ok(self)
+    }
+
+    /// Builds a `url` entry with the specified URL as the content of the
+    /// `loc` child entry and the child entries of this template.
+    pub fn loc<'b, S>(&'b self, loc: S) -> Result<Url<'b>>
+    where
+        S: TryInto<Loc<'b>>,
+    {
+        let mut url = Url::loc(loc)?;
+        if let Some(lastmod) = self.lastmod.as_ref() {
+            url = url.with_lastmod(Lastmod::from(lastmod));
+        }
+        if let Some(changefreq) = self.changefreq {
+            url = url.with_changefreq(changefreq);
+        }
+        if let Some(priority) = self.priority.as_ref() {
+            url = url.with_priority(Priority::from(priority));
+        }
+        Ok(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+
+    #[test]
+    fn test_loc() -> anyhow::Result<()> {
+        let template = UrlTemplate::new();
+        let url = template.loc("http://www.example.com/")?;
+        assert_eq!(url.loc, "http://www.example.com/");
+        assert_eq!(url.lastmod, None);
+        assert_eq!(url.changefreq, None);
+        assert_eq!(url.priority, None);
+
+        let template = UrlTemplate::new()
+            .lastmod("2005-01-01")?
+            .changefreq(Changefreq::Daily)?
+            .priority(0.5)?;
+        let url = template.loc("http://www.example.com/")?;
+        assert!(matches!(url.lastmod, Some(Cow::Borrowed("2005-01-01"))));
+        assert_eq!(url.changefreq, Some(Changefreq::Daily));
+        assert!(matches!(url.priority, Some(Cow::Borrowed("0.5"))));
+        assert!(template.loc("a".repeat(2048).as_str()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(UrlTemplate::new().lastmod("2005-13-01").is_err());
+        assert!(UrlTemplate::new().changefreq("sometimes").is_err());
+        assert!(UrlTemplate::new().priority("1.1").is_err());
+    }
+}
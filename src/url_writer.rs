@@ -0,0 +1,246 @@
+use std::io::Write;
+
+use crate::{
+    alternate::Alternate,
+    changefreq::Changefreq,
+    image::{is_image, Image},
+    lastmod::Lastmod,
+    news::{is_news, News},
+    priority::Priority,
+    sitemap_writer::{Error, SitemapWriter},
+    url_extension::UrlExtension,
+    video::Video,
+};
+
+type Result<T, E = crate::Error> = std::result::Result<T, E>;
+
+/// A writer for the child entries of a `url` element.
+///
+/// It is created by [`SitemapWriter::begin_url`]. The `loc` child entry has
+/// already been written. The optional child entries must be written in the
+/// order `lastmod`, `changefreq`, `priority`, followed by the extensions.
+///
+/// The `url` element is buffered until [`UrlWriter::end`]. If writing a child
+/// entry fails (e.g. the max byte length is exceeded), the whole `url`
+/// element is discarded, and the following calls return an error.
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::SitemapWriter;
+/// use std::io::Cursor;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+/// let mut url = writer.begin_url("http://www.example.com/")?;
+/// url.lastmod("2005-01-01")?.priority(0.8)?;
+/// url.end()?;
+///
+/// let mut url = writer.begin_url("http://www.example.com/about")?;
+/// // `changefreq` can not be written after `priority`.
+/// url.priority("0.5")?;
+/// assert!(url.changefreq("monthly").is_err());
+/// url.end()?;
+/// writer.end()?;
+/// #     Ok(())
+/// # }
+/// ```
+pub struct UrlWriter<'w, W: Write> {
+    writer: &'w mut SitemapWriter<W>,
+    next: usize,
+    number_of_images: usize,
+    news: bool,
+    skipped: bool,
+}
+
+impl<'w, W: Write> UrlWriter<'w, W> {
+    const LASTMOD: usize = 0;
+    const CHANGEFREQ: usize = 1;
+    const PRIORITY: usize = 2;
+    const EXTENSION: usize = 3;
+
+    pub(crate) fn new(writer: &'w mut SitemapWriter<W>) -> Self {
+        Self {
+            writer,
+            next: Self::LASTMOD,
+            number_of_images: 0,
+            news: false,
+            skipped: false,
+        }
+    }
+
+    pub(crate) fn skipped(writer: &'w mut SitemapWriter<W>) -> Self {
+        Self {
+            writer,
+            next: Self::LASTMOD,
+            number_of_images: 0,
+            news: false,
+            skipped: true,
+        }
+    }
+
+    /// Writes a `lastmod` child entry.
+    pub fn lastmod<'a, S>(&mut self, s: S) -> Result<&mut Self>
+    where
+        S: TryInto<Lastmod<'a>>,
+    {
+        self.entry(|this| {
+            let lastmod = s
+                .try_into()
+                .map_err(|_| Error::InvalidLastmod)?
+                .into_inner();
+            let lastmod = this.writer.check_lastmod(lastmod)?;
+            this.advance(Self::LASTMOD)?;
+            if this.skipped {
+                return Ok(());
+            }
+            this.element(b"lastmod", lastmod.as_ref())
+        })
+    }
+
+    /// Writes a `changefreq` child entry.
+    pub fn changefreq<S>(&mut self, s: S) -> Result<&mut Self>
+    where
+        S: TryInto<Changefreq>,
+    {
+        self.entry(|this| {
+            let changefreq = s.try_into().map_err(|_| Error::InvalidChangefreq)?;
+            this.advance(Self::CHANGEFREQ)?;
+            if this.skipped {
+                return Ok(());
+            }
+            this.element(b"changefreq", changefreq.as_ref())
+        })
+    }
+
+    /// Writes a `priority` child entry.
+    pub fn priority<'a, S>(&mut self, s: S) -> Result<&mut Self>
+    where
+        S: TryInto<Priority<'a>>,
+    {
+        self.entry(|this| {
+            let priority = s
+                .try_into()
+                .map_err(|_| Error::InvalidPriority)?
+                .into_inner();
+            this.advance(Self::PRIORITY)?;
+            if this.skipped {
+                return Ok(());
+            }
+            this.element(b"priority", priority.as_ref())
+        })
+    }
+
+    /// Writes the elements of an extension (see [`UrlExtension`]). It can be
+    /// called more than once.
+    ///
+    /// An extension in the image sitemap namespace counts as one
+    /// `image:image` entry. It returns an error if the entry already has
+    /// [`Image::MAX_NUMBER_OF_IMAGES`] images. Likewise, an extension in the
+    /// news sitemap namespace counts as one `news:news` entry, and a `url`
+    /// entry can have only one.
+    pub fn extension(&mut self, extension: &dyn UrlExtension) -> Result<&mut Self> {
+        self.entry(|this| {
+            this.writer.check_namespace(extension)?;
+            let image = is_image(extension);
+            if image && this.number_of_images >= Image::MAX_NUMBER_OF_IMAGES {
+                return Err(Error::MaxNumberOfImages.into());
+            }
+            let news = is_news(extension);
+            if news && this.news {
+                return Err(Error::MaxNumberOfNews.into());
+            }
+            this.advance(Self::EXTENSION)?;
+            if image {
+                this.number_of_images += 1;
+            }
+            this.news |= news;
+            // extensions are repeatable
+            this.next = Self::EXTENSION;
+            if this.skipped {
+                return Ok(());
+            }
+            if let Err(e) = this.writer.write_extension(extension) {
+                this.writer.discard_url();
+                return Err(e);
+            }
+            Ok(())
+        })
+    }
+
+    /// Writes an `xhtml:link` entry (see [`Alternate`]) as an extension.
+    pub fn alternate(&mut self, alternate: &Alternate<'_>) -> Result<&mut Self> {
+        self.extension(alternate)
+    }
+
+    /// Writes an `image:image` entry (see [`Image`]) as an extension.
+    ///
+    /// It returns an error if the entry already has
+    /// [`Image::MAX_NUMBER_OF_IMAGES`] images.
+    pub fn image(&mut self, image: &Image<'_>) -> Result<&mut Self> {
+        self.extension(image)
+    }
+
+    /// Writes a `news:news` entry (see [`News`]) as an extension.
+    ///
+    /// It returns an error if the entry already has a `news:news` entry.
+    pub fn news(&mut self, news: &News<'_>) -> Result<&mut Self> {
+        self.entry(|this| Ok(this.writer.check_news(news)?))?;
+        self.extension(news)
+    }
+
+    /// Writes a `video:video` entry (see [`Video`]) as an extension. It can
+    /// be called more than once.
+    ///
+    /// It returns an error if the video has neither `video:content_loc` nor
+    /// `video:player_loc`, or if its `video:expiration_date` is before its
+    /// `video:publication_date`.
+    pub fn video(&mut self, video: &Video<'_>) -> Result<&mut Self> {
+        self.entry(|_| video.validate())?;
+        self.extension(video)
+    }
+
+    /// Writes a closing `</url>` tag.
+    pub fn end(mut self) -> Result<()> {
+        self.entry(|this| {
+            if this.skipped {
+                return Ok(());
+            }
+            if !this.writer.is_url_open() {
+                return Err(Error::InvalidElementOrder.into());
+            }
+            Ok(this.writer.end_url_inner()?)
+        })?;
+        Ok(())
+    }
+
+    /// Runs `f`, and adds the index of the entry to its error.
+    fn entry<F>(&mut self, f: F) -> Result<&mut Self>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        match f(self) {
+            Ok(()) => Ok(self),
+            Err(e) => Err(e.with_entry_index(self.writer.entry_index())),
+        }
+    }
+
+    fn advance(&mut self, field: usize) -> Result<()> {
+        if !self.skipped && !self.writer.is_url_open() {
+            return Err(Error::InvalidElementOrder.into());
+        }
+        if field < self.next {
+            return Err(Error::InvalidElementOrder.into());
+        }
+        self.next = field + 1;
+        Ok(())
+    }
+
+    fn element(&mut self, name: &[u8], content: &str) -> Result<()> {
+        if let Err(e) = self.writer.xml_writer().element(name, content) {
+            self.writer.discard_url();
+            return Err(Error::from(e).into());
+        }
+        Ok(())
+    }
+}
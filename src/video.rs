@@ -0,0 +1,1228 @@
+use std::borrow::Cow;
+
+use crate::{
+    date_time::W3cDateTime,
+    lastmod::Lastmod,
+    loc::Loc,
+    sitemap_writer::Error,
+    sitemap_xml_writer::is_text,
+    url_extension::{ExtensionWriter, UrlExtension},
+};
+
+type Result<T, E = crate::Error> = std::result::Result<T, E>;
+
+/// A `video:video` entry of the
+/// [video sitemap extension](https://developers.google.com/search/docs/crawling-indexing/sitemaps/video-sitemaps).
+///
+/// It is added to a `url` entry by [`crate::Url::video`], which requires at
+/// least one of `video:content_loc` and `video:player_loc`. The namespace
+/// must be declared by [`crate::SitemapWriterBuilder::namespace`] with
+/// [`Video::PREFIX`] and [`Video::NAMESPACE_URI`].
+///
+/// The `video:title` and `video:description` must not be empty and must not
+/// contain characters that are not allowed in XML. The `video:description`
+/// must be at most 2,048 characters. The optional child entries are
+/// range-checked, and each invalid value returns a specific error (e.g.
+/// [`crate::ErrorKind::InvalidVideoDuration`]).
+///
+/// # Examples
+///
+/// ```rust
+/// use sitemap_xml_writer::{SitemapWriterBuilder, Url, Video};
+/// use std::io::Cursor;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut writer = SitemapWriterBuilder::new()
+///     .namespace(Video::PREFIX, Video::NAMESPACE_URI)
+///     .start(Cursor::new(Vec::new()))?;
+/// writer.write(
+///     Url::loc("http://www.example.com/videos/1.html")?.video(
+///         Video::new(
+///             "http://www.example.com/thumbs/1.jpg",
+///             "Grilling steaks",
+///             "Alkis shows you how to get perfectly done steaks every time",
+///         )?
+///         .content_loc("http://streamserver.example.com/video1.mp4")?,
+///     )?,
+/// )?;
+/// let (inner, _) = writer.finish()?;
+/// assert_eq!(
+///     String::from_utf8(inner.into_inner())?,
+///     concat!(
+///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+///         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:video="http://www.google.com/schemas/sitemap-video/1.1">"#,
+///         r#"<url>"#,
+///         r#"<loc>http://www.example.com/videos/1.html</loc>"#,
+///         r#"<video:video>"#,
+///         r#"<video:thumbnail_loc>http://www.example.com/thumbs/1.jpg</video:thumbnail_loc>"#,
+///         r#"<video:title>Grilling steaks</video:title>"#,
+///         r#"<video:description>Alkis shows you how to get perfectly done steaks every time</video:description>"#,
+///         r#"<video:content_loc>http://streamserver.example.com/video1.mp4</video:content_loc>"#,
+///         r#"</video:video>"#,
+///         r#"</url>"#,
+///         r#"</urlset>"#
+///     )
+/// );
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Video<'a> {
+    thumbnail_loc: Cow<'a, str>,
+    title: Cow<'a, str>,
+    description: Cow<'a, str>,
+    content_loc: Option<Cow<'a, str>>,
+    player_loc: Option<Cow<'a, str>>,
+    duration: Option<u32>,
+    expiration_date: Option<Cow<'a, str>>,
+    rating: Option<Cow<'a, str>>,
+    view_count: Option<u64>,
+    publication_date: Option<Cow<'a, str>>,
+    tags: Vec<Cow<'a, str>>,
+    family_friendly: Option<bool>,
+    restriction: Option<(VideoRelationship, String)>,
+    prices: Vec<VideoPrice>,
+    requires_subscription: Option<bool>,
+    uploader: Option<(Cow<'a, str>, Option<Cow<'a, str>>)>,
+    platform: Option<(VideoRelationship, String)>,
+    live: Option<bool>,
+}
+
+impl<'a> Video<'a> {
+    /// The namespace prefix of the video sitemap extension.
+    pub const PREFIX: &'static str = "video";
+
+    /// The namespace URI of the video sitemap extension.
+    pub const NAMESPACE_URI: &'static str = "http://www.google.com/schemas/sitemap-video/1.1";
+
+    const MAX_DESCRIPTION_LENGTH: usize = 2_048;
+    const MAX_DURATION: u32 = 28_800;
+
+    /// The max number of `video:tag` child entries in a `video:video` entry.
+    pub const MAX_NUMBER_OF_TAGS: usize = 32;
+    const MAX_RATING: f64 = 5.0;
+    const MAX_UPLOADER_LENGTH: usize = 255;
+
+    /// Builds a `video:video` entry with the required `video:thumbnail_loc`,
+    /// `video:title` and `video:description` child entries.
+    pub fn new<L, T, D>(thumbnail_loc: L, title: T, description: D) -> Result<Self>
+    where
+        L: TryInto<Loc<'a>>,
+        T: Into<Cow<'a, str>>,
+        D: Into<Cow<'a, str>>,
+    {
+        let thumbnail_loc = thumbnail_loc
+            .try_into()
+            .map_err(|_| Error::InvalidVideo)?
+            .into_inner();
+        let title = text(title)?;
+        let description = text(description)?;
+        if description.chars().count() > Self::MAX_DESCRIPTION_LENGTH {
+            return Err(Error::InvalidVideo.into());
+        }
+        Ok(Self {
+            thumbnail_loc,
+            title,
+            description,
+            content_loc: None,
+            player_loc: None,
+            duration: None,
+            expiration_date: None,
+            rating: None,
+            view_count: None,
+            publication_date: None,
+            tags: Vec::new(),
+            family_friendly: None,
+            restriction: None,
+            prices: Vec::new(),
+            requires_subscription: None,
+            uploader: None,
+            platform: None,
+            live: None,
+        })
+    }
+
+    /// Changes the `video:content_loc` child entry (the URL of the video
+    /// file) to the specified URL.
+    pub fn content_loc<S>(mut self, loc: S) -> Result<Self>
+    where
+        S: TryInto<Loc<'a>>,
+    {
+        let loc = loc.try_into().map_err(|_| Error::InvalidVideo)?;
+        self.content_loc = Some(loc.into_inner());
+        Ok(self)
+    }
+
+    /// Changes the `video:player_loc` child entry (the URL of the video
+    /// player) to the specified URL.
+    pub fn player_loc<S>(mut self, loc: S) -> Result<Self>
+    where
+        S: TryInto<Loc<'a>>,
+    {
+        let loc = loc.try_into().map_err(|_| Error::InvalidVideo)?;
+        self.player_loc = Some(loc.into_inner());
+        Ok(self)
+    }
+
+    /// Changes the `video:duration` child entry to the specified number of
+    /// seconds (1 to 28,800).
+    pub fn duration(mut self, seconds: u32) -> Result<Self> {
+        if !(1..=Self::MAX_DURATION).contains(&seconds) {
+            return Err(Error::InvalidVideoDuration.into());
+        }
+        self.duration = Some(seconds);
+        Ok(self)
+    }
+
+    /// Changes the `video:expiration_date` child entry to the specified date
+    /// or datetime. It accepts the same values as [`crate::Url::lastmod`].
+    ///
+    /// The video is invalid if it is before the `video:publication_date`.
+    pub fn expiration_date<S>(mut self, s: S) -> Result<Self>
+    where
+        S: TryInto<Lastmod<'a>>,
+    {
+        let date = s
+            .try_into()
+            .map_err(|_| Error::InvalidVideoExpirationDate)?;
+        self.expiration_date = Some(date.into_inner());
+        Ok(self)
+    }
+
+    /// Changes the `video:rating` child entry to the specified value (0.0 to
+    /// 5.0).
+    pub fn rating(mut self, rating: f64) -> Result<Self> {
+        if !(0.0..=Self::MAX_RATING).contains(&rating) {
+            return Err(Error::InvalidVideoRating.into());
+        }
+        self.rating = Some(Cow::Owned(rating.to_string()));
+        Ok(self)
+    }
+
+    /// Changes the `video:view_count` child entry to the specified value.
+    pub fn view_count(mut self, view_count: u64) -> Self {
+        self.view_count = Some(view_count);
+        self
+    }
+
+    /// Changes the `video:publication_date` child entry to the specified
+    /// date or datetime. It accepts the same values as
+    /// [`crate::Url::lastmod`].
+    pub fn publication_date<S>(mut self, s: S) -> Result<Self>
+    where
+        S: TryInto<Lastmod<'a>>,
+    {
+        let date = s
+            .try_into()
+            .map_err(|_| Error::InvalidVideoPublicationDate)?;
+        self.publication_date = Some(date.into_inner());
+        Ok(self)
+    }
+
+    /// Adds a `video:tag` child entry. Call it more than once for multiple
+    /// tags.
+    ///
+    /// It returns an error if the video already has
+    /// [`Video::MAX_NUMBER_OF_TAGS`] tags.
+    pub fn tag<S>(mut self, tag: S) -> Result<Self>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let tag = tag.into();
+        if !is_text(&tag) {
+            return Err(Error::InvalidVideoTag.into());
+        }
+        if self.tags.len() >= Self::MAX_NUMBER_OF_TAGS {
+            return Err(Error::MaxNumberOfVideoTags.into());
+        }
+        self.tags.push(tag);
+        Ok(self)
+    }
+
+    /// Changes the `video:family_friendly` child entry (`yes` or `no`).
+    pub fn family_friendly(mut self, family_friendly: bool) -> Self {
+        self.family_friendly = Some(family_friendly);
+        self
+    }
+
+    /// Changes the `video:restriction` child entry to the specified
+    /// countries (ISO 3166-1 alpha-2 codes, e.g. `"US"`). They are written in
+    /// upper case.
+    pub fn restriction<I, S>(
+        mut self,
+        relationship: VideoRelationship,
+        countries: I,
+    ) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut content = String::new();
+        for country in countries {
+            let country = country.as_ref();
+            if !(country.len() == 2 && country.bytes().all(|b| b.is_ascii_alphabetic())) {
+                return Err(Error::InvalidVideoRestriction.into());
+            }
+            if !content.is_empty() {
+                content.push(' ');
+            }
+            content.push_str(&country.to_ascii_uppercase());
+        }
+        if content.is_empty() {
+            return Err(Error::InvalidVideoRestriction.into());
+        }
+        self.restriction = Some((relationship, content));
+        Ok(self)
+    }
+
+    /// Changes the `video:platform` child entry to the specified platforms.
+    pub fn platform<I>(mut self, relationship: VideoRelationship, platforms: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = VideoPlatform>,
+    {
+        let content = platforms
+            .into_iter()
+            .map(VideoPlatform::as_str)
+            .collect::<Vec<&str>>()
+            .join(" ");
+        if content.is_empty() {
+            return Err(Error::InvalidVideoPlatform.into());
+        }
+        self.platform = Some((relationship, content));
+        Ok(self)
+    }
+
+    /// Adds a `video:price` child entry. Call it more than once for multiple
+    /// prices (e.g. in different currencies).
+    pub fn price(mut self, price: VideoPrice) -> Self {
+        self.prices.push(price);
+        self
+    }
+
+    /// Changes the `video:requires_subscription` child entry (`yes` or
+    /// `no`).
+    pub fn requires_subscription(mut self, requires_subscription: bool) -> Self {
+        self.requires_subscription = Some(requires_subscription);
+        self
+    }
+
+    /// Changes the `video:uploader` child entry to the specified name (at
+    /// most 255 characters).
+    pub fn uploader<S>(mut self, name: S) -> Result<Self>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.uploader = Some((uploader_name(name)?, None));
+        Ok(self)
+    }
+
+    /// Changes the `video:uploader` child entry to the specified name (at
+    /// most 255 characters) with the `info` attribute (the URL of a page
+    /// about the uploader).
+    pub fn uploader_with_info<S, L>(mut self, name: S, info: L) -> Result<Self>
+    where
+        S: Into<Cow<'a, str>>,
+        L: TryInto<Loc<'a>>,
+    {
+        let name = uploader_name(name)?;
+        let info = info
+            .try_into()
+            .map_err(|_| Error::InvalidVideoUploader)?
+            .into_inner();
+        self.uploader = Some((name, Some(info)));
+        Ok(self)
+    }
+
+    /// Changes the `video:live` child entry (`yes` or `no`).
+    pub fn live(mut self, live: bool) -> Self {
+        self.live = Some(live);
+        self
+    }
+
+    /// Converts this `Video` into an owned `Video<'static>`.
+    pub fn into_owned(self) -> Video<'static> {
+        Video {
+            thumbnail_loc: Cow::Owned(self.thumbnail_loc.into_owned()),
+            title: Cow::Owned(self.title.into_owned()),
+            description: Cow::Owned(self.description.into_owned()),
+            content_loc: self.content_loc.map(|s| Cow::Owned(s.into_owned())),
+            player_loc: self.player_loc.map(|s| Cow::Owned(s.into_owned())),
+            duration: self.duration,
+            expiration_date: self.expiration_date.map(|s| Cow::Owned(s.into_owned())),
+            rating: self.rating.map(|s| Cow::Owned(s.into_owned())),
+            view_count: self.view_count,
+            publication_date: self.publication_date.map(|s| Cow::Owned(s.into_owned())),
+            tags: self
+                .tags
+                .into_iter()
+                .map(|s| Cow::Owned(s.into_owned()))
+                .collect(),
+            family_friendly: self.family_friendly,
+            restriction: self.restriction,
+            prices: self.prices,
+            requires_subscription: self.requires_subscription,
+            uploader: self.uploader.map(|(name, info)| {
+                (
+                    Cow::Owned(name.into_owned()),
+                    info.map(|s| Cow::Owned(s.into_owned())),
+                )
+            }),
+            platform: self.platform,
+            live: self.live,
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.content_loc.is_none() && self.player_loc.is_none() {
+            return Err(Error::InvalidVideo.into());
+        }
+        if let (Some(expiration_date), Some(publication_date)) = (
+            self.expiration_date.as_deref().and_then(W3cDateTime::parse),
+            self.publication_date
+                .as_deref()
+                .and_then(W3cDateTime::parse),
+        ) {
+            if expiration_date.order_key() < publication_date.order_key() {
+                return Err(Error::InvalidVideoExpirationDate.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The `relationship` attribute of the `video:restriction` and
+/// `video:platform` child entries.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum VideoRelationship {
+    /// `allow`: the video is available only for the listed values.
+    Allow,
+    /// `deny`: the video is not available for the listed values.
+    Deny,
+}
+
+impl VideoRelationship {
+    fn as_str(self) -> &'static str {
+        match self {
+            VideoRelationship::Allow => "allow",
+            VideoRelationship::Deny => "deny",
+        }
+    }
+}
+
+/// A platform of the `video:platform` child entry.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum VideoPlatform {
+    /// `web`
+    Web,
+    /// `mobile`
+    Mobile,
+    /// `tv`
+    Tv,
+}
+
+impl VideoPlatform {
+    fn as_str(self) -> &'static str {
+        match self {
+            VideoPlatform::Web => "web",
+            VideoPlatform::Mobile => "mobile",
+            VideoPlatform::Tv => "tv",
+        }
+    }
+}
+
+/// The `type` attribute of the `video:price` child entry.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum VideoPriceType {
+    /// `rent`
+    Rent,
+    /// `own`
+    Own,
+}
+
+impl VideoPriceType {
+    fn as_str(self) -> &'static str {
+        match self {
+            VideoPriceType::Rent => "rent",
+            VideoPriceType::Own => "own",
+        }
+    }
+}
+
+/// The `resolution` attribute of the `video:price` child entry.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "UPPERCASE")
+)]
+pub enum VideoResolution {
+    /// `HD`
+    Hd,
+    /// `SD`
+    Sd,
+}
+
+impl VideoResolution {
+    fn as_str(self) -> &'static str {
+        match self {
+            VideoResolution::Hd => "HD",
+            VideoResolution::Sd => "SD",
+        }
+    }
+}
+
+/// A `video:price` child entry of [`Video`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use sitemap_xml_writer::{VideoPrice, VideoPriceType, VideoResolution};
+/// # fn main() -> anyhow::Result<()> {
+/// VideoPrice::new(1.99, "EUR")?
+///     .price_type(VideoPriceType::Rent)
+///     .resolution(VideoResolution::Hd);
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VideoPrice {
+    amount: String,
+    currency: String,
+    price_type: Option<VideoPriceType>,
+    resolution: Option<VideoResolution>,
+}
+
+impl VideoPrice {
+    /// Creates a new `VideoPrice` with the non-negative amount and the
+    /// currency (ISO 4217 code, e.g. `"USD"`). The currency is written in
+    /// upper case.
+    pub fn new(amount: f64, currency: &str) -> Result<Self> {
+        if !(amount.is_finite() && amount >= 0.0) {
+            return Err(Error::InvalidVideoPrice.into());
+        }
+        if !(currency.len() == 3 && currency.bytes().all(|b| b.is_ascii_alphabetic())) {
+            return Err(Error::InvalidVideoPrice.into());
+        }
+        Ok(Self {
+            amount: amount.to_string(),
+            currency: currency.to_ascii_uppercase(),
+            price_type: None,
+            resolution: None,
+        })
+    }
+
+    /// Changes the `type` attribute (the purchase option).
+    pub fn price_type(mut self, price_type: VideoPriceType) -> Self {
+        self.price_type = Some(price_type);
+        self
+    }
+
+    /// Changes the `resolution` attribute.
+    pub fn resolution(mut self, resolution: VideoResolution) -> Self {
+        self.resolution = Some(resolution);
+        self
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for VideoPrice {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::{Error, SerializeStruct};
+        let amount = self.amount.parse::<f64>().map_err(S::Error::custom)?;
+        let mut s = serializer.serialize_struct("VideoPrice", 4)?;
+        s.serialize_field("amount", &amount)?;
+        s.serialize_field("currency", &self.currency)?;
+        match &self.price_type {
+            Some(price_type) => s.serialize_field("type", price_type)?,
+            None => s.skip_field("type")?,
+        }
+        match &self.resolution {
+            Some(resolution) => s.serialize_field("resolution", resolution)?,
+            None => s.skip_field("resolution")?,
+        }
+        s.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for VideoPrice {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "VideoPrice")]
+        struct Repr {
+            amount: f64,
+            currency: String,
+            #[serde(rename = "type")]
+            price_type: Option<VideoPriceType>,
+            resolution: Option<VideoResolution>,
+        }
+
+        let repr = Repr::deserialize(deserializer)?;
+        let mut price = VideoPrice::new(repr.amount, &repr.currency)
+            .map_err(|_| serde::de::Error::custom("invalid video price"))?;
+        price.price_type = repr.price_type;
+        price.resolution = repr.resolution;
+        Ok(price)
+    }
+}
+
+// The `video:restriction`, `video:platform` and `video:uploader` child
+// entries with their attributes.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize, serde::Serialize)]
+struct RestrictionRepr<T> {
+    relationship: VideoRelationship,
+    countries: Vec<T>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize, serde::Serialize)]
+struct PlatformRepr<T> {
+    relationship: VideoRelationship,
+    platforms: Vec<T>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize, serde::Serialize)]
+struct UploaderRepr<T> {
+    name: T,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    info: Option<T>,
+}
+
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for Video<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::{Error, SerializeStruct};
+        let mut s = serializer.serialize_struct("Video", 18)?;
+        s.serialize_field("thumbnail_loc", &self.thumbnail_loc)?;
+        s.serialize_field("title", &self.title)?;
+        s.serialize_field("description", &self.description)?;
+        match &self.content_loc {
+            Some(content_loc) => s.serialize_field("content_loc", content_loc)?,
+            None => s.skip_field("content_loc")?,
+        }
+        match &self.player_loc {
+            Some(player_loc) => s.serialize_field("player_loc", player_loc)?,
+            None => s.skip_field("player_loc")?,
+        }
+        match &self.duration {
+            Some(duration) => s.serialize_field("duration", duration)?,
+            None => s.skip_field("duration")?,
+        }
+        match &self.expiration_date {
+            Some(expiration_date) => s.serialize_field("expiration_date", expiration_date)?,
+            None => s.skip_field("expiration_date")?,
+        }
+        match &self.rating {
+            Some(rating) => {
+                let rating = rating.parse::<f64>().map_err(S::Error::custom)?;
+                s.serialize_field("rating", &rating)?
+            }
+            None => s.skip_field("rating")?,
+        }
+        match &self.view_count {
+            Some(view_count) => s.serialize_field("view_count", view_count)?,
+            None => s.skip_field("view_count")?,
+        }
+        match &self.publication_date {
+            Some(publication_date) => s.serialize_field("publication_date", publication_date)?,
+            None => s.skip_field("publication_date")?,
+        }
+        if self.tags.is_empty() {
+            s.skip_field("tags")?;
+        } else {
+            s.serialize_field("tags", &self.tags)?;
+        }
+        match &self.family_friendly {
+            Some(family_friendly) => s.serialize_field("family_friendly", family_friendly)?,
+            None => s.skip_field("family_friendly")?,
+        }
+        match &self.restriction {
+            Some((relationship, content)) => s.serialize_field(
+                "restriction",
+                &RestrictionRepr {
+                    relationship: *relationship,
+                    countries: content.split(' ').collect::<Vec<&str>>(),
+                },
+            )?,
+            None => s.skip_field("restriction")?,
+        }
+        if self.prices.is_empty() {
+            s.skip_field("prices")?;
+        } else {
+            s.serialize_field("prices", &self.prices)?;
+        }
+        match &self.requires_subscription {
+            Some(requires_subscription) => {
+                s.serialize_field("requires_subscription", requires_subscription)?
+            }
+            None => s.skip_field("requires_subscription")?,
+        }
+        match &self.uploader {
+            Some((name, info)) => s.serialize_field(
+                "uploader",
+                &UploaderRepr {
+                    name: name.as_ref(),
+                    info: info.as_deref(),
+                },
+            )?,
+            None => s.skip_field("uploader")?,
+        }
+        match &self.platform {
+            Some((relationship, content)) => s.serialize_field(
+                "platform",
+                &PlatformRepr {
+                    relationship: *relationship,
+                    platforms: content.split(' ').collect::<Vec<&str>>(),
+                },
+            )?,
+            None => s.skip_field("platform")?,
+        }
+        match &self.live {
+            Some(live) => s.serialize_field("live", live)?,
+            None => s.skip_field("live")?,
+        }
+        s.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for Video<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Video")]
+        struct Repr {
+            thumbnail_loc: Loc<'static>,
+            title: String,
+            description: String,
+            content_loc: Option<Loc<'static>>,
+            player_loc: Option<Loc<'static>>,
+            duration: Option<u32>,
+            expiration_date: Option<Lastmod<'static>>,
+            rating: Option<f64>,
+            view_count: Option<u64>,
+            publication_date: Option<Lastmod<'static>>,
+            #[serde(default)]
+            tags: Vec<String>,
+            family_friendly: Option<bool>,
+            restriction: Option<RestrictionRepr<String>>,
+            #[serde(default)]
+            prices: Vec<VideoPrice>,
+            requires_subscription: Option<bool>,
+            uploader: Option<UploaderRepr<String>>,
+            platform: Option<PlatformRepr<VideoPlatform>>,
+            live: Option<bool>,
+        }
+
+        let repr = Repr::deserialize(deserializer)?;
+        let video = || -> Result<Video<'static>> {
+            let mut video = Video::new(repr.thumbnail_loc, repr.title, repr.description)?;
+            if let Some(content_loc) = repr.content_loc {
+                video = video.content_loc(content_loc)?;
+            }
+            if let Some(player_loc) = repr.player_loc {
+                video = video.player_loc(player_loc)?;
+            }
+            if let Some(duration) = repr.duration {
+                video = video.duration(duration)?;
+            }
+            if let Some(expiration_date) = repr.expiration_date {
+                video = video.expiration_date(expiration_date)?;
+            }
+            if let Some(rating) = repr.rating {
+                video = video.rating(rating)?;
+            }
+            if let Some(view_count) = repr.view_count {
+                video = video.view_count(view_count);
+            }
+            if let Some(publication_date) = repr.publication_date {
+                video = video.publication_date(publication_date)?;
+            }
+            for tag in repr.tags {
+                video = video.tag(tag)?;
+            }
+            if let Some(family_friendly) = repr.family_friendly {
+                video = video.family_friendly(family_friendly);
+            }
+            if let Some(restriction) = repr.restriction {
+                video = video.restriction(restriction.relationship, restriction.countries)?;
+            }
+            for price in repr.prices {
+                video = video.price(price);
+            }
+            if let Some(requires_subscription) = repr.requires_subscription {
+                video = video.requires_subscription(requires_subscription);
+            }
+            if let Some(uploader) = repr.uploader {
+                video = match uploader.info {
+                    Some(info) => {
+                        let info = info
+                            .parse::<Loc<'static>>()
+                            .map_err(|_| Error::InvalidVideoUploader)?;
+                        video.uploader_with_info(uploader.name, info)?
+                    }
+                    None => video.uploader(uploader.name)?,
+                };
+            }
+            if let Some(platform) = repr.platform {
+                video = video.platform(platform.relationship, platform.platforms)?;
+            }
+            if let Some(live) = repr.live {
+                video = video.live(live);
+            }
+            Ok(video)
+        };
+        video().map_err(|_| serde::de::Error::custom("invalid video"))
+    }
+}
+
+fn uploader_name<'a, S>(name: S) -> Result<Cow<'a, str>>
+where
+    S: Into<Cow<'a, str>>,
+{
+    let name = name.into();
+    if !is_text(&name) || name.chars().count() > Video::MAX_UPLOADER_LENGTH {
+        return Err(Error::InvalidVideoUploader.into());
+    }
+    Ok(name)
+}
+
+fn yes_no(b: bool) -> &'static str {
+    if b {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+fn text<'a, S>(s: S) -> Result<Cow<'a, str>>
+where
+    S: Into<Cow<'a, str>>,
+{
+    let s = s.into();
+    if !is_text(&s) {
+        return Err(Error::InvalidVideo.into());
+    }
+    Ok(s)
+}
+
+impl<'a> UrlExtension for Video<'a> {
+    fn namespace(&self) -> (&str, &str) {
+        (Self::PREFIX, Self::NAMESPACE_URI)
+    }
+
+    fn write(&self, writer: &mut ExtensionWriter<'_>) -> Result<()> {
+        self.validate()?;
+        writer
+            .start_tag("video")?
+            .element("thumbnail_loc", &self.thumbnail_loc)?
+            .element("title", &self.title)?
+            .element("description", &self.description)?;
+        if let Some(content_loc) = self.content_loc.as_ref() {
+            writer.element("content_loc", content_loc)?;
+        }
+        if let Some(player_loc) = self.player_loc.as_ref() {
+            writer.element("player_loc", player_loc)?;
+        }
+        if let Some(duration) = self.duration {
+            writer.element("duration", &duration.to_string())?;
+        }
+        if let Some(expiration_date) = self.expiration_date.as_ref() {
+            writer.element("expiration_date", expiration_date)?;
+        }
+        if let Some(rating) = self.rating.as_ref() {
+            writer.element("rating", rating)?;
+        }
+        if let Some(view_count) = self.view_count {
+            writer.element("view_count", &view_count.to_string())?;
+        }
+        if let Some(publication_date) = self.publication_date.as_ref() {
+            writer.element("publication_date", publication_date)?;
+        }
+        for tag in self.tags.iter() {
+            writer.element("tag", tag)?;
+        }
+        if let Some(family_friendly) = self.family_friendly {
+            writer.element("family_friendly", yes_no(family_friendly))?;
+        }
+        if let Some((relationship, content)) = self.restriction.as_ref() {
+            let attributes = [("relationship", relationship.as_str())];
+            writer.element_with_attributes("restriction", &attributes, content)?;
+        }
+        for price in self.prices.iter() {
+            let mut attributes = vec![("currency", price.currency.as_str())];
+            if let Some(price_type) = price.price_type {
+                attributes.push(("type", price_type.as_str()));
+            }
+            if let Some(resolution) = price.resolution {
+                attributes.push(("resolution", resolution.as_str()));
+            }
+            writer.element_with_attributes("price", &attributes, &price.amount)?;
+        }
+        if let Some(requires_subscription) = self.requires_subscription {
+            writer.element("requires_subscription", yes_no(requires_subscription))?;
+        }
+        if let Some((name, info)) = self.uploader.as_ref() {
+            match info {
+                Some(info) => {
+                    writer.element_with_attributes("uploader", &[("info", info)], name)?
+                }
+                None => writer.element("uploader", name)?,
+            };
+        }
+        if let Some((relationship, content)) = self.platform.as_ref() {
+            let attributes = [("relationship", relationship.as_str())];
+            writer.element_with_attributes("platform", &attributes, content)?;
+        }
+        if let Some(live) = self.live {
+            writer.element("live", yes_no(live))?;
+        }
+        writer.end_tag("video")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+    use crate::sitemap_xml_writer::SitemapXmlWriter;
+
+    fn video() -> Result<Video<'static>> {
+        Video::new("http://www.example.com/1.jpg", "Title", "Description")
+    }
+
+    #[test]
+    fn test_new() -> anyhow::Result<()> {
+        assert!(video().is_ok());
+        let thumbnail_loc = "http://www.example.com/1.jpg";
+        assert!(Video::new("a".repeat(2048).as_str(), "Title", "Description").is_err());
+        assert!(Video::new(thumbnail_loc, "", "Description").is_err());
+        assert!(Video::new(thumbnail_loc, "Title", " ").is_err());
+        assert!(Video::new(thumbnail_loc, "Title", "a\u{0}").is_err());
+        assert!(Video::new(thumbnail_loc, "Title", "a".repeat(2048)).is_ok());
+        assert!(matches!(
+            Video::new(thumbnail_loc, "Title", "a".repeat(2049)).map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideo)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate() -> anyhow::Result<()> {
+        assert!(video()?.validate().is_err());
+        assert!(video()?
+            .content_loc("http://www.example.com/1.mp4")?
+            .validate()
+            .is_ok());
+        assert!(video()?
+            .player_loc("http://www.example.com/player?v=1")?
+            .validate()
+            .is_ok());
+        assert!(video()?.content_loc("a".repeat(2048).as_str()).is_err());
+        assert!(video()?.player_loc("a".repeat(2048).as_str()).is_err());
+
+        let f = |publication_date: &str, expiration_date: &str| -> anyhow::Result<bool> {
+            Ok(video()?
+                .content_loc("http://www.example.com/1.mp4")?
+                .publication_date(publication_date)?
+                .expiration_date(expiration_date)?
+                .validate()
+                .is_ok())
+        };
+        assert!(f("2007-11-05", "2009-11-05")?);
+        assert!(f("2007-11-05", "2007-11-05")?);
+        assert!(f("2007-11-05T09:00:00+09:00", "2007-11-05")?);
+        assert!(f("2007-11-05T00:00:00.1Z", "2007-11-05T00:00:00.12Z")?);
+        assert!(!f("2009-11-05", "2007-11-05")?);
+        assert!(!f("2007-11-05T00:00:00Z", "2007-11-05T08:59:59+09:00")?);
+        assert!(!f("2007-11-05T00:00:00.5Z", "2007-11-05T00:00:00.12Z")?);
+        assert!(matches!(
+            video()?
+                .content_loc("http://www.example.com/1.mp4")?
+                .publication_date("2009-11-05")?
+                .expiration_date("2007-11-05")?
+                .validate()
+                .map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoExpirationDate)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_write() -> anyhow::Result<()> {
+        let v = video()?
+            .player_loc("http://www.example.com/player?v=1&w=2")?
+            .content_loc("http://www.example.com/1.mp4")?;
+        let mut xml_writer = SitemapXmlWriter::new(Vec::new(), false, false);
+        let mut writer = ExtensionWriter::new(&mut xml_writer, Video::PREFIX);
+        v.into_owned().write(&mut writer)?;
+        assert_eq!(
+            String::from_utf8(xml_writer.into_inner())?,
+            concat!(
+                "<video:video>",
+                "<video:thumbnail_loc>http://www.example.com/1.jpg</video:thumbnail_loc>",
+                "<video:title>Title</video:title>",
+                "<video:description>Description</video:description>",
+                "<video:content_loc>http://www.example.com/1.mp4</video:content_loc>",
+                "<video:player_loc>http://www.example.com/player?v=1&amp;w=2</video:player_loc>",
+                "</video:video>"
+            )
+        );
+
+        let mut xml_writer = SitemapXmlWriter::new(Vec::new(), false, false);
+        let mut writer = ExtensionWriter::new(&mut xml_writer, Video::PREFIX);
+        assert!(video()?.write(&mut writer).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_optional_child_entries() -> anyhow::Result<()> {
+        let v = video()?
+            .content_loc("http://www.example.com/1.mp4")?
+            .family_friendly(false)
+            .publication_date("2007-11-05T19:20:30+08:00")?
+            .view_count(12345)
+            .rating(4.2)?
+            .expiration_date("2009-11-05")?
+            .duration(600)?;
+        let mut xml_writer = SitemapXmlWriter::new(Vec::new(), false, false);
+        let mut writer = ExtensionWriter::new(&mut xml_writer, Video::PREFIX);
+        v.into_owned().write(&mut writer)?;
+        assert_eq!(
+            String::from_utf8(xml_writer.into_inner())?,
+            concat!(
+                "<video:video>",
+                "<video:thumbnail_loc>http://www.example.com/1.jpg</video:thumbnail_loc>",
+                "<video:title>Title</video:title>",
+                "<video:description>Description</video:description>",
+                "<video:content_loc>http://www.example.com/1.mp4</video:content_loc>",
+                "<video:duration>600</video:duration>",
+                "<video:expiration_date>2009-11-05</video:expiration_date>",
+                "<video:rating>4.2</video:rating>",
+                "<video:view_count>12345</video:view_count>",
+                "<video:publication_date>2007-11-05T19:20:30+08:00</video:publication_date>",
+                "<video:family_friendly>no</video:family_friendly>",
+                "</video:video>"
+            )
+        );
+
+        assert!(video()?.duration(1).is_ok());
+        assert!(video()?.duration(28_800).is_ok());
+        assert!(matches!(
+            video()?.duration(0).map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoDuration)
+        ));
+        assert!(matches!(
+            video()?.duration(28_801).map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoDuration)
+        ));
+        assert!(video()?.rating(0.0).is_ok());
+        assert!(video()?.rating(5.0).is_ok());
+        assert!(matches!(
+            video()?.rating(-0.1).map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoRating)
+        ));
+        assert!(matches!(
+            video()?.rating(5.1).map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoRating)
+        ));
+        assert!(matches!(
+            video()?.rating(f64::NAN).map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoRating)
+        ));
+        assert!(matches!(
+            video()?.expiration_date("2009-13-05").map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoExpirationDate)
+        ));
+        assert!(matches!(
+            video()?
+                .publication_date("2007-11-05T25:00:00+08:00")
+                .map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoPublicationDate)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_restriction_platform_price() -> anyhow::Result<()> {
+        let v = video()?
+            .content_loc("http://www.example.com/1.mp4")?
+            .restriction(VideoRelationship::Allow, ["ie", "GB", "Us"])?
+            .platform(
+                VideoRelationship::Deny,
+                [VideoPlatform::Mobile, VideoPlatform::Tv],
+            )?
+            .price(VideoPrice::new(1.99, "eur")?)
+            .price(
+                VideoPrice::new(2.5, "USD")?
+                    .price_type(VideoPriceType::Own)
+                    .resolution(VideoResolution::Hd),
+            );
+        let mut xml_writer = SitemapXmlWriter::new(Vec::new(), false, false);
+        let mut writer = ExtensionWriter::new(&mut xml_writer, Video::PREFIX);
+        v.write(&mut writer)?;
+        let actual = String::from_utf8(xml_writer.into_inner())?;
+        assert!(actual.ends_with(concat!(
+            r#"<video:content_loc>http://www.example.com/1.mp4</video:content_loc>"#,
+            r#"<video:restriction relationship="allow">IE GB US</video:restriction>"#,
+            r#"<video:price currency="EUR">1.99</video:price>"#,
+            r#"<video:price currency="USD" type="own" resolution="HD">2.5</video:price>"#,
+            r#"<video:platform relationship="deny">mobile tv</video:platform>"#,
+            r#"</video:video>"#
+        )));
+
+        assert!(matches!(
+            video()?
+                .restriction(VideoRelationship::Allow, Vec::<&str>::new())
+                .map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoRestriction)
+        ));
+        assert!(matches!(
+            video()?
+                .restriction(VideoRelationship::Deny, ["USA"])
+                .map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoRestriction)
+        ));
+        assert!(matches!(
+            video()?
+                .restriction(VideoRelationship::Deny, ["U1"])
+                .map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoRestriction)
+        ));
+        assert!(matches!(
+            video()?
+                .platform(VideoRelationship::Allow, [])
+                .map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoPlatform)
+        ));
+        assert!(matches!(
+            VideoPrice::new(-1.0, "USD").map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoPrice)
+        ));
+        assert!(matches!(
+            VideoPrice::new(f64::INFINITY, "USD").map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoPrice)
+        ));
+        assert!(matches!(
+            VideoPrice::new(1.0, "US").map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoPrice)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_live_requires_subscription_uploader() -> anyhow::Result<()> {
+        let v = video()?
+            .content_loc("http://www.example.com/1.mp4")?
+            .live(true)
+            .platform(VideoRelationship::Allow, [VideoPlatform::Web])?
+            .uploader_with_info("A & B", "http://www.example.com/users/ab?x=1&y=2")?
+            .requires_subscription(false)
+            .price(VideoPrice::new(1.0, "USD")?);
+        let mut xml_writer = SitemapXmlWriter::new(Vec::new(), false, false);
+        let mut writer = ExtensionWriter::new(&mut xml_writer, Video::PREFIX);
+        v.into_owned().write(&mut writer)?;
+        let actual = String::from_utf8(xml_writer.into_inner())?;
+        assert!(actual.ends_with(concat!(
+            r#"<video:price currency="USD">1</video:price>"#,
+            r#"<video:requires_subscription>no</video:requires_subscription>"#,
+            r#"<video:uploader info="http://www.example.com/users/ab?x=1&amp;y=2">A &amp; B</video:uploader>"#,
+            r#"<video:platform relationship="allow">web</video:platform>"#,
+            r#"<video:live>yes</video:live>"#,
+            r#"</video:video>"#
+        )));
+
+        let v = video()?
+            .player_loc("http://www.example.com/player")?
+            .uploader("GrillyMcGrillerson")?;
+        let mut xml_writer = SitemapXmlWriter::new(Vec::new(), false, false);
+        let mut writer = ExtensionWriter::new(&mut xml_writer, Video::PREFIX);
+        v.write(&mut writer)?;
+        let actual = String::from_utf8(xml_writer.into_inner())?;
+        assert!(actual.ends_with(concat!(
+            r#"<video:uploader>GrillyMcGrillerson</video:uploader>"#,
+            r#"</video:video>"#
+        )));
+
+        assert!(video()?.uploader("a".repeat(255)).is_ok());
+        assert!(matches!(
+            video()?.uploader("a".repeat(256)).map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoUploader)
+        ));
+        assert!(matches!(
+            video()?.uploader("").map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoUploader)
+        ));
+        assert!(matches!(
+            video()?
+                .uploader_with_info("a", "a".repeat(2048).as_str())
+                .map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoUploader)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_tag() -> anyhow::Result<()> {
+        let v = video()?
+            .content_loc("http://www.example.com/1.mp4")?
+            .family_friendly(true)
+            .tag("steak")?
+            .tag("meat")?
+            .publication_date("2007-11-05")?;
+        let mut xml_writer = SitemapXmlWriter::new(Vec::new(), false, false);
+        let mut writer = ExtensionWriter::new(&mut xml_writer, Video::PREFIX);
+        v.write(&mut writer)?;
+        let actual = String::from_utf8(xml_writer.into_inner())?;
+        assert!(actual.ends_with(concat!(
+            r#"<video:publication_date>2007-11-05</video:publication_date>"#,
+            r#"<video:tag>steak</video:tag>"#,
+            r#"<video:tag>meat</video:tag>"#,
+            r#"<video:family_friendly>yes</video:family_friendly>"#,
+            r#"</video:video>"#
+        )));
+
+        let mut v = video()?;
+        for i in 0..Video::MAX_NUMBER_OF_TAGS {
+            v = v.tag(i.to_string())?;
+        }
+        assert!(matches!(
+            v.clone().tag("a").map_err(|e| e.kind()),
+            Err(ErrorKind::MaxNumberOfVideoTags)
+        ));
+        assert!(matches!(
+            video()?.tag(" ").map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidVideoTag)
+        ));
+        Ok(())
+    }
+}
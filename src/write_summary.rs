@@ -0,0 +1,41 @@
+/// A summary of a written sitemap file or sitemap index file.
+///
+/// It is returned by [`crate::SitemapWriter::finish`] and
+/// [`crate::SitemapIndexWriter::finish`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WriteSummary {
+    number_of_entries: usize,
+    byte_length: usize,
+    truncated: bool,
+}
+
+impl WriteSummary {
+    pub(crate) fn new(number_of_entries: usize, byte_length: usize) -> Self {
+        Self {
+            number_of_entries,
+            byte_length,
+            truncated: false,
+        }
+    }
+
+    pub(crate) fn truncated(mut self) -> Self {
+        self.truncated = true;
+        self
+    }
+
+    /// Returns the number of written `url` or `sitemap` elements.
+    pub fn number_of_entries(&self) -> usize {
+        self.number_of_entries
+    }
+
+    /// Returns the number of written bytes.
+    pub fn byte_length(&self) -> usize {
+        self.byte_length
+    }
+
+    /// Returns `true` if the file was closed early by `abort_and_close` (e.g.
+    /// on shutdown) and may not contain all entries.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
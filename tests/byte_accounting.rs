@@ -0,0 +1,125 @@
+use sitemap_xml_writer::{
+    ChunkedWriter, Image, Sitemap, SitemapIndexWriterBuilder, SitemapWriterBuilder, Url, Video,
+    VideoPrice, VideoRelationship, WriteSummary,
+};
+
+use std::io::{BufWriter, Cursor, Write};
+
+/// A sink that counts the bytes accepted by the underlying writer, to
+/// cross-check the byte length counted by the writers.
+struct Counting<W> {
+    inner: W,
+    count: usize,
+}
+
+impl<W: Write> Counting<W> {
+    fn new(inner: W) -> Self {
+        Self { inner, count: 0 }
+    }
+}
+
+impl<W: Write> Write for Counting<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let l = self.inner.write(buf)?;
+        self.count += l;
+        Ok(l)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn audit(summary: &WriteSummary, counted: usize) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        summary.byte_length() == counted,
+        "byte length diverged: counted by the writer {}, observed by the sink {}",
+        summary.byte_length(),
+        counted
+    );
+    Ok(())
+}
+
+fn write_sitemaps<W: Write>(new_inner: impl Fn() -> W) -> anyhow::Result<()> {
+    for indent in [false, true] {
+        let mut writer = SitemapWriterBuilder::new()
+            .indent(indent)
+            .trailing_newline(indent)
+            .provenance_comments(true)
+            .loc_prefix("http://www.example.com/")
+            .namespace(Image::PREFIX, Image::NAMESPACE_URI)
+            .namespace(Video::PREFIX, Video::NAMESPACE_URI)
+            .start(Counting::new(new_inner()))?;
+        writer.write("http://www.example.com/?a=1&b='2'")?;
+        writer.write(
+            Url::loc("http://www.example.com/products/1")?
+                .lastmod("2005-01-01")?
+                .changefreq("monthly")?
+                .priority("0.8")?
+                .provenance("products.csv:1 -- <note>")
+                .image(Image::loc("http://www.example.com/1.jpg")?.caption("\"A\" & B")?)?
+                .video(
+                    Video::new("http://www.example.com/1.jpg", "Title", "Description")?
+                        .content_loc("http://www.example.com/1.mp4")?
+                        .restriction(VideoRelationship::Allow, ["US", "GB"])?
+                        .price(VideoPrice::new(1.99, "USD")?)
+                        .uploader_with_info("A", "http://www.example.com/users/a?x&y")?,
+                )?,
+        )?;
+        writer
+            .begin_url("http://example.org/other")?
+            .lastmod("2005-01-01")?;
+        let (inner, summary) = writer.reset(Counting::new(new_inner()))?;
+        audit(&summary, inner.count)?;
+
+        writer.write("http://www.example.com/after-reset")?;
+        let (inner, summary) = writer.finish()?;
+        audit(&summary, inner.count)?;
+    }
+    Ok(())
+}
+
+fn write_sitemap_indexes<W: Write>(new_inner: impl Fn() -> W) -> anyhow::Result<()> {
+    for indent in [false, true] {
+        let mut writer = SitemapIndexWriterBuilder::new()
+            .indent(indent)
+            .trailing_newline(indent)
+            .loc_prefix("http://www.example.com/")
+            .start(Counting::new(new_inner()))?;
+        writer.write("http://www.example.com/sitemap1.xml?a&b")?;
+        writer.write(
+            Sitemap::loc("http://example.org/sitemap2.xml.gz")?
+                .lastmod("2004-10-01T18:23:17+00:00")?,
+        )?;
+        let (inner, summary) = writer.reset(Counting::new(new_inner()))?;
+        audit(&summary, inner.count)?;
+
+        let (inner, summary) = writer.finish()?;
+        audit(&summary, inner.count)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_byte_accounting_cursor() -> anyhow::Result<()> {
+    write_sitemaps(|| Cursor::new(Vec::new()))?;
+    write_sitemap_indexes(|| Cursor::new(Vec::new()))?;
+    Ok(())
+}
+
+#[test]
+fn test_byte_accounting_chunked_writer() -> anyhow::Result<()> {
+    // `ChunkedWriter` accepts partial writes at chunk boundaries.
+    for chunk_size in [1, 7, 64] {
+        write_sitemaps(|| ChunkedWriter::new(chunk_size))?;
+        write_sitemap_indexes(|| ChunkedWriter::new(chunk_size))?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_byte_accounting_buf_writer() -> anyhow::Result<()> {
+    write_sitemaps(|| BufWriter::with_capacity(16, Vec::new()))?;
+    write_sitemap_indexes(|| BufWriter::with_capacity(16, Vec::new()))?;
+    Ok(())
+}
@@ -1,13 +1,19 @@
-use sitemap_xml_writer::{Lastmod, Loc, Sitemap, SitemapIndexWriter};
+use sitemap_xml_writer::{
+    ErrorKind, HostPolicy, HostPolicyAction, Lastmod, LastmodSkewGuard, Loc, SchemePolicy, Sitemap,
+    SitemapFormat, SitemapIndexWriter, SitemapIndexWriterBuilder,
+};
 
-use std::io::Cursor;
+use std::{
+    io::Cursor,
+    sync::{Arc, Mutex},
+};
 
 #[test]
 fn test_sitemap_index_writer_start_with_indent() -> anyhow::Result<()> {
     let mut writer = SitemapIndexWriter::start_with_indent(Cursor::new(Vec::new()))?;
     writer.write("http://www.example.com/sitemap1.xml.gz")?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
 <sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
   <sitemap>
@@ -22,8 +28,8 @@ fn test_sitemap_index_writer_start_with_indent() -> anyhow::Result<()> {
 fn test_sitemap_index_writer_write_str() -> anyhow::Result<()> {
     let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
     writer.write("http://www.example.com/sitemap1.xml.gz")?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     let expected = concat!(
         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
         r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -44,8 +50,8 @@ fn test_sitemap_index_writer_write_sitemap() -> anyhow::Result<()> {
             .lastmod("2004-10-01T18:23:17+00:00")?,
     )?;
     writer.write(Sitemap::loc("http://www.example.com/sitemap2.xml.gz")?.lastmod("2005-01-01")?)?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     // Sample XML Sitemap Index in <https://sitemaps.org/protocol.html>
     let expected = concat!(
         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
@@ -77,8 +83,8 @@ fn test_sitemap_index_writer_write_sitemap_with_url_feature() -> anyhow::Result<
         Sitemap::loc(::url::Url::parse("http://www.example.com/sitemap2.xml.gz")?)?
             .lastmod("2005-01-01")?,
     )?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     // Sample XML Sitemap Index in <https://sitemaps.org/protocol.html>
     let expected = concat!(
         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
@@ -116,8 +122,8 @@ fn test_sitemap_index_writer_write_sitemap_with_chrono_feature() -> anyhow::Resu
                 "%Y-%m-%d",
             )?)?,
     )?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     // Sample XML Sitemap Index in <https://sitemaps.org/protocol.html>
     let expected = concat!(
         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
@@ -151,15 +157,15 @@ fn test_sitemap_index_writer_write_sitemap_with_time_feature() -> anyhow::Result
             // `::time::Date` support
             .lastmod(::time::macros::date!(2005-01-01))?,
     )?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     // Sample XML Sitemap Index in <https://sitemaps.org/protocol.html>
     let expected = concat!(
         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
         r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
         r#"<sitemap>"#,
         r#"<loc>http://www.example.com/sitemap1.xml.gz</loc>"#,
-        r#"<lastmod>2004-10-01T18:23:17.000000000Z</lastmod>"#,
+        r#"<lastmod>2004-10-01T18:23:17+00:00</lastmod>"#,
         r#"</sitemap>"#,
         r#"<sitemap>"#,
         r#"<loc>http://www.example.com/sitemap2.xml.gz</loc>"#,
@@ -178,8 +184,8 @@ fn test_sitemap_index_writer_write_sitemap_typed() -> anyhow::Result<()> {
         Sitemap::loc(Loc::try_from("http://www.example.com/sitemap1.xml.gz")?)?
             .lastmod(Lastmod::try_from("2004-10-01T18:23:17+00:00")?)?,
     )?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     let expected = concat!(
         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
         r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -197,8 +203,8 @@ fn test_sitemap_index_writer_write_sitemap_typed() -> anyhow::Result<()> {
 fn test_sitemap_index_writer_write_sitemap_loc_only() -> anyhow::Result<()> {
     let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
     writer.write(Sitemap::loc("http://www.example.com/sitemap1.xml.gz")?)?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     let expected = concat!(
         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
         r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -241,12 +247,82 @@ fn test_sitemap_index_writer_max_byte_length() -> anyhow::Result<()> {
     writer.write(url2.as_str())?;
     writer.end()?;
 
+    // the closing tag always fits, so the entry that does not fit is
+    // rejected as a whole
     let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
     for _ in 0..48_545 {
         writer.write(url.as_str())?;
     }
-    writer.write(url3.as_str())?;
-    assert!(writer.end().is_err());
+    assert_eq!(
+        writer.write(url3.as_str()).err().map(|e| e.kind()),
+        Some(ErrorKind::MaxByteLength)
+    );
+    assert_eq!(
+        writer
+            .write(Sitemap::loc(url2.as_str())?.lastmod("2005-01-01")?)
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::MaxByteLength)
+    );
+    let (inner, summary) = writer.abort_and_close()?;
+    assert_eq!(summary.number_of_entries(), 48_545);
+    let actual = inner.into_inner();
+    assert_eq!(actual.len(), 52_428_719);
+    assert!(actual
+        .ends_with(format!("<sitemap><loc>{}</loc></sitemap></sitemapindex>", url).as_bytes()));
+    Ok(())
+}
+
+/// A writer that fails while `fail` is set, without writing anything.
+struct FlakyWriter {
+    written: Vec<u8>,
+    fail: std::rc::Rc<std::cell::Cell<bool>>,
+}
+
+impl std::io::Write for FlakyWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.fail.get() {
+            return Err(std::io::Error::new(std::io::ErrorKind::Other, "flaky"));
+        }
+        self.written.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_sitemap_index_writer_io_error_partway_through_sitemap() -> anyhow::Result<()> {
+    let fail = std::rc::Rc::new(std::cell::Cell::new(false));
+    let mut writer = SitemapIndexWriter::start(FlakyWriter {
+        written: Vec::new(),
+        fail: fail.clone(),
+    })?;
+    writer.write("http://www.example.com/sitemap1.xml")?;
+    fail.set(true);
+    assert_eq!(
+        writer
+            .write(Sitemap::loc("http://www.example.com/sitemap2.xml")?.lastmod("2005-01-01")?)
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::Io)
+    );
+    fail.set(false);
+    let (inner, summary) = writer.abort_and_close()?;
+    assert_eq!(summary.number_of_entries(), 1);
+    assert_eq!(summary.byte_length(), inner.written.len());
+    assert_eq!(
+        String::from_utf8(inner.written)?,
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+            r#"<sitemap>"#,
+            r#"<loc>http://www.example.com/sitemap1.xml</loc>"#,
+            r#"</sitemap>"#,
+            r#"</sitemapindex>"#
+        )
+    );
     Ok(())
 }
 
@@ -268,3 +344,462 @@ fn test_sitemap_index_writer_max_number_of_urls() -> anyhow::Result<()> {
     writer.end()?;
     Ok(())
 }
+
+#[test]
+fn test_sitemap_index_writer_abort_and_close_after_end_or_error() -> anyhow::Result<()> {
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<sitemap>"#,
+        r#"<loc>http://www.example.com/sitemap1.xml</loc>"#,
+        r#"</sitemap>"#,
+        r#"</sitemapindex>"#
+    );
+    let too_long = format!("http://www.example.com/{}", "a".repeat(2048));
+
+    let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/sitemap1.xml")?;
+    writer.end()?;
+    let (inner, summary) = writer.abort_and_close()?;
+    assert_eq!(String::from_utf8(inner.into_inner())?, expected);
+    assert!(summary.is_truncated());
+    assert_eq!(summary.number_of_entries(), 1);
+
+    let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/sitemap1.xml")?;
+    assert!(writer.write(too_long.as_str()).is_err());
+    let (inner, summary) = writer.abort_and_close()?;
+    assert_eq!(String::from_utf8(inner.into_inner())?, expected);
+    assert_eq!(summary.number_of_entries(), 1);
+
+    let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
+    writer.extend(["http://www.example.com/sitemap1.xml", too_long.as_str()]);
+    let (inner, summary) = writer.abort_and_close()?;
+    assert_eq!(String::from_utf8(inner.into_inner())?, expected);
+    assert_eq!(summary.number_of_entries(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_index_writer_get_ref_and_get_mut() -> anyhow::Result<()> {
+    let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
+    let position = writer.get_ref().position();
+    writer.write("http://www.example.com/sitemap1.xml.gz")?;
+    assert!(writer.get_ref().position() > position);
+    writer.get_mut().get_mut().clear();
+    writer.get_mut().set_position(0);
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    assert_eq!(actual, "</sitemapindex>");
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_index_writer_finish_sync() -> anyhow::Result<()> {
+    let path =
+        std::env::temp_dir().join("sitemap-xml-writer-test-sitemap-index-writer-finish-sync.xml");
+    let mut writer =
+        SitemapIndexWriter::start(std::io::BufWriter::new(std::fs::File::create(&path)?))?;
+    writer.write("http://www.example.com/sitemap1.xml.gz")?;
+    writer.finish_sync()?;
+    let actual = std::fs::read_to_string(&path)?;
+    std::fs::remove_file(&path)?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<sitemap>"#,
+        r#"<loc>http://www.example.com/sitemap1.xml.gz</loc>"#,
+        r#"</sitemap>"#,
+        r#"</sitemapindex>"#
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_index_writer_finish() -> anyhow::Result<()> {
+    let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/sitemap1.xml.gz")?;
+    writer.write("http://www.example.com/sitemap2.xml.gz")?;
+    let (inner, summary) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<sitemap>"#,
+        r#"<loc>http://www.example.com/sitemap1.xml.gz</loc>"#,
+        r#"</sitemap>"#,
+        r#"<sitemap>"#,
+        r#"<loc>http://www.example.com/sitemap2.xml.gz</loc>"#,
+        r#"</sitemap>"#,
+        r#"</sitemapindex>"#
+    );
+    assert_eq!(actual, expected);
+    assert_eq!(summary.number_of_entries(), 2);
+    assert_eq!(summary.byte_length(), expected.len());
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_index_writer_end_and_finish() -> anyhow::Result<()> {
+    let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/sitemap1.xml.gz")?;
+    writer.end()?;
+    writer.end()?;
+    assert_eq!(
+        writer
+            .write("http://www.example.com/sitemap2.xml.gz")
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::InvalidElementOrder)
+    );
+    let (inner, summary) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<sitemap>"#,
+        r#"<loc>http://www.example.com/sitemap1.xml.gz</loc>"#,
+        r#"</sitemap>"#,
+        r#"</sitemapindex>"#
+    );
+    assert_eq!(actual, expected);
+    assert_eq!(summary.number_of_entries(), 1);
+    assert_eq!(summary.byte_length(), expected.len());
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_index_writer_builder_trailing_newline() -> anyhow::Result<()> {
+    let mut writer = SitemapIndexWriterBuilder::new()
+        .trailing_newline(true)
+        .start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/sitemap1.xml.gz")?;
+    let (inner, summary) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<sitemap>"#,
+        r#"<loc>http://www.example.com/sitemap1.xml.gz</loc>"#,
+        r#"</sitemap>"#,
+        "</sitemapindex>\n"
+    );
+    assert_eq!(actual, expected);
+    assert_eq!(summary.byte_length(), expected.len());
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_index_writer_builder_host_policy() -> anyhow::Result<()> {
+    let policy = HostPolicy::new().deny_host("staging.example.com");
+    let mut writer = SitemapIndexWriterBuilder::new()
+        .host_policy(policy.clone())
+        .start(Cursor::new(Vec::new()))?;
+    assert!(writer
+        .write("http://staging.example.com/sitemap1.xml.gz")
+        .is_err());
+
+    let mut writer = SitemapIndexWriterBuilder::new()
+        .host_policy(policy.on_violation(HostPolicyAction::Skip))
+        .start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/sitemap1.xml.gz")?;
+    writer.write("http://staging.example.com/sitemap1.xml.gz")?;
+    let (inner, summary) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<sitemap>"#,
+        r#"<loc>http://www.example.com/sitemap1.xml.gz</loc>"#,
+        r#"</sitemap>"#,
+        r#"</sitemapindex>"#
+    );
+    assert_eq!(actual, expected);
+    assert_eq!(summary.number_of_entries(), 1);
+    Ok(())
+}
+
+#[cfg(feature = "unicode-normalization")]
+#[test]
+fn test_sitemap_index_writer_builder_normalize_locs() -> anyhow::Result<()> {
+    let mut writer = SitemapIndexWriterBuilder::new()
+        .normalize_locs(true)
+        .start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/cafe\u{301}.xml")?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<sitemap>"#,
+        "<loc>http://www.example.com/caf\u{e9}.xml</loc>",
+        r#"</sitemap>"#,
+        r#"</sitemapindex>"#
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[cfg(feature = "url")]
+#[test]
+fn test_sitemap_index_writer_builder_canonicalize_locs() -> anyhow::Result<()> {
+    let mut writer = SitemapIndexWriterBuilder::new()
+        .canonicalize_locs(true)
+        .start(Cursor::new(Vec::new()))?;
+    writer.write("HTTP://WWW.EXAMPLE.COM/sitemap%2d1.xml")?;
+    assert!(writer.write("sitemap1.xml").is_err());
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<sitemap>"#,
+        r#"<loc>http://www.example.com/sitemap-1.xml</loc>"#,
+        r#"</sitemap>"#,
+        r#"</sitemapindex>"#
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_index_writer_write_sitemap_format() -> anyhow::Result<()> {
+    let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
+    writer
+        .write(Sitemap::loc("http://www.example.com/sitemap1.xml")?.format(SitemapFormat::Xml)?)?;
+    writer.write(Sitemap::loc("http://www.example.com/sitemap2.txt")?.format("txt")?)?;
+    writer.write(Sitemap::loc("http://www.example.com/feed.rss")?.format("rss")?)?;
+    assert!(Sitemap::loc("http://www.example.com/sitemap3.html")?
+        .format("html")
+        .is_err());
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<sitemap>"#,
+        r#"<loc>http://www.example.com/sitemap1.xml</loc>"#,
+        r#"</sitemap>"#,
+        r#"<sitemap>"#,
+        r#"<loc>http://www.example.com/sitemap2.txt</loc>"#,
+        r#"</sitemap>"#,
+        r#"<sitemap>"#,
+        r#"<loc>http://www.example.com/feed.rss</loc>"#,
+        r#"</sitemap>"#,
+        r#"</sitemapindex>"#
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_index_writer_write_sitemap_ref() -> anyhow::Result<()> {
+    let sitemap = Sitemap::loc("http://www.example.com/sitemap1.xml.gz")?.lastmod("2005-01-01")?;
+    let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
+    writer.write(&sitemap)?;
+    writer.write(sitemap)?;
+    let (inner, summary) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let sitemap = concat!(
+        r#"<sitemap>"#,
+        r#"<loc>http://www.example.com/sitemap1.xml.gz</loc>"#,
+        r#"<lastmod>2005-01-01</lastmod>"#,
+        r#"</sitemap>"#,
+    );
+    let expected = [
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        sitemap,
+        sitemap,
+        r#"</sitemapindex>"#,
+    ]
+    .concat();
+    assert_eq!(actual, expected);
+    assert_eq!(summary.number_of_entries(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_index_writer_write_sitemap_infallible() -> anyhow::Result<()> {
+    let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
+    writer.write(
+        Sitemap::new(Loc::try_from("http://www.example.com/sitemap1.xml.gz")?)
+            .with_lastmod(Lastmod::try_from("2004-10-01T18:23:17+00:00")?),
+    )?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<sitemap>"#,
+        r#"<loc>http://www.example.com/sitemap1.xml.gz</loc>"#,
+        r#"<lastmod>2004-10-01T18:23:17+00:00</lastmod>"#,
+        r#"</sitemap>"#,
+        r#"</sitemapindex>"#
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_index_writer_builder_escape_audit() -> anyhow::Result<()> {
+    let escaped = Arc::new(Mutex::new(Vec::new()));
+    let mut writer = SitemapIndexWriterBuilder::new()
+        .escape_audit({
+            let escaped = Arc::clone(&escaped);
+            move |audit| {
+                escaped
+                    .lock()
+                    .unwrap()
+                    .push((audit.loc().to_string(), audit.escaped().collect::<String>()))
+            }
+        })
+        .start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/sitemap.xml?a=1")?;
+    writer.write("http://www.example.com/sitemap.xml?a=1&b='2'")?;
+    writer.end()?;
+    assert_eq!(
+        *escaped.lock().unwrap(),
+        [(
+            "http://www.example.com/sitemap.xml?a=1&b='2'".to_string(),
+            "&''".to_string()
+        )]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_index_writer_builder_scheme_policy() -> anyhow::Result<()> {
+    let mut writer = SitemapIndexWriterBuilder::new()
+        .scheme_policy(SchemePolicy::http())
+        .start(Cursor::new(Vec::new()))?;
+    writer.write("https://www.example.com/sitemap.xml")?;
+    let e = writer
+        .write("ftp://ftp.example.com/sitemap.xml")
+        .unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::SchemeNotAllowed);
+    assert_eq!(e.context().entry_index(), Some(1));
+    assert_eq!(
+        e.to_string(),
+        "sitemap index writer: entry 1: scheme not allowed"
+    );
+    let (_, summary) = writer.finish()?;
+    assert_eq!(summary.number_of_entries(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_index_writer_builder_lastmod_skew_guard() -> anyhow::Result<()> {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    // 2005-01-01T00:00:00Z
+    let now = UNIX_EPOCH + Duration::from_secs(1_104_537_600);
+    let guard = LastmodSkewGuard::new(Duration::from_secs(86_400)).now(now);
+    let mut writer = SitemapIndexWriterBuilder::new()
+        .lastmod_skew_guard(guard)
+        .start(Cursor::new(Vec::new()))?;
+    writer.write(Sitemap::loc("http://www.example.com/sitemap1.xml")?.lastmod("2005-01-02")?)?;
+    assert_eq!(
+        writer
+            .write(Sitemap::loc("http://www.example.com/sitemap2.xml")?.lastmod("2005-01-03")?)
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::FutureLastmod)
+    );
+    // an unchecked invalid value is not reported as a future lastmod
+    assert_eq!(
+        writer
+            .write(
+                Sitemap::loc("http://www.example.com/sitemap3.xml")?
+                    .with_lastmod(Lastmod::new_unchecked("2005-02-30"))
+            )
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::InvalidLastmod)
+    );
+    let (_, summary) = writer.finish()?;
+    assert_eq!(summary.number_of_entries(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_index_writer_builder_self_loc() -> anyhow::Result<()> {
+    let mut writer = SitemapIndexWriterBuilder::new()
+        .self_loc("HTTP://WWW.Example.COM./sitemap_index.xml")
+        .start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/sitemap1.xml.gz")?;
+    assert!(writer
+        .write("http://www.example.com/sitemap_index.xml")
+        .is_err());
+    assert!(writer
+        .write("http://www.example.com./sitemap_index.xml")
+        .is_err());
+    let (_, summary) = writer.finish()?;
+    assert_eq!(summary.number_of_entries(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_index_writer_reset() -> anyhow::Result<()> {
+    let mut writer = SitemapIndexWriterBuilder::new()
+        .indent(true)
+        .start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/sitemap1.xml")?;
+    let (first, first_summary) = writer.reset(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/sitemap2.xml")?;
+    let (second, second_summary) = writer.finish()?;
+    let first = String::from_utf8(first.into_inner())?;
+    let second = String::from_utf8(second.into_inner())?;
+    assert_eq!(first, second.replace("sitemap2.xml", "sitemap1.xml"));
+    assert_eq!(first_summary.byte_length(), first.len());
+    assert_eq!(first_summary, second_summary);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_index_writer_reset_after_end() -> anyhow::Result<()> {
+    let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/sitemap1.xml")?;
+    writer.end()?;
+    let (first, first_summary) = writer.reset(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/sitemap2.xml")?;
+    let (second, second_summary) = writer.finish()?;
+    let first = String::from_utf8(first.into_inner())?;
+    let second = String::from_utf8(second.into_inner())?;
+    assert_eq!(first, second.replace("sitemap2.xml", "sitemap1.xml"));
+    assert_eq!(first.matches("</sitemapindex>").count(), 1);
+    assert_eq!(first_summary, second_summary);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_index_writer_extend() -> anyhow::Result<()> {
+    let mut writer = SitemapIndexWriter::start(Cursor::new(Vec::new()))?;
+    writer.extend(["http://www.example.com/sitemap1.xml"]);
+    let sitemap = Sitemap::loc("http://www.example.com/sitemap2.xml")?.lastmod("2005-01-01")?;
+    writer.extend([&sitemap]);
+    writer.deferred_result()?;
+
+    let too_long = format!("http://www.example.com/{}", "a".repeat(2048));
+    writer.extend([too_long.as_str(), "http://www.example.com/sitemap3.xml"]);
+    writer.extend(["http://www.example.com/sitemap4.xml"]);
+    assert_eq!(
+        writer.deferred_result().err().map(|e| e.kind()),
+        Some(ErrorKind::InvalidLoc)
+    );
+    writer.extend(vec![Sitemap::loc("http://www.example.com/sitemap5.xml")?]);
+    let (inner, summary) = writer.finish()?;
+    assert_eq!(summary.number_of_entries(), 3);
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<sitemap><loc>http://www.example.com/sitemap1.xml</loc></sitemap>"#,
+        r#"<sitemap><loc>http://www.example.com/sitemap2.xml</loc><lastmod>2005-01-01</lastmod></sitemap>"#,
+        r#"<sitemap><loc>http://www.example.com/sitemap5.xml</loc></sitemap>"#,
+        r#"</sitemapindex>"#
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
@@ -1,4 +1,8 @@
-use sitemap_xml_writer::{Changefreq, Lastmod, Loc, Priority, SitemapWriter, Url};
+use sitemap_xml_writer::{
+    Alternate, AlternateCluster, Changefreq, Error, ErrorKind, ExtensionWriter, GoogleNewsProfile,
+    HostPolicy, HostPolicyAction, Image, Lastmod, LastmodSkewAction, LastmodSkewGuard, Loc, News,
+    Priority, SitemapWriter, SitemapWriterBuilder, Url, UrlExtension, Video,
+};
 
 use std::io::Cursor;
 
@@ -6,8 +10,8 @@ use std::io::Cursor;
 fn test_sitemap_writer_start_with_indent() -> anyhow::Result<()> {
     let mut writer = SitemapWriter::start_with_indent(Cursor::new(Vec::new()))?;
     writer.write("http://www.example.com/")?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
 <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
   <url>
@@ -22,8 +26,8 @@ fn test_sitemap_writer_start_with_indent() -> anyhow::Result<()> {
 fn test_sitemap_writer_write_str() -> anyhow::Result<()> {
     let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
     writer.write("http://www.example.com/")?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     let expected = concat!(
         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -63,8 +67,8 @@ fn test_sitemap_writer_write_url() -> anyhow::Result<()> {
         Url::loc("http://www.example.com/catalog?item=83&desc=vacation_usa")?
             .lastmod("2004-11-23")?,
     )?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     // Sample XML Sitemap in <https://sitemaps.org/protocol.html>
     let expected = concat!(
         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
@@ -132,8 +136,8 @@ fn test_sitemap_writer_write_url_with_url_feature() -> anyhow::Result<()> {
         Url::loc("http://www.example.com/catalog?item=83&desc=vacation_usa")?
             .lastmod("2004-11-23")?,
     )?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     // Sample XML Sitemap in <https://sitemaps.org/protocol.html>
     let expected = concat!(
         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
@@ -201,8 +205,8 @@ fn test_sitemap_writer_write_url_with_chrono_feature() -> anyhow::Result<()> {
         Url::loc("http://www.example.com/catalog?item=83&desc=vacation_usa")?
             .lastmod("2004-11-23")?,
     )?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     // Sample XML Sitemap in <https://sitemaps.org/protocol.html>
     let expected = concat!(
         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
@@ -269,8 +273,8 @@ fn test_sitemap_writer_write_url_with_time_feature() -> anyhow::Result<()> {
         Url::loc("http://www.example.com/catalog?item=83&desc=vacation_usa")?
             .lastmod("2004-11-23")?,
     )?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     // Sample XML Sitemap in <https://sitemaps.org/protocol.html>
     let expected = concat!(
         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
@@ -293,7 +297,7 @@ fn test_sitemap_writer_write_url_with_time_feature() -> anyhow::Result<()> {
         r#"<url>"#,
         r#"<loc>http://www.example.com/catalog?item=74&amp;desc=vacation_newfoundland</loc>"#,
         // r#"<lastmod>2004-12-23T18:00:15+00:00</lastmod>"#,
-        r#"<lastmod>2004-12-23T18:00:15.000000000Z</lastmod>"#,
+        r#"<lastmod>2004-12-23T18:00:15+00:00</lastmod>"#,
         r#"<priority>0.3</priority>"#,
         r#"</url>"#,
         r#"<url>"#,
@@ -315,8 +319,8 @@ fn test_sitemap_writer_write_url_typed() -> anyhow::Result<()> {
             .changefreq(Changefreq::try_from("monthly")?)?
             .priority(Priority::try_from("0.8")?)?,
     )?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     let expected = concat!(
         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -336,8 +340,8 @@ fn test_sitemap_writer_write_url_typed() -> anyhow::Result<()> {
 fn test_sitemap_writer_write_url_loc() -> anyhow::Result<()> {
     let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
     writer.write(Url::loc("http://www.example.com/")?)?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     let expected = concat!(
         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -354,8 +358,8 @@ fn test_sitemap_writer_write_url_loc() -> anyhow::Result<()> {
 fn test_sitemap_writer_write_url_lastmod() -> anyhow::Result<()> {
     let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
     writer.write(Url::loc("http://www.example.com/")?.lastmod("2005-01-01")?)?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     let expected = concat!(
         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -373,8 +377,8 @@ fn test_sitemap_writer_write_url_lastmod() -> anyhow::Result<()> {
 fn test_sitemap_writer_write_url_changefreq() -> anyhow::Result<()> {
     let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
     writer.write(Url::loc("http://www.example.com/")?.changefreq("monthly")?)?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     let expected = concat!(
         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -392,8 +396,8 @@ fn test_sitemap_writer_write_url_changefreq() -> anyhow::Result<()> {
 fn test_sitemap_writer_write_url_priority() -> anyhow::Result<()> {
     let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
     writer.write(Url::loc("http://www.example.com/")?.priority("0.8")?)?;
-    writer.end()?;
-    let actual = String::from_utf8(writer.into_inner().into_inner())?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
     let expected = concat!(
         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
         r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
@@ -437,12 +441,118 @@ fn test_sitemap_writer_max_byte_length() -> anyhow::Result<()> {
     writer.write(url2.as_str())?;
     writer.end()?;
 
+    // the closing tag always fits, so the entry that does not fit is
+    // rejected as a whole
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    for _ in 0..48_545 {
+        writer.write(url.as_str())?;
+    }
+    assert_eq!(
+        writer.write(url3.as_str()).err().map(|e| e.kind()),
+        Some(ErrorKind::MaxByteLength)
+    );
+    let (inner, summary) = writer.abort_and_close()?;
+    assert_eq!(summary.number_of_entries(), 48_545);
+    assert_eq!(summary.byte_length(), 52_428_707);
+    let actual = inner.into_inner();
+    assert_eq!(actual.len(), 52_428_707);
+    assert!(actual.ends_with(format!("<url><loc>{}</loc></url></urlset>", url).as_bytes()));
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_max_byte_length_partway_through_url() -> anyhow::Result<()> {
+    // 107 + 1_080 * 48_545 + 93 = 52_428_800
+    let url = format!("http://www.example.com/{}", "x".repeat(1035));
+    let url2 = format!("http://www.example.com/{}", "x".repeat(48));
     let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
     for _ in 0..48_545 {
         writer.write(url.as_str())?;
     }
-    writer.write(url3.as_str())?;
-    assert!(writer.end().is_err());
+    assert_eq!(
+        writer
+            .write(Url::loc(url2.as_str())?.priority("0.5")?)
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::MaxByteLength)
+    );
+    let mut url_writer = writer.begin_url(url2.as_str())?;
+    assert_eq!(
+        url_writer.priority("0.5").err().map(|e| e.kind()),
+        Some(ErrorKind::MaxByteLength)
+    );
+    // the `url` element has been discarded
+    assert_eq!(
+        url_writer.changefreq("daily").err().map(|e| e.kind()),
+        Some(ErrorKind::InvalidElementOrder)
+    );
+    assert!(url_writer.end().is_err());
+    let (inner, summary) = writer.abort_and_close()?;
+    assert_eq!(summary.number_of_entries(), 48_545);
+    let actual = String::from_utf8(inner.into_inner())?;
+    assert!(actual.ends_with(&format!("<url><loc>{}</loc></url></urlset>", url)));
+    Ok(())
+}
+
+/// A writer that fails while `fail` is set, without writing anything.
+struct FlakyWriter {
+    written: Vec<u8>,
+    fail: std::rc::Rc<std::cell::Cell<bool>>,
+}
+
+impl std::io::Write for FlakyWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.fail.get() {
+            return Err(std::io::Error::new(std::io::ErrorKind::Other, "flaky"));
+        }
+        self.written.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_sitemap_writer_io_error_partway_through_url() -> anyhow::Result<()> {
+    let fail = std::rc::Rc::new(std::cell::Cell::new(false));
+    let mut writer = SitemapWriterBuilder::new()
+        .namespace(Image::PREFIX, Image::NAMESPACE_URI)
+        .start(FlakyWriter {
+            written: Vec::new(),
+            fail: fail.clone(),
+        })?;
+    writer.write("http://www.example.com/1")?;
+    fail.set(true);
+    assert_eq!(
+        writer
+            .write(
+                Url::loc("http://www.example.com/2")?
+                    .lastmod("2005-01-01")?
+                    .image(Image::loc("http://www.example.com/2.jpg")?)?
+            )
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::Io)
+    );
+    let mut url = writer.begin_url("http://www.example.com/3")?;
+    url.lastmod("2005-01-01")?;
+    assert_eq!(url.end().err().map(|e| e.kind()), Some(ErrorKind::Io));
+    fail.set(false);
+    let (inner, summary) = writer.abort_and_close()?;
+    assert_eq!(summary.number_of_entries(), 1);
+    assert_eq!(summary.byte_length(), inner.written.len());
+    assert_eq!(
+        String::from_utf8(inner.written)?,
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:image="http://www.google.com/schemas/sitemap-image/1.1">"#,
+            r#"<url>"#,
+            r#"<loc>http://www.example.com/1</loc>"#,
+            r#"</url>"#,
+            r#"</urlset>"#
+        )
+    );
     Ok(())
 }
 
@@ -462,3 +572,1240 @@ fn test_sitemap_writer_max_number_of_urls() -> anyhow::Result<()> {
     writer.end()?;
     Ok(())
 }
+
+#[test]
+fn test_sitemap_writer_begin_url() -> anyhow::Result<()> {
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    let mut url = writer.begin_url("http://www.example.com/")?;
+    url.lastmod("2005-01-01")?
+        .changefreq("monthly")?
+        .priority("0.8")?;
+    url.end()?;
+    let mut url =
+        writer.begin_url("http://www.example.com/catalog?item=12&desc=vacation_hawaii")?;
+    url.changefreq(Changefreq::Monthly)?;
+    url.end()?;
+    writer.begin_url("http://www.example.com/about")?.end()?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/</loc>"#,
+        r#"<lastmod>2005-01-01</lastmod>"#,
+        r#"<changefreq>monthly</changefreq>"#,
+        r#"<priority>0.8</priority>"#,
+        r#"</url>"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/catalog?item=12&amp;desc=vacation_hawaii</loc>"#,
+        r#"<changefreq>monthly</changefreq>"#,
+        r#"</url>"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/about</loc>"#,
+        r#"</url>"#,
+        r#"</urlset>"#
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_begin_url_invalid_element_order() -> anyhow::Result<()> {
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    let mut url = writer.begin_url("http://www.example.com/")?;
+    url.changefreq("monthly")?;
+    assert!(url.lastmod("2005-01-01").is_err());
+    assert!(url.changefreq("monthly").is_err());
+    url.priority("0.8")?;
+    assert!(url.priority("0.8").is_err());
+    url.end()?;
+    assert!(writer
+        .begin_url("invalid loc".repeat(2048).as_str())
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_begin_url_without_end() -> anyhow::Result<()> {
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    writer.begin_url("http://www.example.com/1")?;
+    writer.begin_url("http://www.example.com/2")?;
+    writer.write("http://www.example.com/3")?;
+    writer.begin_url("http://www.example.com/4")?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<url><loc>http://www.example.com/1</loc></url>"#,
+        r#"<url><loc>http://www.example.com/2</loc></url>"#,
+        r#"<url><loc>http://www.example.com/3</loc></url>"#,
+        r#"<url><loc>http://www.example.com/4</loc></url>"#,
+        r#"</urlset>"#
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_get_ref_and_get_mut() -> anyhow::Result<()> {
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    let position = writer.get_ref().position();
+    writer.write("http://www.example.com/")?;
+    assert!(writer.get_ref().position() > position);
+    writer.get_mut().get_mut().clear();
+    writer.get_mut().set_position(0);
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    assert_eq!(actual, "</urlset>");
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_finish_sync() -> anyhow::Result<()> {
+    let path = std::env::temp_dir().join("sitemap-xml-writer-test-sitemap-writer-finish-sync.xml");
+    let mut writer = SitemapWriter::start(std::io::BufWriter::new(std::fs::File::create(&path)?))?;
+    writer.write("http://www.example.com/")?;
+    writer.finish_sync()?;
+    let actual = std::fs::read_to_string(&path)?;
+    std::fs::remove_file(&path)?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/</loc>"#,
+        r#"</url>"#,
+        r#"</urlset>"#
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_finish() -> anyhow::Result<()> {
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/")?;
+    writer.write("http://www.example.com/about")?;
+    let (inner, summary) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/</loc>"#,
+        r#"</url>"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/about</loc>"#,
+        r#"</url>"#,
+        r#"</urlset>"#
+    );
+    assert_eq!(actual, expected);
+    assert_eq!(summary.number_of_entries(), 2);
+    assert_eq!(summary.byte_length(), expected.len());
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_end_and_finish() -> anyhow::Result<()> {
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/")?;
+    writer.end()?;
+    writer.end()?;
+    assert_eq!(
+        writer
+            .write("http://www.example.com/about")
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::InvalidElementOrder)
+    );
+    assert_eq!(
+        writer
+            .begin_url("http://www.example.com/about")
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::InvalidElementOrder)
+    );
+    let (inner, summary) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/</loc>"#,
+        r#"</url>"#,
+        r#"</urlset>"#
+    );
+    assert_eq!(actual, expected);
+    assert_eq!(summary.number_of_entries(), 1);
+    assert_eq!(summary.byte_length(), expected.len());
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_reset_after_end() -> anyhow::Result<()> {
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/1")?;
+    writer.end()?;
+    let (first, first_summary) = writer.reset(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/2")?;
+    let (second, second_summary) = writer.finish()?;
+    let first = String::from_utf8(first.into_inner())?;
+    let second = String::from_utf8(second.into_inner())?;
+    assert_eq!(first, second.replace("/2<", "/1<"));
+    assert_eq!(first.matches("</urlset>").count(), 1);
+    assert_eq!(first_summary, second_summary);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_builder_trailing_newline() -> anyhow::Result<()> {
+    let mut writer = SitemapWriterBuilder::new()
+        .trailing_newline(true)
+        .start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/")?;
+    let (inner, summary) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/</loc>"#,
+        r#"</url>"#,
+        "</urlset>\n"
+    );
+    assert_eq!(actual, expected);
+    assert_eq!(summary.byte_length(), expected.len());
+
+    let mut writer = SitemapWriterBuilder::new()
+        .indent(true)
+        .trailing_newline(true)
+        .start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/")?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    assert!(actual.ends_with("</url>\n</urlset>\n"));
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_builder_host_policy() -> anyhow::Result<()> {
+    let policy = HostPolicy::new()
+        .allow_host("www.example.com")
+        .deny_host("*.staging.example.com");
+    let mut writer = SitemapWriterBuilder::new()
+        .host_policy(policy.clone())
+        .start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/")?;
+    assert!(writer.write("http://www.staging.example.com/").is_err());
+    assert!(writer.begin_url("http://example.com/").is_err());
+
+    let mut writer = SitemapWriterBuilder::new()
+        .host_policy(policy.on_violation(HostPolicyAction::Skip))
+        .start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/")?;
+    writer.write("http://www.staging.example.com/")?;
+    let mut url = writer.begin_url("http://example.com/")?;
+    url.lastmod("2005-01-01")?;
+    assert!(url.lastmod("2005-01-01").is_err());
+    url.end()?;
+    let (inner, summary) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/</loc>"#,
+        r#"</url>"#,
+        r#"</urlset>"#
+    );
+    assert_eq!(actual, expected);
+    assert_eq!(summary.number_of_entries(), 1);
+    Ok(())
+}
+
+#[cfg(feature = "unicode-normalization")]
+#[test]
+fn test_sitemap_writer_builder_normalize_locs() -> anyhow::Result<()> {
+    let mut writer = SitemapWriterBuilder::new()
+        .normalize_locs(true)
+        .start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/cafe\u{301}")?;
+    writer
+        .begin_url("http://www.example.com/caf\u{e9}")?
+        .end()?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<url>"#,
+        "<loc>http://www.example.com/caf\u{e9}</loc>",
+        r#"</url>"#,
+        r#"<url>"#,
+        "<loc>http://www.example.com/caf\u{e9}</loc>",
+        r#"</url>"#,
+        r#"</urlset>"#
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[cfg(feature = "url")]
+#[test]
+fn test_sitemap_writer_builder_canonicalize_locs() -> anyhow::Result<()> {
+    let mut url = ::url::Url::parse("http://www.example.com/search")?;
+    url.query_pairs_mut().append_pair("q", "a b");
+
+    let mut writer = SitemapWriterBuilder::new()
+        .canonicalize_locs(true)
+        .start(Cursor::new(Vec::new()))?;
+    writer.write(Url::loc(url)?)?;
+    writer.write("http://www.example.com/search?q=a%20b")?;
+    writer.begin_url("HTTP://WWW.EXAMPLE.COM/%7euser")?.end()?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/search?q=a%20b</loc>"#,
+        r#"</url>"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/search?q=a%20b</loc>"#,
+        r#"</url>"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/~user</loc>"#,
+        r#"</url>"#,
+        r#"</urlset>"#
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_builder_loc_prefix() -> anyhow::Result<()> {
+    let locs = [
+        "http://www.example.com/products/?a=1&b=2",
+        "http://www.example.com/products/",
+        "http://www.example.com/about?c=3&d=4",
+    ];
+    let mut expected = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    let mut writer = SitemapWriterBuilder::new()
+        .loc_prefix("http://www.example.com/products/")
+        .start(Cursor::new(Vec::new()))?;
+    for loc in locs {
+        expected.write(loc)?;
+        writer.write(loc)?;
+    }
+    writer.begin_url(locs[0])?.end()?;
+    expected.begin_url(locs[0])?.end()?;
+    let (expected, expected_summary) = expected.finish()?;
+    let (actual, actual_summary) = writer.finish()?;
+    assert_eq!(
+        String::from_utf8(actual.into_inner())?,
+        String::from_utf8(expected.into_inner())?
+    );
+    assert_eq!(actual_summary, expected_summary);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_builder_provenance_comments() -> anyhow::Result<()> {
+    let url = || -> anyhow::Result<Url> {
+        Ok(Url::loc("http://www.example.com/")?.provenance("pipeline-a --line 42"))
+    };
+
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    writer.write(url()?)?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    assert!(!actual.contains("<!--"));
+
+    let mut writer = SitemapWriterBuilder::new()
+        .indent(true)
+        .provenance_comments(true)
+        .start(Cursor::new(Vec::new()))?;
+    writer.write(url()?)?;
+    writer.write("http://www.example.com/about")?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <!-- pipeline-a - -line 42 -->
+  <url>
+    <loc>http://www.example.com/</loc>
+  </url>
+  <url>
+    <loc>http://www.example.com/about</loc>
+  </url>
+</urlset>"#;
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_write_url_ref() -> anyhow::Result<()> {
+    let url = Url::loc("http://www.example.com/")?
+        .lastmod("2005-01-01")?
+        .priority("0.8")?;
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    writer.write(&url)?;
+    writer.write(&url)?;
+    writer.write(url)?;
+    let (inner, summary) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let url = concat!(
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/</loc>"#,
+        r#"<lastmod>2005-01-01</lastmod>"#,
+        r#"<priority>0.8</priority>"#,
+        r#"</url>"#,
+    );
+    let expected = [
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        url,
+        url,
+        url,
+        r#"</urlset>"#,
+    ]
+    .concat();
+    assert_eq!(actual, expected);
+    assert_eq!(summary.number_of_entries(), 3);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_write_url_mutated() -> anyhow::Result<()> {
+    let mut url = Url::loc("http://www.example.com/")?
+        .lastmod("2005-01-01")?
+        .changefreq("monthly")?
+        .priority("0.8")?;
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    writer.write(&url)?;
+    url.set_loc("http://www.example.com/about")?
+        .set_lastmod("2005-01-02")?
+        .clear_changefreq()
+        .clear_priority();
+    assert!(url.set_priority("1.1").is_err());
+    writer.write(&url)?;
+    url.clear_lastmod().set_changefreq(Changefreq::Daily)?;
+    writer.write(&url)?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/</loc>"#,
+        r#"<lastmod>2005-01-01</lastmod>"#,
+        r#"<changefreq>monthly</changefreq>"#,
+        r#"<priority>0.8</priority>"#,
+        r#"</url>"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/about</loc>"#,
+        r#"<lastmod>2005-01-02</lastmod>"#,
+        r#"</url>"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/about</loc>"#,
+        r#"<changefreq>daily</changefreq>"#,
+        r#"</url>"#,
+        r#"</urlset>"#
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_write_url_infallible() -> anyhow::Result<()> {
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    writer.write(
+        Url::new(Loc::try_from("http://www.example.com/")?)
+            .with_lastmod(Lastmod::try_from("2005-01-01")?)
+            .with_changefreq(Changefreq::Monthly)
+            .with_priority(Priority::try_from("0.8")?),
+    )?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/</loc>"#,
+        r#"<lastmod>2005-01-01</lastmod>"#,
+        r#"<changefreq>monthly</changefreq>"#,
+        r#"<priority>0.8</priority>"#,
+        r#"</url>"#,
+        r#"</urlset>"#
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_limits_apply_to_canonical_bytes() -> anyhow::Result<()> {
+    struct HtmlPre<W: std::io::Write>(W);
+
+    impl<W: std::io::Write> HtmlPre<W> {
+        fn start(mut inner: W) -> std::io::Result<Self> {
+            inner.write_all(b"<pre>")?;
+            Ok(Self(inner))
+        }
+
+        fn finish(mut self) -> std::io::Result<W> {
+            self.0.write_all(b"</pre>")?;
+            Ok(self.0)
+        }
+    }
+
+    impl<W: std::io::Write> std::io::Write for HtmlPre<W> {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            for b in buf {
+                match b {
+                    b'&' => self.0.write_all(b"&amp;")?,
+                    b'<' => self.0.write_all(b"&lt;")?,
+                    b'>' => self.0.write_all(b"&gt;")?,
+                    _ => self.0.write_all(&[*b])?,
+                }
+            }
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.0.flush()
+        }
+    }
+
+    let mut writer = SitemapWriter::start(HtmlPre::start(Cursor::new(Vec::new()))?)?;
+    writer.write("http://www.example.com/?a=1&b=2")?;
+    let (inner, summary) = writer.finish()?;
+    let actual = String::from_utf8(inner.finish()?.into_inner())?;
+    let canonical = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/?a=1&amp;b=2</loc>"#,
+        r#"</url>"#,
+        r#"</urlset>"#
+    );
+    let expected = format!(
+        "<pre>{}</pre>",
+        canonical
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    );
+    assert_eq!(actual, expected);
+    assert_eq!(summary.byte_length(), canonical.len());
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_builder_self_loc() -> anyhow::Result<()> {
+    let mut writer = SitemapWriterBuilder::new()
+        .self_loc("http://www.example.com/sitemap.xml")
+        .self_loc("http://example.com/sitemap.xml")
+        .start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/")?;
+    assert!(writer.write("http://www.example.com/sitemap.xml").is_err());
+    assert!(writer.begin_url("http://example.com/sitemap.xml").is_err());
+    assert!(writer.write("HTTP://WWW.Example.COM/sitemap.xml").is_err());
+    assert!(writer.write("http://www.example.com./sitemap.xml").is_err());
+    writer.write("http://www.example.com/SITEMAP.xml")?;
+    let (_, summary) = writer.finish()?;
+    assert_eq!(summary.number_of_entries(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_builder_lastmod_skew_guard() -> anyhow::Result<()> {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    // 2005-01-01T00:00:00Z
+    let now = UNIX_EPOCH + Duration::from_secs(1_104_537_600);
+    let guard = LastmodSkewGuard::new(Duration::from_secs(86_400)).now(now);
+
+    let mut writer = SitemapWriterBuilder::new()
+        .lastmod_skew_guard(guard.clone())
+        .start(Cursor::new(Vec::new()))?;
+    assert!(writer
+        .write(Url::loc("http://www.example.com/")?.lastmod("2005-01-03")?)
+        .is_err());
+    let mut url = writer.begin_url("http://www.example.com/")?;
+    assert!(url.lastmod("2005-01-03").is_err());
+    url.end()?;
+    // an unchecked invalid value is not reported as a future lastmod
+    assert_eq!(
+        writer
+            .write(
+                Url::loc("http://www.example.com/")?
+                    .with_lastmod(Lastmod::new_unchecked("2005-02-30"))
+            )
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::InvalidLastmod)
+    );
+    let mut url = writer.begin_url("http://www.example.com/")?;
+    assert_eq!(
+        url.lastmod(Lastmod::new_unchecked("invalid"))
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::InvalidLastmod)
+    );
+    url.end()?;
+    let (_, summary) = writer.finish()?;
+    assert_eq!(summary.number_of_entries(), 2);
+
+    let mut writer = SitemapWriterBuilder::new()
+        .lastmod_skew_guard(guard.on_violation(LastmodSkewAction::Clamp))
+        .start(Cursor::new(Vec::new()))?;
+    writer.write(Url::loc("http://www.example.com/")?.lastmod("2005-01-02")?)?;
+    writer.write(Url::loc("http://www.example.com/about")?.lastmod("2005-01-03")?)?;
+    writer
+        .begin_url("http://www.example.com/contact")?
+        .lastmod("2006-01-01")?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/</loc>"#,
+        r#"<lastmod>2005-01-02</lastmod>"#,
+        r#"</url>"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/about</loc>"#,
+        r#"<lastmod>2005-01-01T00:00:00+00:00</lastmod>"#,
+        r#"</url>"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/contact</loc>"#,
+        r#"<lastmod>2005-01-01T00:00:00+00:00</lastmod>"#,
+        r#"</url>"#,
+        r#"</urlset>"#
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_write_unchecked() -> anyhow::Result<()> {
+    let lastmod = Lastmod::new_unchecked("2005-01-01");
+    let priority = Priority::new_unchecked("0.8");
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    for i in 0..2 {
+        writer.write(
+            Url::loc_unchecked(format!("http://www.example.com/{}", i))
+                .with_lastmod(lastmod.clone())
+                .with_priority(priority.clone()),
+        )?;
+    }
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/0</loc>"#,
+        r#"<lastmod>2005-01-01</lastmod>"#,
+        r#"<priority>0.8</priority>"#,
+        r#"</url>"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/1</loc>"#,
+        r#"<lastmod>2005-01-01</lastmod>"#,
+        r#"<priority>0.8</priority>"#,
+        r#"</url>"#,
+        r#"</urlset>"#
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_abort_and_close() -> anyhow::Result<()> {
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/1")?;
+    writer
+        .begin_url("http://www.example.com/2")?
+        .lastmod("2005-01-01")?;
+    let (inner, summary) = writer.abort_and_close()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/1</loc>"#,
+        r#"</url>"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/2</loc>"#,
+        r#"<lastmod>2005-01-01</lastmod>"#,
+        r#"</url>"#,
+        r#"</urlset>"#
+    );
+    assert_eq!(actual, expected);
+    assert!(summary.is_truncated());
+    assert_eq!(summary.number_of_entries(), 2);
+
+    let writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    let (_, summary) = writer.finish()?;
+    assert!(!summary.is_truncated());
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_abort_and_close_after_end_or_error() -> anyhow::Result<()> {
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/1</loc>"#,
+        r#"<changefreq>daily</changefreq>"#,
+        r#"</url>"#,
+        r#"</urlset>"#
+    );
+    let too_long = format!("http://www.example.com/{}", "a".repeat(2048));
+
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    writer.write(Url::loc("http://www.example.com/1")?.changefreq("daily")?)?;
+    writer.end()?;
+    let (inner, summary) = writer.abort_and_close()?;
+    assert_eq!(String::from_utf8(inner.into_inner())?, expected);
+    assert!(summary.is_truncated());
+    assert_eq!(summary.number_of_entries(), 1);
+
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    let mut url = writer.begin_url("http://www.example.com/1")?;
+    url.changefreq("daily")?;
+    assert!(url.lastmod("2005-01-01").is_err());
+    assert!(writer.write(too_long.as_str()).is_err());
+    let (inner, summary) = writer.abort_and_close()?;
+    assert_eq!(String::from_utf8(inner.into_inner())?, expected);
+    assert_eq!(summary.number_of_entries(), 1);
+
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    writer.write(Url::loc("http://www.example.com/1")?.changefreq("daily")?)?;
+    writer.extend([too_long.as_str()]);
+    let (inner, summary) = writer.abort_and_close()?;
+    assert_eq!(String::from_utf8(inner.into_inner())?, expected);
+    assert_eq!(summary.number_of_entries(), 1);
+    Ok(())
+}
+
+struct Note(&'static str);
+
+impl UrlExtension for Note {
+    fn namespace(&self) -> (&str, &str) {
+        ("note", "http://www.example.com/schemas/note/1.0")
+    }
+
+    fn write(&self, writer: &mut ExtensionWriter<'_>) -> Result<(), Error> {
+        writer.element("note", self.0)?;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_sitemap_writer_extension() -> anyhow::Result<()> {
+    let mut writer = SitemapWriterBuilder::new()
+        .namespace("note", "http://www.example.com/schemas/note/1.0")
+        .start(Cursor::new(Vec::new()))?;
+    let url = Url::loc("http://www.example.com/1")?
+        .priority("0.8")?
+        .extension(Note("a & b"))
+        .extension(Note("c"));
+    writer.write(&url)?;
+    writer
+        .begin_url("http://www.example.com/2")?
+        .lastmod("2005-01-01")?
+        .extension(&Note("d"))?
+        .extension(&Note("e"))?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:note="http://www.example.com/schemas/note/1.0">"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/1</loc>"#,
+        r#"<priority>0.8</priority>"#,
+        r#"<note:note>a &amp; b</note:note>"#,
+        r#"<note:note>c</note:note>"#,
+        r#"</url>"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/2</loc>"#,
+        r#"<lastmod>2005-01-01</lastmod>"#,
+        r#"<note:note>d</note:note>"#,
+        r#"<note:note>e</note:note>"#,
+        r#"</url>"#,
+        r#"</urlset>"#
+    );
+    assert_eq!(actual, expected);
+
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    let url = Url::loc("http://www.example.com/")?.extension(Note("a"));
+    assert!(writer.write(url).is_err());
+    let mut url = writer.begin_url("http://www.example.com/")?;
+    assert!(url.extension(&Note("a")).is_err());
+    url.end()?;
+
+    let mut writer = SitemapWriterBuilder::new()
+        .namespace("note", "http://www.example.com/schemas/note/1.0")
+        .start(Cursor::new(Vec::new()))?;
+    let mut url = writer.begin_url("http://www.example.com/")?;
+    url.extension(&Note("a"))?;
+    assert!(url.priority("0.8").is_err());
+
+    for prefix in ["", "1a", "a:b", "xmlns"] {
+        let builder = SitemapWriterBuilder::new().namespace(prefix, "http://www.example.com/");
+        assert!(builder.start(Cursor::new(Vec::new())).is_err());
+    }
+    let builder = SitemapWriterBuilder::new()
+        .namespace("a", "http://www.example.com/1")
+        .namespace("a", "http://www.example.com/2");
+    assert!(builder.start(Cursor::new(Vec::new())).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_images() -> anyhow::Result<()> {
+    let mut writer = SitemapWriterBuilder::new()
+        .namespace(Image::PREFIX, Image::NAMESPACE_URI)
+        .start(Cursor::new(Vec::new()))?;
+    let mut url = Url::loc("http://www.example.com/")?;
+    for i in 0..Image::MAX_NUMBER_OF_IMAGES {
+        url = url.image(
+            Image::loc(format!("http://www.example.com/{}.jpg", i).as_str())?.into_owned(),
+        )?;
+    }
+    let e = url
+        .image(Image::loc("http://www.example.com/a.jpg")?)
+        .err()
+        .unwrap();
+    assert_eq!(e.kind(), ErrorKind::MaxNumberOfImages);
+
+    let url = Url::loc("http://www.example.com/1")?
+        .image(Image::loc("http://www.example.com/b.jpg")?)?
+        .image(Image::loc("http://www.example.com/a.jpg")?)?;
+    writer.write(url)?;
+    let image = Image::loc("http://www.example.com/c.jpg")?;
+    let mut url = writer.begin_url("http://www.example.com/2")?;
+    for _ in 0..Image::MAX_NUMBER_OF_IMAGES {
+        url.image(&image)?;
+    }
+    assert_eq!(
+        url.image(&image).err().map(|e| e.kind()),
+        Some(ErrorKind::MaxNumberOfImages)
+    );
+    url.end()?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    assert!(actual.starts_with(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:image="http://www.google.com/schemas/sitemap-image/1.1">"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/1</loc>"#,
+        r#"<image:image><image:loc>http://www.example.com/b.jpg</image:loc></image:image>"#,
+        r#"<image:image><image:loc>http://www.example.com/a.jpg</image:loc></image:image>"#,
+        r#"</url>"#,
+    )));
+    assert_eq!(actual.matches("<image:image>").count(), 1_002);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_image_extensions() -> anyhow::Result<()> {
+    let mut writer = SitemapWriterBuilder::new()
+        .namespace(Image::PREFIX, Image::NAMESPACE_URI)
+        .start(Cursor::new(Vec::new()))?;
+    let image = Image::loc("http://www.example.com/a.jpg")?;
+
+    // `UrlWriter::extension` shares the counter with `UrlWriter::image`
+    let mut url = writer.begin_url("http://www.example.com/1")?;
+    for i in 0..Image::MAX_NUMBER_OF_IMAGES {
+        if i % 2 == 0 {
+            url.extension(&image)?;
+        } else {
+            url.image(&image)?;
+        }
+    }
+    assert_eq!(
+        url.extension(&image).err().map(|e| e.kind()),
+        Some(ErrorKind::MaxNumberOfImages)
+    );
+    assert_eq!(
+        url.image(&image).err().map(|e| e.kind()),
+        Some(ErrorKind::MaxNumberOfImages)
+    );
+    url.end()?;
+
+    // `Url::extension` shares the counter with `Url::image`
+    let mut url = Url::loc("http://www.example.com/2")?;
+    for _ in 0..Image::MAX_NUMBER_OF_IMAGES {
+        url = url.extension(image.clone());
+    }
+    assert_eq!(
+        url.image(image.clone()).err().map(|e| e.kind()),
+        Some(ErrorKind::MaxNumberOfImages)
+    );
+
+    // `SitemapWriter::write` rejects a `Url` with too many image extensions
+    let mut url = Url::loc("http://www.example.com/3")?;
+    for _ in 0..=Image::MAX_NUMBER_OF_IMAGES {
+        url = url.extension(image.clone());
+    }
+    assert_eq!(
+        writer.write(url).err().map(|e| e.kind()),
+        Some(ErrorKind::MaxNumberOfImages)
+    );
+
+    let (inner, summary) = writer.finish()?;
+    assert_eq!(summary.number_of_entries(), 1);
+    let actual = String::from_utf8(inner.into_inner())?;
+    assert_eq!(
+        actual.matches("<image:image>").count(),
+        Image::MAX_NUMBER_OF_IMAGES
+    );
+    assert!(!actual.contains("http://www.example.com/3"));
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_videos() -> anyhow::Result<()> {
+    let video = |i: usize| -> anyhow::Result<Video<'static>> {
+        let thumbnail_loc = format!("http://www.example.com/{}.jpg", i);
+        let video = Video::new(
+            thumbnail_loc.as_str(),
+            format!("Title {}", i),
+            format!("Description {}", i),
+        )?;
+        Ok(video.into_owned())
+    };
+    let mut writer = SitemapWriterBuilder::new()
+        .namespace(Video::PREFIX, Video::NAMESPACE_URI)
+        .start(Cursor::new(Vec::new()))?;
+    let url = Url::loc("http://www.example.com/")?
+        .video(video(1)?.content_loc("http://www.example.com/1.mp4")?)?
+        .video(video(2)?.player_loc("http://www.example.com/player?v=2")?)?;
+    let e = Url::loc("http://www.example.com/")?
+        .video(video(1)?.content_loc("http://www.example.com/1.mp4")?)?
+        .video(video(2)?)
+        .err()
+        .unwrap();
+    assert_eq!(e.kind(), ErrorKind::InvalidVideo);
+    writer.write(url)?;
+    // a video added via `Url::extension` is validated when writing
+    assert_eq!(
+        writer
+            .write(Url::loc("http://www.example.com/1")?.extension(video(5)?))
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::InvalidVideo)
+    );
+    let mut url = writer.begin_url("http://www.example.com/1")?;
+    assert_eq!(
+        url.extension(&video(5)?).err().map(|e| e.kind()),
+        Some(ErrorKind::InvalidVideo)
+    );
+
+    let mut url = writer.begin_url("http://www.example.com/2")?;
+    assert!(url.video(&video(3)?).is_err());
+    url.video(&video(3)?.content_loc("http://www.example.com/3.mp4")?)?
+        .video(&video(4)?.content_loc("http://www.example.com/4.mp4")?)?;
+    url.end()?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:video="http://www.google.com/schemas/sitemap-video/1.1">"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/</loc>"#,
+        r#"<video:video>"#,
+        r#"<video:thumbnail_loc>http://www.example.com/1.jpg</video:thumbnail_loc>"#,
+        r#"<video:title>Title 1</video:title>"#,
+        r#"<video:description>Description 1</video:description>"#,
+        r#"<video:content_loc>http://www.example.com/1.mp4</video:content_loc>"#,
+        r#"</video:video>"#,
+        r#"<video:video>"#,
+        r#"<video:thumbnail_loc>http://www.example.com/2.jpg</video:thumbnail_loc>"#,
+        r#"<video:title>Title 2</video:title>"#,
+        r#"<video:description>Description 2</video:description>"#,
+        r#"<video:player_loc>http://www.example.com/player?v=2</video:player_loc>"#,
+        r#"</video:video>"#,
+        r#"</url>"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/2</loc>"#,
+        r#"<video:video>"#,
+        r#"<video:thumbnail_loc>http://www.example.com/3.jpg</video:thumbnail_loc>"#,
+        r#"<video:title>Title 3</video:title>"#,
+        r#"<video:description>Description 3</video:description>"#,
+        r#"<video:content_loc>http://www.example.com/3.mp4</video:content_loc>"#,
+        r#"</video:video>"#,
+        r#"<video:video>"#,
+        r#"<video:thumbnail_loc>http://www.example.com/4.jpg</video:thumbnail_loc>"#,
+        r#"<video:title>Title 4</video:title>"#,
+        r#"<video:description>Description 4</video:description>"#,
+        r#"<video:content_loc>http://www.example.com/4.mp4</video:content_loc>"#,
+        r#"</video:video>"#,
+        r#"</url>"#,
+        r#"</urlset>"#
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_news() -> anyhow::Result<()> {
+    let news = || News::new("The Example Times", "en", "2008-12-23", "Title");
+    let mut writer = SitemapWriterBuilder::new()
+        .namespace(News::PREFIX, News::NAMESPACE_URI)
+        .start(Cursor::new(Vec::new()))?;
+    writer.write(
+        Url::loc("http://www.example.com/1")?.news(
+            news()?
+                .keywords(["a", "b"])?
+                .stock_tickers(["NASDAQ:A", "NASDAQ:B"])?,
+        )?,
+    )?;
+    let mut url = writer.begin_url("http://www.example.com/2")?;
+    url.news(&news()?)?;
+    assert_eq!(
+        url.news(&news()?).err().map(|e| e.kind()),
+        Some(ErrorKind::MaxNumberOfNews)
+    );
+    url.end()?;
+    assert_eq!(
+        Url::loc("http://www.example.com/3")?
+            .news(news()?)?
+            .news(news()?)
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::MaxNumberOfNews)
+    );
+    // a news added via `Url::extension` is counted when writing
+    assert_eq!(
+        writer
+            .write(
+                Url::loc("http://www.example.com/3")?
+                    .news(news()?)?
+                    .extension(news()?)
+            )
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::MaxNumberOfNews)
+    );
+    let e = news()?
+        .stock_tickers(["A", "B", "C", "D", "E", "F"])
+        .err()
+        .unwrap();
+    assert_eq!(e.kind(), ErrorKind::MaxNumberOfNewsStockTickers);
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:news="http://www.google.com/schemas/sitemap-news/0.9">"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/1</loc>"#,
+        r#"<news:news>"#,
+        r#"<news:publication><news:name>The Example Times</news:name><news:language>en</news:language></news:publication>"#,
+        r#"<news:publication_date>2008-12-23</news:publication_date>"#,
+        r#"<news:title>Title</news:title>"#,
+        r#"<news:keywords>a, b</news:keywords>"#,
+        r#"<news:stock_tickers>NASDAQ:A, NASDAQ:B</news:stock_tickers>"#,
+        r#"</news:news>"#,
+        r#"</url>"#,
+        r#"<url>"#,
+        r#"<loc>http://www.example.com/2</loc>"#,
+        r#"<news:news>"#,
+        r#"<news:publication><news:name>The Example Times</news:name><news:language>en</news:language></news:publication>"#,
+        r#"<news:publication_date>2008-12-23</news:publication_date>"#,
+        r#"<news:title>Title</news:title>"#,
+        r#"</news:news>"#,
+        r#"</url>"#,
+        r#"</urlset>"#
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_alternates() -> anyhow::Result<()> {
+    let en = "http://www.example.com/en/";
+    let de = "http://www.example.com/de/";
+    let urls = [en, de]
+        .into_iter()
+        .map(|loc| -> anyhow::Result<Url> {
+            Ok(Url::loc(loc)?
+                .alternate(Alternate::new("en", en)?)
+                .alternate(Alternate::new("de", de)?))
+        })
+        .collect::<anyhow::Result<Vec<Url>>>()?;
+    let mut cluster = AlternateCluster::new();
+    for url in urls.iter() {
+        cluster.add(url);
+    }
+    assert!(cluster.validate().is_empty());
+
+    assert_eq!(
+        SitemapWriterBuilder::new()
+            .start(Cursor::new(Vec::new()))?
+            .write(&urls[0])
+            .err()
+            .unwrap()
+            .kind(),
+        ErrorKind::InvalidExtension
+    );
+
+    let mut writer = SitemapWriterBuilder::new()
+        .indent(true)
+        .namespace(Alternate::PREFIX, Alternate::NAMESPACE_URI)
+        .start(Cursor::new(Vec::new()))?;
+    writer.write(&urls[0])?;
+    let mut url = writer.begin_url(de)?;
+    url.priority("0.5")?
+        .alternate(&Alternate::new("en", en)?)?
+        .alternate(&Alternate::new("de", de)?)?;
+    url.end()?;
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = [
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:xhtml="http://www.w3.org/1999/xhtml">"#,
+        r#"  <url>"#,
+        r#"    <loc>http://www.example.com/en/</loc>"#,
+        r#"    <xhtml:link rel="alternate" hreflang="en" href="http://www.example.com/en/"/>"#,
+        r#"    <xhtml:link rel="alternate" hreflang="de" href="http://www.example.com/de/"/>"#,
+        r#"  </url>"#,
+        r#"  <url>"#,
+        r#"    <loc>http://www.example.com/de/</loc>"#,
+        r#"    <priority>0.5</priority>"#,
+        r#"    <xhtml:link rel="alternate" hreflang="en" href="http://www.example.com/en/"/>"#,
+        r#"    <xhtml:link rel="alternate" hreflang="de" href="http://www.example.com/de/"/>"#,
+        r#"  </url>"#,
+        r#"</urlset>"#,
+    ]
+    .join("\n");
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_extend() -> anyhow::Result<()> {
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    writer.extend(["http://www.example.com/1"]);
+    writer.extend(vec![Url::loc("http://www.example.com/2")?.priority("0.5")?]);
+    let url = Url::loc("http://www.example.com/3")?;
+    writer.extend(std::iter::once(&url));
+    writer.deferred_result()?;
+
+    let (_, summary) = writer.reset(Cursor::new(Vec::new()))?;
+    assert_eq!(summary.number_of_entries(), 3);
+
+    let too_long = format!("http://www.example.com/{}", "a".repeat(2048));
+    writer.extend(["http://www.example.com/4", too_long.as_str()]);
+    assert_eq!(
+        writer.finish().err().map(|e| e.kind()),
+        Some(ErrorKind::InvalidLoc)
+    );
+
+    let mut writer = SitemapWriterBuilder::new()
+        .host_policy(HostPolicy::new().allow_host("www.example.com"))
+        .start(Cursor::new(Vec::new()))?;
+    writer.extend([
+        "http://www.example.com/1",
+        "http://example.org/2",
+        "http://www.example.com/3",
+    ]);
+    writer.extend(["http://www.example.com/4"]);
+    assert_eq!(
+        writer.deferred_result().err().map(|e| e.kind()),
+        Some(ErrorKind::HostNotAllowed)
+    );
+    writer.extend(["http://www.example.com/5"]);
+    let (inner, _) = writer.finish()?;
+    let actual = String::from_utf8(inner.into_inner())?;
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        r#"<url><loc>http://www.example.com/1</loc></url>"#,
+        r#"<url><loc>http://www.example.com/5</loc></url>"#,
+        r#"</urlset>"#
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_entry_index() -> anyhow::Result<()> {
+    let too_long = format!("http://www.example.com/{}", "a".repeat(2048));
+    let mut writer = SitemapWriter::start(Cursor::new(Vec::new()))?;
+    writer.write("http://www.example.com/0")?;
+    let e = writer.write(too_long.as_str()).unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::InvalidLoc);
+    assert_eq!(e.context().entry_index(), Some(1));
+    assert_eq!(e.to_string(), "sitemap writer: entry 1: invalid loc");
+
+    // the rejected entries are counted
+    let mut url = writer.begin_url("http://www.example.com/2")?;
+    let e = url.priority("1.1").err().unwrap();
+    assert_eq!(e.context().entry_index(), Some(2));
+    url.end()?;
+    let e = writer.begin_url(too_long.as_str()).err().unwrap();
+    assert_eq!(e.context().entry_index(), Some(3));
+
+    writer.extend(["http://www.example.com/4", too_long.as_str()]);
+    let e = writer.deferred_result().unwrap_err();
+    assert_eq!(e.context().entry_index(), Some(5));
+
+    // the index restarts with the file
+    let _ = writer.reset(Cursor::new(Vec::new()))?;
+    let e = writer.write(too_long.as_str()).unwrap_err();
+    assert_eq!(e.context().entry_index(), Some(0));
+    let (_, summary) = writer.finish()?;
+    assert_eq!(summary.number_of_entries(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_writer_builder_google_news_profile() -> anyhow::Result<()> {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    // 2008-12-24T00:00:00Z
+    let now = UNIX_EPOCH + Duration::from_secs(1_230_076_800);
+    let news = |date| News::new("The Example Times", "en", date, "Title");
+    let mut writer = SitemapWriterBuilder::new()
+        .namespace(News::PREFIX, News::NAMESPACE_URI)
+        .google_news_profile(GoogleNewsProfile::new().now(now))
+        .start(Cursor::new(Vec::new()))?;
+    writer.write(Url::loc("http://www.example.com/1")?.news(news("2008-12-23")?)?)?;
+    let url = Url::loc("http://www.example.com/2")?.news(news("2008-12-21")?)?;
+    assert_eq!(
+        writer.write(&url).err().map(|e| e.kind()),
+        Some(ErrorKind::StaleNewsPublicationDate)
+    );
+    assert_eq!(
+        writer.write(url).err().map(|e| e.kind()),
+        Some(ErrorKind::StaleNewsPublicationDate)
+    );
+    let mut url = writer.begin_url("http://www.example.com/3")?;
+    assert_eq!(
+        url.news(&news("2008-12-21")?).err().map(|e| e.kind()),
+        Some(ErrorKind::StaleNewsPublicationDate)
+    );
+    url.news(&news("2008-12-22")?)?;
+    url.end()?;
+    for i in 3..=GoogleNewsProfile::MAX_NUMBER_OF_URLS {
+        writer.write(format!("http://www.example.com/{}", i + 2).as_str())?;
+    }
+    assert_eq!(
+        writer
+            .write("http://www.example.com/")
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::MaxNumberOfNewsUrls)
+    );
+    let (_, summary) = writer.finish()?;
+    assert_eq!(
+        summary.number_of_entries(),
+        GoogleNewsProfile::MAX_NUMBER_OF_URLS
+    );
+    Ok(())
+}
@@ -0,0 +1,315 @@
+use quick_xml::{events::Event, Reader};
+use sitemap_xml_writer::{
+    Alternate, Changefreq, Image, News, Sitemap, SitemapIndexWriter, SitemapWriterBuilder, Url,
+    Video,
+};
+
+use std::io::Cursor;
+
+/// A small deterministic PRNG (xorshift64*) so that failures are reproducible.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    fn loc(&mut self) -> String {
+        const CHARS: &[&str] = &[
+            "a", "z", "0", "9", "/", "?", "=", "&", "'", "%20", "#", "-", "_", "~", "é", "日本",
+            "🦀",
+        ];
+        let mut s = String::from("http://www.example.com/");
+        for _ in 0..self.below(64) {
+            s.push_str(CHARS[self.below(CHARS.len())]);
+        }
+        s
+    }
+
+    /// Returns a text with the sequences that break naive XML writers (e.g.
+    /// `]]>`, `--`, quotes and control characters allowed in XML 1.0).
+    fn text(&mut self) -> String {
+        const CHARS: &[&str] = &[
+            "a", " ", "]]>", "--", "<!--", "\"", "'", "<", ">", "&", "&amp;", "\t", "\n", "\u{7f}",
+            "\u{85}", "\u{fffd}", "é", "🦀",
+        ];
+        let mut s = String::from("x");
+        for _ in 0..self.below(16) {
+            s.push_str(CHARS[self.below(CHARS.len())]);
+        }
+        s
+    }
+
+    fn lastmod(&mut self) -> String {
+        let year = 1970 + self.below(100);
+        let month = 1 + self.below(12);
+        let day = 1 + self.below(28);
+        match self.below(3) {
+            0 => format!("{:04}-{:02}-{:02}", year, month, day),
+            1 => format!(
+                "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+                year,
+                month,
+                day,
+                self.below(24),
+                self.below(60),
+                self.below(60)
+            ),
+            _ => format!(
+                "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}+09:00",
+                year,
+                month,
+                day,
+                self.below(24),
+                self.below(60),
+                self.below(60),
+                self.below(1_000)
+            ),
+        }
+    }
+}
+
+type Entry = Vec<(String, String)>;
+
+/// Parses `xml` with an independent XML parser and returns the root element
+/// name and the `(name, text)` pairs of the leaf elements of each entry in
+/// document order. The text of an empty element is its attributes (e.g.
+/// `rel=alternate hreflang=en href=http://www.example.com/`).
+fn parse(xml: &str) -> anyhow::Result<(String, Vec<Entry>)> {
+    let mut reader = Reader::from_str(xml);
+    let mut root = None;
+    let mut entries = vec![];
+    let mut depth = 0_usize;
+    let mut leaf = false;
+    let mut text = String::new();
+    loop {
+        match reader.read_event()? {
+            Event::Decl(_) => {}
+            Event::Start(e) => {
+                depth += 1;
+                let name = e.name().as_ref().to_string();
+                match depth {
+                    1 => root = Some(name),
+                    2 => entries.push(vec![]),
+                    _ => {}
+                }
+                leaf = depth >= 3;
+                text.clear();
+            }
+            Event::Empty(e) => {
+                anyhow::ensure!(depth >= 2, "unexpected empty element");
+                let name = e.name().as_ref().to_string();
+                let mut attributes = vec![];
+                for attribute in e.attributes() {
+                    let attribute = attribute?;
+                    attributes.push(format!(
+                        "{}={}",
+                        attribute.key.as_ref(),
+                        attribute.normalized_value(quick_xml::XmlVersion::Explicit1_0)?
+                    ));
+                }
+                let entry = entries.last_mut().expect("entry");
+                entry.push((name, attributes.join(" ")));
+                leaf = false;
+            }
+            Event::End(e) => {
+                if leaf {
+                    let name = e.name().as_ref().to_string();
+                    let content = quick_xml::escape::unescape(&text)?.into_owned();
+                    entries.last_mut().expect("entry").push((name, content));
+                }
+                leaf = false;
+                depth -= 1;
+            }
+            Event::Text(e) => {
+                let raw = e.into_inner();
+                anyhow::ensure!(leaf || raw.trim().is_empty(), "unexpected text");
+                text.push_str(&raw);
+            }
+            Event::GeneralRef(e) => {
+                anyhow::ensure!(leaf, "unexpected reference");
+                text.push('&');
+                text.push_str(&e.into_inner());
+                text.push(';');
+            }
+            Event::Eof => break,
+            e => anyhow::bail!("unexpected event {:?}", e),
+        }
+    }
+    anyhow::ensure!(depth == 0, "unclosed elements");
+    Ok((root.expect("root"), entries))
+}
+
+fn to_entries(expected: Vec<Vec<(&str, String)>>) -> Vec<Entry> {
+    expected
+        .into_iter()
+        .map(|fields| {
+            fields
+                .into_iter()
+                .map(|(name, content)| (name.to_string(), content))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_sitemap_writer_output_is_well_formed() -> anyhow::Result<()> {
+    let mut rng = Rng(0x5eed_1234_abcd_ef01);
+    for pretty in [false, true] {
+        for _ in 0..20 {
+            let mut expected = vec![];
+            let mut writer = SitemapWriterBuilder::new()
+                .indent(pretty)
+                .namespace(Alternate::PREFIX, Alternate::NAMESPACE_URI)
+                .namespace(Image::PREFIX, Image::NAMESPACE_URI)
+                .namespace(News::PREFIX, News::NAMESPACE_URI)
+                .namespace(Video::PREFIX, Video::NAMESPACE_URI)
+                .start(Cursor::new(Vec::new()))?;
+            for _ in 0..rng.below(50) {
+                let mut fields = vec![("loc", rng.loc())];
+                if rng.below(2) == 0 {
+                    fields.push(("lastmod", rng.lastmod()));
+                }
+                if rng.below(2) == 0 {
+                    let changefreq = *rng.choose(&[
+                        Changefreq::Always,
+                        Changefreq::Hourly,
+                        Changefreq::Daily,
+                        Changefreq::Weekly,
+                        Changefreq::Monthly,
+                        Changefreq::Yearly,
+                        Changefreq::Never,
+                    ]);
+                    fields.push(("changefreq", changefreq.as_ref().to_string()));
+                }
+                if rng.below(2) == 0 {
+                    fields.push(("priority", (rng.below(11) as f64 / 10.0).to_string()));
+                }
+
+                let mut url = Url::loc(fields[0].1.as_str())?;
+                for (name, content) in &fields[1..] {
+                    url = match *name {
+                        "lastmod" => url.lastmod(content.as_str())?,
+                        "changefreq" => url.changefreq(content.as_str())?,
+                        _ => url.priority(content.as_str())?,
+                    };
+                }
+
+                // alternates are written before the other extensions
+                let mut extension_fields = vec![];
+                for _ in 0..rng.below(3) {
+                    let hreflang = *rng.choose(&["en", "de-ch", "x-default"]);
+                    let href = rng.loc();
+                    url = url.alternate(Alternate::new(hreflang, href.as_str())?.into_owned());
+                    extension_fields.push((
+                        "xhtml:link",
+                        format!("rel=alternate hreflang={} href={}", hreflang, href),
+                    ));
+                }
+                for _ in 0..rng.below(3) {
+                    let (loc, caption, title) = (rng.loc(), rng.text(), rng.text());
+                    assert!(Image::loc(loc.as_str())?
+                        .caption(format!("{}\u{1}", caption))
+                        .is_err());
+                    let image = Image::loc(loc.as_str())?
+                        .caption(caption.as_str())?
+                        .title(title.as_str())?;
+                    url = url.image(image.into_owned())?;
+                    extension_fields.extend([
+                        ("image:loc", loc),
+                        ("image:caption", caption),
+                        ("image:title", title),
+                    ]);
+                }
+                if rng.below(2) == 0 {
+                    let (name, publication_date, title) = (rng.text(), rng.lastmod(), rng.text());
+                    assert!(News::new(format!("{}\u{1}", name), "en", "2008-12-23", "t").is_err());
+                    let news = News::new(
+                        name.as_str(),
+                        "en",
+                        publication_date.as_str(),
+                        title.as_str(),
+                    )?;
+                    url = url.news(news.into_owned())?;
+                    extension_fields.extend([
+                        ("news:name", name),
+                        ("news:language", "en".to_string()),
+                        ("news:publication_date", publication_date),
+                        ("news:title", title),
+                    ]);
+                }
+                for _ in 0..rng.below(2) {
+                    let (thumbnail_loc, title, description, content_loc) =
+                        (rng.loc(), rng.text(), rng.text(), rng.loc());
+                    assert!(Video::new(thumbnail_loc.as_str(), title.as_str(), "\u{1}").is_err());
+                    let video =
+                        Video::new(thumbnail_loc.as_str(), title.as_str(), description.as_str())?
+                            .content_loc(content_loc.as_str())?;
+                    url = url.video(video.into_owned())?;
+                    extension_fields.extend([
+                        ("video:thumbnail_loc", thumbnail_loc),
+                        ("video:title", title),
+                        ("video:description", description),
+                        ("video:content_loc", content_loc),
+                    ]);
+                }
+                writer.write(url)?;
+                fields.extend(extension_fields);
+                expected.push(fields);
+            }
+            let (inner, _) = writer.finish()?;
+
+            let xml = String::from_utf8(inner.into_inner())?;
+            let (root, actual) = parse(&xml)?;
+            assert_eq!(root, "urlset");
+            assert_eq!(actual, to_entries(expected));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_index_writer_output_is_well_formed() -> anyhow::Result<()> {
+    let mut rng = Rng(0x0123_4567_89ab_cdef);
+    for pretty in [false, true] {
+        for _ in 0..20 {
+            let mut expected = vec![];
+            let mut writer = if pretty {
+                SitemapIndexWriter::start_with_indent(Cursor::new(Vec::new()))?
+            } else {
+                SitemapIndexWriter::start(Cursor::new(Vec::new()))?
+            };
+            for _ in 0..rng.below(50) {
+                let mut fields = vec![("loc", rng.loc())];
+                if rng.below(2) == 0 {
+                    fields.push(("lastmod", rng.lastmod()));
+                }
+
+                let mut sitemap = Sitemap::loc(fields[0].1.as_str())?;
+                if let Some((_, lastmod)) = fields.get(1) {
+                    sitemap = sitemap.lastmod(lastmod.as_str())?;
+                }
+                writer.write(sitemap)?;
+                expected.push(fields);
+            }
+            let (inner, _) = writer.finish()?;
+
+            let xml = String::from_utf8(inner.into_inner())?;
+            let (root, actual) = parse(&xml)?;
+            assert_eq!(root, "sitemapindex");
+            assert_eq!(actual, to_entries(expected));
+        }
+    }
+    Ok(())
+}